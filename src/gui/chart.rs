@@ -1,22 +1,97 @@
 use crate::model::chart::PriceChart;
 use crate::gui::update::Message;
-use iced::{mouse, Color, Point, Rectangle, Renderer, Theme, Size, Pixels, event::Status};
+use iced::{alignment, mouse, Color, Point, Rectangle, Renderer, Theme, Size, Pixels, event::Status};
 use iced::widget::canvas;
 use iced::widget::canvas::{Frame, Path, Stroke, Text, Event};
 
 pub const CHART_WIDTH: f32 = 1000f32;
 pub const CHART_HEIGHT: f32 = 400f32;
 
-const BOUNDS_OFFSET: f32 = 50.0;
-const INNER_OFFSET: f32 = 15.0;
+pub(crate) const BOUNDS_OFFSET: f32 = 50.0;
+pub(crate) const INNER_OFFSET: f32 = 15.0;
+pub(crate) const LABEL_SIZE: f32 = 12.0;
+const LABEL_GAP: f32 = 5.0;
+const LEGEND_SPACING: f32 = 60.0;
 const POINT_RADIUS_MAX: f32 = 6.0;
 const POINT_RADIUS_MIN: f32 = 2.0;
 const VERTICAL_SCALING: f32 = 1f32 / 8f32; // represents what % of the canvas we should put the highest price of the PriceChart
 
 const COLOR_MAX_VAL: f32 = 255f32;
-const COLOR_WHITE: Color = Color{r: 246f32 / COLOR_MAX_VAL, g: 232f32 / COLOR_MAX_VAL, b: 234f32 / COLOR_MAX_VAL, a:1f32};
-const COLOR_BLUE: Color = Color{r: 132f32 / COLOR_MAX_VAL, g: 220f32 / COLOR_MAX_VAL, b: 207f32 / COLOR_MAX_VAL, a:1f32};
-const COLOR_RED: Color = Color{r: 239f32 / COLOR_MAX_VAL, g: 98f32 / COLOR_MAX_VAL, b: 108f32 / COLOR_MAX_VAL, a:1f32};
+pub(crate) const COLOR_WHITE: Color = Color{r: 246f32 / COLOR_MAX_VAL, g: 232f32 / COLOR_MAX_VAL, b: 234f32 / COLOR_MAX_VAL, a:1f32};
+pub(crate) const COLOR_BLUE: Color = Color{r: 132f32 / COLOR_MAX_VAL, g: 220f32 / COLOR_MAX_VAL, b: 207f32 / COLOR_MAX_VAL, a:1f32};
+pub(crate) const COLOR_YELLOW: Color = Color{r: 247f32 / COLOR_MAX_VAL, g: 208f32 / COLOR_MAX_VAL, b: 96f32 / COLOR_MAX_VAL, a:1f32};
+pub(crate) const COLOR_RED: Color = Color{r: 239f32 / COLOR_MAX_VAL, g: 98f32 / COLOR_MAX_VAL, b: 108f32 / COLOR_MAX_VAL, a:1f32};
+
+/// Drawing area of the smaller canvases: inset by `INNER_OFFSET` with room for the value labels on the
+/// right and the range labels below, like the price chart.
+pub(crate) struct PlotArea {
+    pub left: f32,
+    pub top: f32,
+    pub width: f32,
+    pub height: f32,
+    canvas_height: f32,
+}
+
+impl PlotArea {
+    pub fn new(bounds: Rectangle) -> PlotArea {
+        PlotArea {
+            left: INNER_OFFSET,
+            top: INNER_OFFSET,
+            width: bounds.width - INNER_OFFSET - BOUNDS_OFFSET,
+            height: bounds.height - INNER_OFFSET - BOUNDS_OFFSET,
+            canvas_height: bounds.height,
+        }
+    }
+
+    /// Rest of the canvas after leaving `left` and `top` free for labels.
+    pub fn with_margins(bounds: Rectangle, left: f32, top: f32) -> PlotArea {
+        PlotArea { left, top, width: bounds.width - left, height: bounds.height - top, canvas_height: bounds.height }
+    }
+
+    /// Horizontal position of a fraction `s` of the width.
+    pub fn x(&self, s: f64) -> f32 {
+        self.left + s as f32 * self.width
+    }
+
+    /// Vertical position of a fraction `s` of the height measured from the top.
+    pub fn y(&self, s: f64) -> f32 {
+        self.top + s as f32 * self.height
+    }
+
+    pub fn draw_horizontal_axis(&self, frame: &mut Frame, y: f32) {
+        frame.stroke(&Path::line(Point::new(self.left, y), Point::new(self.left + self.width, y)), Stroke::default().with_color(COLOR_WHITE));
+    }
+
+    /// Labels the value axis on the right: each label comes with the height it marks, the top one
+    /// starting and the bottom one ending there.
+    pub fn draw_value_labels(&self, frame: &mut Frame, top: (String, f32), bottom: Option<(String, f32)>) {
+        let x = self.left + self.width + LABEL_GAP;
+        frame.fill_text(plot_label(top.0, Point::new(x, top.1), alignment::Horizontal::Left, COLOR_WHITE));
+        if let Some((content, y)) = bottom {
+            frame.fill_text(plot_label(content, Point::new(x, y - LABEL_SIZE), alignment::Horizontal::Left, COLOR_WHITE));
+        }
+    }
+
+    /// Labels both ends of the horizontal range below the drawing area.
+    pub fn draw_range_labels(&self, frame: &mut Frame, first: String, last: String) {
+        let y = self.top + self.height + LABEL_GAP;
+        frame.fill_text(plot_label(first, Point::new(self.left, y), alignment::Horizontal::Left, COLOR_WHITE));
+        frame.fill_text(plot_label(last, Point::new(self.left + self.width, y), alignment::Horizontal::Right, COLOR_WHITE));
+    }
+
+    /// Names the series in their own colours along the bottom of the canvas.
+    pub fn draw_legend(&self, frame: &mut Frame, entries: &[(&str, Color)]) {
+        let y = self.canvas_height - LABEL_SIZE - LABEL_GAP;
+        for (i, (name, color)) in entries.iter().enumerate() {
+            let position = Point::new(self.left + i as f32 * LEGEND_SPACING, y);
+            frame.fill_text(plot_label(name.to_string(), position, alignment::Horizontal::Left, *color));
+        }
+    }
+}
+
+pub(crate) fn plot_label(content: String, position: Point, horizontal_alignment: alignment::Horizontal, color: Color) -> Text {
+    Text { content, position, color, size: Pixels(LABEL_SIZE), horizontal_alignment, ..Text::default() }
+}
 
 pub struct ChartDisplayState {
     pub points: Vec<Point>,
@@ -28,6 +103,8 @@ pub struct ChartDisplayState {
     pub _select_index: Option<usize>,
     pub x_labels: Vec<Text>,
     pub y_labels: Vec<Text>,
+    pub barrier_levels: Vec<f64>,
+    pub barrier_ys: Vec<f32>,
 }
 
 impl Default for ChartDisplayState {
@@ -42,6 +119,8 @@ impl Default for ChartDisplayState {
             _select_index: None, 
             x_labels: vec![Text::default(), Text::default()],
             y_labels: vec![Text::default(), Text::default()],
+            barrier_levels: Vec::new(),
+            barrier_ys: Vec::new(),
         };
         result.update_display_points(&PriceChart::default(), Rectangle::new(Point::new(0f32, 0f32), Size::new(CHART_WIDTH, CHART_HEIGHT)));
         result
@@ -86,6 +165,12 @@ impl canvas::Program<Message> for PriceChart {
             frame.fill(&circle, COLOR_RED);
         }
 
+        // Draw barrier levels
+        for y in &state.barrier_ys {
+            let barrier = Path::line(Point::new(INNER_OFFSET, *y), Point::new(bounds.width - BOUNDS_OFFSET, *y));
+            frame.stroke(&barrier, Stroke::default().with_color(COLOR_YELLOW).with_width(2.0));
+        }
+
         for label in &state.x_labels {
             frame.fill_text(label.clone());
         }
@@ -130,30 +215,27 @@ impl canvas::Program<Message> for PriceChart {
         cursor: mouse::Cursor,
     ) -> (Status, Option<Message>) {
 
-        if !state.initialized || self.refresh_chart {
+        if !state.initialized || self.refresh_chart || state.barrier_levels != self.barrier_levels {
             state.x_labels.clear();
             state.y_labels.clear();
             state.update_display_points(self, bounds);
             state.initialized = true;
         }
-        match event {
-            Event::Mouse(mouse::Event::CursorMoved { position }) => {
-                if let Some(pos) = cursor.position_in(bounds) {
-                    let hover_radius = 10.0; 
-                    state.hover_index = state.points.iter()
-                        .enumerate()
-                        .filter(|(_, point)| {
-                            let dx = (point.x - pos.x).abs();
-                            let dy = (point.y - pos.y).abs();
-                            (dx.powi(2) + dy.powi(2)).sqrt() < hover_radius
-                        })
-                        .map(|(index, _)| index)
-                        .next(); 
-                } else {
-                    state.hover_index = None; 
-                }
+        if let Event::Mouse(mouse::Event::CursorMoved { .. }) = event {
+            if let Some(pos) = cursor.position_in(bounds) {
+                let hover_radius = 10.0; 
+                state.hover_index = state.points.iter()
+                    .enumerate()
+                    .filter(|(_, point)| {
+                        let dx = (point.x - pos.x).abs();
+                        let dy = (point.y - pos.y).abs();
+                        (dx.powi(2) + dy.powi(2)).sqrt() < hover_radius
+                    })
+                    .map(|(index, _)| index)
+                    .next(); 
+            } else {
+                state.hover_index = None; 
             }
-            _ => {}
         }
     
         (Status::Captured, None)
@@ -167,8 +249,9 @@ impl ChartDisplayState {
             Size::new(bounds.width - INNER_OFFSET - BOUNDS_OFFSET, bounds.height - INNER_OFFSET - BOUNDS_OFFSET),
         );
     
-        let min_price = price_chart.min_price as f32;
-        let max_price = price_chart.max_price as f32;
+        // Keep the barriers inside the viewing range
+        let min_price = price_chart.barrier_levels.iter().fold(price_chart.min_price, |a, &b| a.min(b)) as f32;
+        let max_price = price_chart.barrier_levels.iter().fold(price_chart.max_price, |a, &b| a.max(b)) as f32;
         let diff = max_price - min_price;
         let scaled_diff = diff * VERTICAL_SCALING;
         self.min_viewing_price = min_price - scaled_diff;
@@ -183,6 +266,12 @@ impl ChartDisplayState {
             self.points.push(Point::new(x, y));
         }
 
+        self.barrier_levels = price_chart.barrier_levels.clone();
+        self.barrier_ys = self.barrier_levels
+            .iter()
+            .map(|&level| (self.max_viewing_price - level as f32) / self.price_diff * (drawing_bounds.height - BOUNDS_OFFSET))
+            .collect();
+
         // Draw X-axis labels (dates)
         let num_x_labels = 5;
         let date_range = price_chart.data.last().unwrap().date  - price_chart.data[0].date;
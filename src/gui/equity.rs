@@ -0,0 +1,69 @@
+use crate::gui::chart::{PlotArea, COLOR_BLUE, COLOR_RED, INNER_OFFSET};
+use crate::gui::update::Message;
+use crate::model::performance::BacktestReport;
+use iced::{mouse, Point, Rectangle, Renderer, Theme};
+use iced::widget::canvas;
+use iced::widget::canvas::{Frame, Path, Stroke};
+
+pub const EQUITY_WIDTH: f32 = 600f32;
+pub const EQUITY_HEIGHT: f32 = 400f32;
+
+const EQUITY_SHARE: f32 = 0.7; // of the drawing height, the rest showing the drawdown below it
+
+impl canvas::Program<Message> for BacktestReport {
+    type State = ();
+
+    fn draw(
+            &self,
+            _state: &Self::State,
+            renderer: &Renderer,
+            _theme: &Theme,
+            bounds: Rectangle,
+            _cursor: mouse::Cursor,
+        ) -> Vec<canvas::Geometry<Renderer>> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let curve = &self.result.equity_curve;
+        if curve.len() < 2 {
+            return vec![frame.into_geometry()];
+        }
+        let min_equity = curve.iter().fold(f64::INFINITY, |a, p| a.min(p.equity));
+        let max_equity = curve.iter().fold(f64::NEG_INFINITY, |a, p| a.max(p.equity));
+        let equity_range = if max_equity > min_equity { max_equity - min_equity } else { 1.0 };
+        let max_drawdown = self.metrics.max_drawdown.max(f64::EPSILON);
+
+        let area = PlotArea::new(bounds);
+        let equity_height = area.height * EQUITY_SHARE;
+        let drawdown_top = INNER_OFFSET + equity_height + INNER_OFFSET;
+        let drawdown_height = area.height - equity_height - INNER_OFFSET;
+        let x = |i: usize| area.x(i as f64 / (curve.len() - 1) as f64);
+        let y = |equity: f64| INNER_OFFSET + ((max_equity - equity) / equity_range) as f32 * equity_height;
+        let dd_y = |drawdown: f64| drawdown_top + (drawdown / max_drawdown) as f32 * drawdown_height;
+
+        // Axes of both panels
+        area.draw_horizontal_axis(&mut frame, INNER_OFFSET + equity_height);
+        area.draw_horizontal_axis(&mut frame, drawdown_top);
+
+        let equity_line = Path::new(|p| {
+            p.move_to(Point::new(x(0), y(curve[0].equity)));
+            for (i, point) in curve.iter().enumerate().skip(1) {
+                p.line_to(Point::new(x(i), y(point.equity)));
+            }
+        });
+        frame.stroke(&equity_line, Stroke::default().with_color(COLOR_BLUE).with_width(2.0));
+
+        let drawdown_area = Path::new(|p| {
+            p.move_to(Point::new(x(0), drawdown_top));
+            for (i, drawdown) in self.drawdowns.iter().enumerate() {
+                p.line_to(Point::new(x(i), dd_y(*drawdown)));
+            }
+            p.line_to(Point::new(x(self.drawdowns.len() - 1), drawdown_top));
+            p.close();
+        });
+        frame.fill(&drawdown_area, COLOR_RED);
+
+        area.draw_value_labels(&mut frame, (format!("{:.2}", max_equity), INNER_OFFSET), Some((format!("{:.2}", min_equity), INNER_OFFSET + equity_height)));
+        area.draw_value_labels(&mut frame, (String::from("0%"), drawdown_top), Some((format!("-{:.1}%", 100.0 * self.metrics.max_drawdown), drawdown_top + drawdown_height)));
+        area.draw_range_labels(&mut frame, curve[0].date.format("%Y-%m-%d").to_string(), curve[curve.len() - 1].date.format("%Y-%m-%d").to_string());
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,72 @@
+use crate::gui::chart::{plot_label, PlotArea, COLOR_BLUE, COLOR_RED, COLOR_WHITE};
+use crate::gui::update::Message;
+use iced::{alignment, mouse, Color, Point, Rectangle, Renderer, Size, Theme};
+use iced::widget::canvas;
+use iced::widget::canvas::{Frame, Path, Text};
+
+pub const HEATMAP_SIZE: f32 = 360f32;
+
+const LABEL_WIDTH: f32 = 60.0;
+const LABEL_HEIGHT: f32 = 20.0;
+
+/// Grid of values coloured from blue at the low end of the range to red at the high end.
+pub struct Heatmap {
+    pub row_labels: Vec<String>,
+    pub column_labels: Vec<String>,
+    pub values: Vec<Vec<f64>>,
+    pub range: (f64, f64),
+}
+
+impl Heatmap {
+    fn cell_color(&self, value: f64) -> Color {
+        let (low, high) = self.range;
+        let t = if high > low { ((value - low) / (high - low)).clamp(0.0, 1.0) as f32 } else { 0.5 };
+        let blend = |a: Color, b: Color, s: f32| Color::from_rgb(a.r + (b.r - a.r) * s, a.g + (b.g - a.g) * s, a.b + (b.b - a.b) * s);
+        if t < 0.5 { blend(COLOR_BLUE, COLOR_WHITE, 2.0 * t) } else { blend(COLOR_WHITE, COLOR_RED, 2.0 * t - 1.0) }
+    }
+}
+
+/// Shared plot label centred vertically on `position`, so it sits in the middle of its row.
+fn centered_label(content: String, position: Point, horizontal_alignment: alignment::Horizontal, color: Color) -> Text {
+    Text { vertical_alignment: alignment::Vertical::Center, ..plot_label(content, position, horizontal_alignment, color) }
+}
+
+impl canvas::Program<Message> for Heatmap {
+    type State = ();
+
+    fn draw(
+            &self,
+            _state: &Self::State,
+            renderer: &Renderer,
+            _theme: &Theme,
+            bounds: Rectangle,
+            _cursor: mouse::Cursor,
+        ) -> Vec<canvas::Geometry<Renderer>> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let rows = self.values.len();
+        let columns = self.values.first().map_or(0, |row| row.len());
+        if rows == 0 || columns == 0 {
+            return vec![frame.into_geometry()];
+        }
+        let area = PlotArea::with_margins(bounds, LABEL_WIDTH, LABEL_HEIGHT);
+        let cell = Size::new(area.width / columns as f32, area.height / rows as f32);
+
+        for (j, name) in self.column_labels.iter().enumerate() {
+            let position = Point::new(area.x((j as f64 + 0.5) / columns as f64), LABEL_HEIGHT / 2.0);
+            frame.fill_text(centered_label(name.clone(), position, alignment::Horizontal::Center, COLOR_WHITE));
+        }
+        for (i, row) in self.values.iter().enumerate() {
+            let top = area.y(i as f64 / rows as f64);
+            if let Some(name) = self.row_labels.get(i) {
+                frame.fill_text(centered_label(name.clone(), Point::new(LABEL_WIDTH - 5.0, top + cell.height / 2.0), alignment::Horizontal::Right, COLOR_WHITE));
+            }
+            for (j, value) in row.iter().enumerate() {
+                let corner = Point::new(area.x(j as f64 / columns as f64), top);
+                frame.fill(&Path::rectangle(corner, cell), self.cell_color(*value));
+                let center = Point::new(corner.x + cell.width / 2.0, corner.y + cell.height / 2.0);
+                frame.fill_text(centered_label(format!("{:.2}", value), center, alignment::Horizontal::Center, Color::BLACK));
+            }
+        }
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,58 @@
+use crate::gui::chart::{PlotArea, COLOR_BLUE, COLOR_YELLOW};
+use crate::gui::update::Message;
+use crate::model::statistics::Histogram;
+use iced::{mouse, Point, Rectangle, Renderer, Size, Theme};
+use iced::widget::canvas;
+use iced::widget::canvas::{Frame, Path, Stroke};
+
+pub const HISTOGRAM_WIDTH: f32 = 400f32;
+pub const HISTOGRAM_HEIGHT: f32 = 300f32;
+
+const BAR_GAP: f32 = 1.0;
+
+impl canvas::Program<Message> for Histogram {
+    type State = ();
+
+    fn draw(
+            &self,
+            _state: &Self::State,
+            renderer: &Renderer,
+            _theme: &Theme,
+            bounds: Rectangle,
+            _cursor: mouse::Cursor,
+        ) -> Vec<canvas::Geometry<Renderer>> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        if self.counts.is_empty() {
+            return vec![frame.into_geometry()];
+        }
+        let normal_counts = self.normal_counts();
+        let max_count = self.counts.iter().map(|c| *c as f64).chain(normal_counts.iter().copied()).fold(1.0, f64::max);
+
+        let area = PlotArea::new(bounds);
+        let bins = self.counts.len() as f64;
+        let bar_width = area.width / self.counts.len() as f32;
+        let y = |count: f64| area.y((max_count - count) / max_count);
+
+        area.draw_horizontal_axis(&mut frame, y(0.0));
+        for (i, count) in self.counts.iter().enumerate() {
+            let top = y(*count as f64);
+            let left = area.x(i as f64 / bins);
+            frame.fill_rectangle(Point::new(left + BAR_GAP, top), Size::new(bar_width - 2.0 * BAR_GAP, y(0.0) - top), COLOR_BLUE);
+        }
+
+        // Normal overlay through the bin centres
+        let overlay = Path::new(|p| {
+            p.move_to(Point::new(area.x(0.5 / bins), y(normal_counts[0])));
+            for (i, count) in normal_counts.iter().enumerate().skip(1) {
+                p.line_to(Point::new(area.x((i as f64 + 0.5) / bins), y(*count)));
+            }
+        });
+        frame.stroke(&overlay, Stroke::default().with_color(COLOR_YELLOW).with_width(2.0));
+
+        let upper = self.lower + self.bin_width * self.counts.len() as f64;
+        area.draw_value_labels(&mut frame, (format!("{:.0}", max_count), area.y(0.0)), None);
+        area.draw_range_labels(&mut frame, format!("{:.2}%", 100.0 * self.lower), format!("{:.2}%", 100.0 * upper));
+        area.draw_legend(&mut frame, &[("Returns", COLOR_BLUE), ("Normal", COLOR_YELLOW)]);
+        vec![frame.into_geometry()]
+    }
+}
@@ -1,3 +1,8 @@
 pub mod view;
 pub mod update;
-pub mod chart;
\ No newline at end of file
+pub mod chart;
+pub mod heatmap;
+pub mod payoff;
+pub mod equity;
+pub mod qq;
+pub mod histogram;
@@ -0,0 +1,55 @@
+use crate::gui::chart::{PlotArea, COLOR_BLUE, COLOR_RED, COLOR_YELLOW};
+use crate::gui::update::Message;
+use crate::model::strategy::PayoffDiagram;
+use iced::{mouse, Point, Rectangle, Renderer, Theme};
+use iced::widget::canvas;
+use iced::widget::canvas::{Frame, Path, Stroke};
+
+pub const PAYOFF_WIDTH: f32 = 400f32;
+pub const PAYOFF_HEIGHT: f32 = 400f32;
+
+
+impl canvas::Program<Message> for PayoffDiagram {
+    type State = ();
+
+    fn draw(
+            &self,
+            _state: &Self::State,
+            renderer: &Renderer,
+            _theme: &Theme,
+            bounds: Rectangle,
+            _cursor: mouse::Cursor,
+        ) -> Vec<canvas::Geometry<Renderer>> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let (Some(&low), Some(&high)) = (self.spots.first(), self.spots.last()) else {
+            return vec![frame.into_geometry()];
+        };
+        let pnl = self.expiry_pnl.iter().chain(&self.current_pnl);
+        let min_pnl = pnl.clone().fold(0.0, |a: f64, &b| a.min(b));
+        let max_pnl = pnl.fold(0.0, |a: f64, &b| a.max(b));
+        let pnl_range = if max_pnl > min_pnl { max_pnl - min_pnl } else { 1.0 };
+
+        let area = PlotArea::new(bounds);
+        let x = |spot: f64| area.x((spot - low) / (high - low));
+        let y = |value: f64| area.y((max_pnl - value) / pnl_range);
+
+        // Break-even line and current spot
+        area.draw_horizontal_axis(&mut frame, y(0.0));
+        frame.stroke(&Path::line(Point::new(x(self.spot), area.y(0.0)), Point::new(x(self.spot), area.y(1.0))), Stroke::default().with_color(COLOR_YELLOW));
+
+        for (values, color) in [(&self.expiry_pnl, COLOR_BLUE), (&self.current_pnl, COLOR_RED)] {
+            let line = Path::new(|p| {
+                p.move_to(Point::new(x(self.spots[0]), y(values[0])));
+                for (spot, value) in self.spots.iter().zip(values.iter()).skip(1) {
+                    p.line_to(Point::new(x(*spot), y(*value)));
+                }
+            });
+            frame.stroke(&line, Stroke::default().with_color(color).with_width(2.0));
+        }
+
+        area.draw_value_labels(&mut frame, (format!("{:.2}", max_pnl), area.y(0.0)), Some((format!("{:.2}", min_pnl), area.y(1.0))));
+        area.draw_range_labels(&mut frame, format!("{:.2}", low), format!("{:.2}", high));
+        area.draw_legend(&mut frame, &[("Expiry", COLOR_BLUE), ("Today", COLOR_RED)]);
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,50 @@
+use crate::gui::chart::{PlotArea, COLOR_BLUE, COLOR_WHITE, COLOR_YELLOW};
+use crate::gui::update::Message;
+use crate::model::innovations::QqPlot;
+use iced::{mouse, Point, Rectangle, Renderer, Theme};
+use iced::widget::canvas;
+use iced::widget::canvas::{Frame, Path, Stroke};
+
+pub const QQ_SIZE: f32 = 400f32;
+
+const POINT_RADIUS: f32 = 2.0;
+
+impl canvas::Program<Message> for QqPlot {
+    type State = ();
+
+    fn draw(
+            &self,
+            _state: &Self::State,
+            renderer: &Renderer,
+            _theme: &Theme,
+            bounds: Rectangle,
+            _cursor: mouse::Cursor,
+        ) -> Vec<canvas::Geometry<Renderer>> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        if self.empirical.is_empty() {
+            return vec![frame.into_geometry()];
+        }
+        // Same scale on both axes so a perfect fit lies on the diagonal
+        let values = self.empirical.iter().chain(&self.theoretical);
+        let low = values.clone().fold(f64::INFINITY, |a, &b| a.min(b));
+        let high = values.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+        let range = if high > low { high - low } else { 1.0 };
+
+        let area = PlotArea::new(bounds);
+        let x = |value: f64| area.x((value - low) / range);
+        let y = |value: f64| area.y((high - value) / range);
+
+        frame.stroke(&Path::line(Point::new(x(low), y(low)), Point::new(x(high), y(high))), Stroke::default().with_color(COLOR_YELLOW));
+        let points = Path::new(|p| {
+            for (theoretical, empirical) in self.theoretical.iter().zip(&self.empirical) {
+                p.circle(Point::new(x(*theoretical), y(*empirical)), POINT_RADIUS);
+            }
+        });
+        frame.fill(&points, COLOR_BLUE);
+
+        area.draw_value_labels(&mut frame, (format!("{:.2}", high), area.y(0.0)), Some((format!("{:.2}", low), area.y(1.0))));
+        area.draw_range_labels(&mut frame, format!("{:.2}", low), format!("{:.2}", high));
+        area.draw_legend(&mut frame, &[("Fitted quantiles against standardised returns", COLOR_WHITE)]);
+        vec![frame.into_geometry()]
+    }
+}
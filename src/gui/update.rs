@@ -1,5 +1,25 @@
-use crate::model::application::OptiRust;
-use crate::model::monte_carlo::MonteCarloPricing;
+use chrono::NaiveDate;
+use crate::model::analytic::OptionType;
+use crate::model::application::{OptiRust, VALUATION_DATE_FORMAT};
+use crate::model::calibration::CalibrationTarget;
+use crate::model::heston::HestonScheme;
+use crate::model::innovations::InnovationDistribution;
+use crate::model::asian::{AsianAverage, AsianStrike};
+use crate::model::barrier::{BarrierDirection, BarrierKnock};
+use crate::model::bootstrap::BootstrapScheme;
+use crate::model::calendar::{Calendar, DayCount};
+use crate::model::correlation::CovarianceEstimator;
+use crate::model::costs::SpreadQuote;
+use crate::model::exotics::{DigitalKind, LookbackStrike};
+use crate::model::monte_carlo::{ExerciseStyle, PayoffKind, SimulationModel};
+use crate::model::multi_asset::MultiAssetPayoff;
+use crate::model::risk::VarMethod;
+use crate::model::scenario::{ScenarioPricer, ScenarioTarget};
+use crate::model::statistics::ReturnKind;
+use crate::model::strategy::{LegKind, StrategyTemplate};
+use crate::model::sweep::SweepMetric;
+use crate::model::systematic::SystematicStrategy;
+use crate::model::yield_curve::{CurveInterpolation, InterpolatedQuantity};
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -18,10 +38,149 @@ pub enum Message {
     ImpliedVolChanged(String),
     RiskFreeRateChanged(String),
     DaysToExpireChanged(String),
+    ValuationDateChanged(String),
     NumStepsChanged(String),
     NumSimulationsChanged(String),
+    ModelSelected(SimulationModel),
+    HestonSchemeSelected(HestonScheme),
+    HestonKappaChanged(String),
+    HestonThetaChanged(String),
+    HestonXiChanged(String),
+    HestonRhoChanged(String),
+    HestonV0Changed(String),
+    MertonLambdaChanged(String),
+    MertonMuJumpChanged(String),
+    MertonSigmaJumpChanged(String),
+    KouLambdaChanged(String),
+    KouPUpChanged(String),
+    KouEtaUpChanged(String),
+    KouEtaDownChanged(String),
+    EstimateJumps,
+    UseHistoricalVol,
+    SabrAlphaChanged(String),
+    SabrBetaChanged(String),
+    SabrRhoChanged(String),
+    SabrNuChanged(String),
+    UseSabrVol,
+    BootstrapSchemeSelected(BootstrapScheme),
+    BootstrapBlockLengthChanged(String),
+    BootstrapTargetVolChanged(String),
+    InnovationsSelected(InnovationDistribution),
+    InnovationNuChanged(String),
+    InnovationSkewChanged(String),
+    NigAlphaChanged(String),
+    NigBetaChanged(String),
+    FitInnovations,
+    ReturnKindSelected(ReturnKind),
+    AutocorrelationLagsChanged(String),
     UpdateParameters,
     RunMonteCarlo,
+
+    OptionChainPathChanged(String),
+    LoadOptionChain,
+    CalibrationTargetSelected(CalibrationTarget),
+    CalibrateHeston,
+    CalibrationPathChanged(String),
+    SaveCalibration,
+    LoadCalibration,
+    VolSurfacePathChanged(String),
+    LoadVolSurface,
+    CalibrateSabr,
+    DividendYieldChanged(String),
+    DividendsPathChanged(String),
+    LoadDividends,
+    ExerciseSelected(ExerciseStyle),
+    YieldCurvePathChanged(String),
+    LoadYieldCurve,
+    CurveInterpolationSelected(CurveInterpolation),
+    CurveQuantitySelected(InterpolatedQuantity),
+    DayCountSelected(DayCount),
+    CalendarSelected(Calendar),
+    CalendarPathChanged(String),
+    LoadCalendar,
+    PayoffSelected(PayoffKind),
+    BarrierDirectionSelected(BarrierDirection),
+    BarrierKnockSelected(BarrierKnock),
+    BarrierLevelChanged(String),
+    BarrierRebateChanged(String),
+    BrownianBridgeToggled(bool),
+    AsianAverageSelected(AsianAverage),
+    AsianStrikeSelected(AsianStrike),
+    AveragingDaysChanged(String),
+    LookbackStrikeSelected(LookbackStrike),
+    DigitalKindSelected(DigitalKind),
+    DigitalCashChanged(String),
+    DigitalSmoothingChanged(String),
+    GapTriggerChanged(String),
+    MultiAssetPayoffSelected(MultiAssetPayoff),
+    AssetSpotsChanged(String),
+    AssetVolsChanged(String),
+    AssetDividendYieldsChanged(String),
+    BasketWeightsChanged(String),
+    CorrelationChanged(String),
+    PriceMultiAsset,
+    TickersChanged(String),
+    ImportTickers,
+    CovarianceEstimatorSelected(CovarianceEstimator),
+    EwmaDecayChanged(String),
+    UseCorrelationEstimate,
+    StrategyTemplateSelected(StrategyTemplate),
+    StrategyWidthChanged(String),
+    AddLeg,
+    RemoveLeg(usize),
+    LegKindSelected(usize, LegKind),
+    LegQuantityChanged(usize, String),
+    LegStrikeChanged(usize, String),
+    LegDaysChanged(usize, String),
+    PriceStrategy,
+    HedgeOptionTypeSelected(OptionType),
+    HedgeRebalanceStepsChanged(String),
+    HedgeCostChanged(String),
+    HedgeVolChanged(String),
+    HedgeSimulatedPaths,
+    HedgeHistory,
+    VarConfidencesChanged(String),
+    VarMethodSelected(VarMethod),
+    VarWindowChanged(String),
+    ComputeRisk,
+    BacktestVar,
+    ScenarioSpotShocksChanged(String),
+    ScenarioVolShocksChanged(String),
+    ScenarioDecayDaysChanged(String),
+    ScenarioRateShockChanged(String),
+    ScenarioPricerSelected(ScenarioPricer),
+    ScenarioTargetSelected(ScenarioTarget),
+    RunScenarios,
+    ScenarioCsvPathChanged(String),
+    ExportScenarios,
+    BacktestInitialCashChanged(String),
+    RunBacktest,
+    CostCommissionOrderChanged(String),
+    CostCommissionChanged(String),
+    CostContractSizeChanged(String),
+    CostNotionalRateChanged(String),
+    CostSpreadQuoteSelected(SpreadQuote),
+    CostSpreadChanged(String),
+    CostSlippageImpactChanged(String),
+    SweepOptionKindSelected(LegKind),
+    SweepQuantityChanged(String),
+    SweepStrikeOffsetsChanged(String),
+    SweepDaysToExpiryChanged(String),
+    SweepRollDaysChanged(String),
+    SweepMetricSelected(SweepMetric),
+    InSampleBarsChanged(String),
+    OutOfSampleBarsChanged(String),
+    RunSweep,
+    RunWalkForward,
+    SystematicStrategySelected(SystematicStrategy),
+    SystematicQuantityChanged(String),
+    SystematicDaysChanged(String),
+    SystematicRollDaysChanged(String),
+    SystematicDeltaChanged(String),
+    SystematicWingDeltaChanged(String),
+    ProfitTakeChanged(String),
+    StopLossChanged(String),
+    RunSystematicBacktest,
 }
 
 impl OptiRust {
@@ -58,14 +217,62 @@ impl OptiRust {
             Message::ImpliedVolChanged(value) => self.monte_carlo_params.implied_vol = value,
             Message::RiskFreeRateChanged(value) => self.monte_carlo_params.risk_free_rate = value,
             Message::DaysToExpireChanged(value) => self.monte_carlo_params.days_to_expire = value,
+            Message::ValuationDateChanged(value) => {
+                if let Ok(date) = NaiveDate::parse_from_str(&value, VALUATION_DATE_FORMAT) {
+                    self.monte_carlo_params.valuation_date = date;
+                }
+                self.valuation_date_text = value;
+            }
             Message::NumSimulationsChanged(value) => self.monte_carlo_params.num_simulations = value,
             Message::NumStepsChanged(value) => self.monte_carlo_params.num_steps = value,
+            Message::ModelSelected(model) => self.monte_carlo_params.model = model,
+            Message::HestonSchemeSelected(scheme) => self.monte_carlo_params.heston_scheme = scheme,
+            Message::HestonKappaChanged(value) => self.monte_carlo_params.heston_kappa = value,
+            Message::HestonThetaChanged(value) => self.monte_carlo_params.heston_theta = value,
+            Message::HestonXiChanged(value) => self.monte_carlo_params.heston_xi = value,
+            Message::HestonRhoChanged(value) => self.monte_carlo_params.heston_rho = value,
+            Message::HestonV0Changed(value) => self.monte_carlo_params.heston_v0 = value,
+            Message::MertonLambdaChanged(value) => self.monte_carlo_params.merton_lambda = value,
+            Message::MertonMuJumpChanged(value) => self.monte_carlo_params.merton_mu_jump = value,
+            Message::MertonSigmaJumpChanged(value) => self.monte_carlo_params.merton_sigma_jump = value,
+            Message::KouLambdaChanged(value) => self.monte_carlo_params.kou_lambda = value,
+            Message::KouPUpChanged(value) => self.monte_carlo_params.kou_p_up = value,
+            Message::KouEtaUpChanged(value) => self.monte_carlo_params.kou_eta_up = value,
+            Message::KouEtaDownChanged(value) => self.monte_carlo_params.kou_eta_down = value,
+            Message::SabrAlphaChanged(value) => self.monte_carlo_params.sabr_alpha = value,
+            Message::SabrBetaChanged(value) => self.monte_carlo_params.sabr_beta = value,
+            Message::SabrRhoChanged(value) => self.monte_carlo_params.sabr_rho = value,
+            Message::SabrNuChanged(value) => self.monte_carlo_params.sabr_nu = value,
+            Message::UseSabrVol => self.use_sabr_vol(),
+            Message::BootstrapSchemeSelected(scheme) => self.monte_carlo_params.bootstrap_scheme = scheme,
+            Message::BootstrapBlockLengthChanged(value) => self.monte_carlo_params.bootstrap_block_length = value,
+            Message::BootstrapTargetVolChanged(value) => self.monte_carlo_params.bootstrap_target_vol = value,
+            Message::EstimateJumps => self.estimate_jumps(),
+            Message::UseHistoricalVol => self.use_historical_vol(),
+            Message::InnovationsSelected(distribution) => {
+                self.monte_carlo_params.innovations = distribution;
+                self.qq_plot = None;
+            }
+            Message::InnovationNuChanged(value) => self.monte_carlo_params.innovation_nu = value,
+            Message::InnovationSkewChanged(value) => self.monte_carlo_params.innovation_skew = value,
+            Message::NigAlphaChanged(value) => self.monte_carlo_params.nig_alpha = value,
+            Message::NigBetaChanged(value) => self.monte_carlo_params.nig_beta = value,
+            Message::FitInnovations => self.fit_innovations(),
+            Message::ReturnKindSelected(kind) => self.monte_carlo_params.return_kind = kind,
+            Message::AutocorrelationLagsChanged(value) => self.monte_carlo_params.autocorrelation_lags = value,
             Message::UpdateParameters => {
-                self.monte_carlo_pricing = MonteCarloPricing::from_params(&self.monte_carlo_params);
+                self.monte_carlo_pricing = self.build_pricing();
                 self.monte_carlo_params.implied_vol = self.monte_carlo_pricing.implied_volatility().to_string();
             }
             Message::RunMonteCarlo => {
-                self.monte_carlo_pricing = MonteCarloPricing::from_params(&self.monte_carlo_params);
+                self.monte_carlo_pricing = self.build_pricing();
+                if self.monte_carlo_pricing.payoff == PayoffKind::Asian {
+                    let estimate = self.monte_carlo_pricing.asian_estimate(self.chart.underlying_price());
+                    self.pricing_result = Some(estimate.price);
+                    self.asian_estimate = Some(estimate);
+                    return;
+                }
+                self.asian_estimate = None;
                 let output = self.monte_carlo_pricing.price(&self.chart);
                 if output.is_ok() {
                     self.pricing_result = output.ok();
@@ -73,6 +280,138 @@ impl OptiRust {
                     self.error_message = Some(output.err().unwrap().to_string());
                 }
             }
+            Message::OptionChainPathChanged(value) => self.option_chain_path = value,
+            Message::LoadOptionChain => self.load_option_chain(),
+            Message::CalibrationTargetSelected(target) => self.calibration_target = target,
+            Message::CalibrateHeston => self.calibrate_heston(),
+            Message::CalibrationPathChanged(value) => self.calibration_path = value,
+            Message::SaveCalibration => self.save_heston_calibration(),
+            Message::LoadCalibration => self.load_heston_calibration(),
+            Message::VolSurfacePathChanged(value) => self.vol_surface_path = value,
+            Message::LoadVolSurface => self.load_vol_surface(),
+            Message::CalibrateSabr => self.calibrate_sabr(),
+            Message::DividendYieldChanged(value) => self.monte_carlo_params.dividend_yield = value,
+            Message::DividendsPathChanged(value) => self.dividends_path = value,
+            Message::LoadDividends => self.load_dividends(),
+            Message::ExerciseSelected(exercise) => self.monte_carlo_params.exercise = exercise,
+            Message::YieldCurvePathChanged(value) => self.yield_curve_path = value,
+            Message::LoadYieldCurve => self.load_yield_curve(),
+            Message::CurveInterpolationSelected(interpolation) => self.monte_carlo_params.curve_interpolation = interpolation,
+            Message::CurveQuantitySelected(quantity) => self.monte_carlo_params.curve_quantity = quantity,
+            Message::DayCountSelected(day_count) => self.monte_carlo_params.day_count = day_count,
+            Message::CalendarSelected(calendar) => self.monte_carlo_params.calendar = calendar,
+            Message::CalendarPathChanged(value) => self.calendar_path = value,
+            Message::LoadCalendar => self.load_calendar(),
+            Message::PayoffSelected(payoff) => self.monte_carlo_params.payoff = payoff,
+            Message::BarrierDirectionSelected(direction) => self.monte_carlo_params.barrier_direction = direction,
+            Message::BarrierKnockSelected(knock) => self.monte_carlo_params.barrier_knock = knock,
+            Message::BarrierLevelChanged(value) => self.monte_carlo_params.barrier_level = value,
+            Message::BarrierRebateChanged(value) => self.monte_carlo_params.barrier_rebate = value,
+            Message::BrownianBridgeToggled(value) => self.monte_carlo_params.brownian_bridge = value,
+            Message::AsianAverageSelected(average) => self.monte_carlo_params.asian_average = average,
+            Message::AsianStrikeSelected(strike) => self.monte_carlo_params.asian_strike = strike,
+            Message::AveragingDaysChanged(value) => self.monte_carlo_params.averaging_days = value,
+            Message::LookbackStrikeSelected(strike) => self.monte_carlo_params.lookback_strike = strike,
+            Message::DigitalKindSelected(kind) => self.monte_carlo_params.digital_kind = kind,
+            Message::DigitalCashChanged(value) => self.monte_carlo_params.digital_cash = value,
+            Message::DigitalSmoothingChanged(value) => self.monte_carlo_params.digital_smoothing = value,
+            Message::GapTriggerChanged(value) => self.monte_carlo_params.gap_trigger = value,
+            Message::MultiAssetPayoffSelected(payoff) => self.monte_carlo_params.multi_asset_payoff = payoff,
+            Message::AssetSpotsChanged(value) => self.monte_carlo_params.asset_spots = value,
+            Message::AssetVolsChanged(value) => self.monte_carlo_params.asset_vols = value,
+            Message::AssetDividendYieldsChanged(value) => self.monte_carlo_params.asset_dividend_yields = value,
+            Message::BasketWeightsChanged(value) => self.monte_carlo_params.basket_weights = value,
+            Message::CorrelationChanged(value) => self.monte_carlo_params.correlation = value,
+            Message::PriceMultiAsset => self.price_multi_asset(),
+            Message::TickersChanged(value) => self.tickers_text = value,
+            Message::ImportTickers => self.import_tickers(),
+            Message::CovarianceEstimatorSelected(estimator) => {
+                self.monte_carlo_params.covariance_estimator = estimator;
+                self.estimate_correlation();
+            }
+            Message::EwmaDecayChanged(value) => {
+                self.monte_carlo_params.ewma_decay = value;
+                self.estimate_correlation();
+            }
+            Message::UseCorrelationEstimate => self.use_correlation_estimate(),
+            Message::StrategyTemplateSelected(template) => self.monte_carlo_params.set_strategy_template(template),
+            Message::StrategyWidthChanged(value) => self.monte_carlo_params.strategy_width = value,
+            Message::AddLeg => self.monte_carlo_params.add_strategy_leg(),
+            Message::RemoveLeg(index) => {
+                if index < self.monte_carlo_params.strategy_legs.len() {
+                    self.monte_carlo_params.strategy_legs.remove(index);
+                }
+            }
+            Message::LegKindSelected(index, kind) => {
+                if let Some(leg) = self.monte_carlo_params.strategy_legs.get_mut(index) {
+                    leg.kind = kind;
+                }
+            }
+            Message::LegQuantityChanged(index, value) => {
+                if let Some(leg) = self.monte_carlo_params.strategy_legs.get_mut(index) {
+                    leg.quantity = value;
+                }
+            }
+            Message::LegStrikeChanged(index, value) => {
+                if let Some(leg) = self.monte_carlo_params.strategy_legs.get_mut(index) {
+                    leg.strike = value;
+                }
+            }
+            Message::LegDaysChanged(index, value) => {
+                if let Some(leg) = self.monte_carlo_params.strategy_legs.get_mut(index) {
+                    leg.days_to_expire = value;
+                }
+            }
+            Message::PriceStrategy => self.price_strategy(),
+            Message::HedgeOptionTypeSelected(option_type) => self.monte_carlo_params.hedge_option_type = option_type,
+            Message::HedgeRebalanceStepsChanged(value) => self.monte_carlo_params.hedge_rebalance_steps = value,
+            Message::HedgeCostChanged(value) => self.monte_carlo_params.hedge_cost = value,
+            Message::HedgeVolChanged(value) => self.monte_carlo_params.hedge_vol = value,
+            Message::HedgeSimulatedPaths => self.hedge_on_simulated_paths(),
+            Message::HedgeHistory => self.hedge_on_history(),
+            Message::VarConfidencesChanged(value) => self.monte_carlo_params.var_confidences = value,
+            Message::VarMethodSelected(method) => self.monte_carlo_params.var_method = method,
+            Message::VarWindowChanged(value) => self.monte_carlo_params.var_window = value,
+            Message::ComputeRisk => self.compute_risk(),
+            Message::BacktestVar => self.backtest_var(),
+            Message::ScenarioSpotShocksChanged(value) => self.monte_carlo_params.scenario_spot_shocks = value,
+            Message::ScenarioVolShocksChanged(value) => self.monte_carlo_params.scenario_vol_shocks = value,
+            Message::ScenarioDecayDaysChanged(value) => self.monte_carlo_params.scenario_decay_days = value,
+            Message::ScenarioRateShockChanged(value) => self.monte_carlo_params.scenario_rate_shock = value,
+            Message::ScenarioPricerSelected(pricer) => self.monte_carlo_params.scenario_pricer = pricer,
+            Message::ScenarioTargetSelected(target) => self.monte_carlo_params.scenario_target = target,
+            Message::RunScenarios => self.run_scenarios(),
+            Message::ScenarioCsvPathChanged(value) => self.scenario_csv_path = value,
+            Message::ExportScenarios => self.export_scenarios(),
+            Message::BacktestInitialCashChanged(value) => self.monte_carlo_params.backtest_initial_cash = value,
+            Message::RunBacktest => self.run_backtest(),
+            Message::CostCommissionOrderChanged(value) => self.monte_carlo_params.cost_commission_order = value,
+            Message::CostCommissionChanged(value) => self.monte_carlo_params.cost_commission = value,
+            Message::CostContractSizeChanged(value) => self.monte_carlo_params.cost_contract_size = value,
+            Message::CostNotionalRateChanged(value) => self.monte_carlo_params.cost_notional_rate = value,
+            Message::CostSpreadQuoteSelected(quote) => self.monte_carlo_params.cost_spread_quote = quote,
+            Message::CostSpreadChanged(value) => self.monte_carlo_params.cost_spread = value,
+            Message::CostSlippageImpactChanged(value) => self.monte_carlo_params.cost_slippage_impact = value,
+            Message::SweepOptionKindSelected(kind) => self.monte_carlo_params.sweep_option_kind = kind,
+            Message::SweepQuantityChanged(value) => self.monte_carlo_params.sweep_quantity = value,
+            Message::SweepStrikeOffsetsChanged(value) => self.monte_carlo_params.sweep_strike_offsets = value,
+            Message::SweepDaysToExpiryChanged(value) => self.monte_carlo_params.sweep_days_to_expiry = value,
+            Message::SweepRollDaysChanged(value) => self.monte_carlo_params.sweep_roll_days = value,
+            Message::SweepMetricSelected(metric) => self.sort_sweep(metric),
+            Message::InSampleBarsChanged(value) => self.monte_carlo_params.in_sample_bars = value,
+            Message::OutOfSampleBarsChanged(value) => self.monte_carlo_params.out_of_sample_bars = value,
+            Message::RunSweep => self.run_sweep(),
+            Message::RunWalkForward => self.run_walk_forward(),
+            Message::SystematicStrategySelected(strategy) => self.monte_carlo_params.systematic_strategy = strategy,
+            Message::SystematicQuantityChanged(value) => self.monte_carlo_params.systematic_quantity = value,
+            Message::SystematicDaysChanged(value) => self.monte_carlo_params.systematic_days = value,
+            Message::SystematicRollDaysChanged(value) => self.monte_carlo_params.systematic_roll_days = value,
+            Message::SystematicDeltaChanged(value) => self.monte_carlo_params.systematic_delta = value,
+            Message::SystematicWingDeltaChanged(value) => self.monte_carlo_params.systematic_wing_delta = value,
+            Message::ProfitTakeChanged(value) => self.monte_carlo_params.profit_take = value,
+            Message::StopLossChanged(value) => self.monte_carlo_params.stop_loss = value,
+            Message::RunSystematicBacktest => self.run_systematic_backtest(),
         }
+        self.chart.barrier_levels = self.monte_carlo_params.barrier_levels();
     }
 }
\ No newline at end of file
@@ -1,24 +1,60 @@
-use iced::widget::{button, canvas, center, column, container, mouse_area, opaque, rich_text, row, span, stack, text, text_input, Container, Row};
+use iced::widget::{button, canvas, center, checkbox, column, container, mouse_area, opaque, pick_list, rich_text, row, span, stack, text, text_input, Column, Container, Row};
 use iced::{color, font, Color, Element, Font};
-use crate::model::application::OptiRust;
+use crate::model::analytic::OptionType;
+use crate::model::application::{OptiRust, VALUATION_DATE_FORMAT};
+use crate::model::asian::{AsianAverage, AsianStrike};
+use crate::model::barrier::{BarrierDirection, BarrierKnock};
+use crate::model::bootstrap::BootstrapScheme;
+use crate::model::calendar::DayCount;
+use crate::model::correlation::CovarianceEstimator;
+use crate::model::costs::SpreadQuote;
+use crate::model::calibration::CalibrationTarget;
+use crate::model::exotics::{DigitalKind, LookbackStrike};
+use crate::model::heston::HestonScheme;
+use crate::model::innovations::InnovationDistribution;
+use crate::model::monte_carlo::{ExerciseStyle, PayoffKind, SimulationModel};
+use crate::model::multi_asset::MultiAssetPayoff;
+use crate::model::risk::VarMethod;
+use crate::model::scenario::{ScenarioPricer, ScenarioTarget};
+use crate::model::statistics::ReturnKind;
+use crate::model::strategy::{LegKind, StrategyTemplate};
+use crate::model::sweep::{metric_grid, SweepMetric};
+use crate::model::systematic::SystematicStrategy;
+use crate::model::yield_curve::{CurveInterpolation, InterpolatedQuantity};
 use crate::gui::chart;
+use crate::gui::equity::{EQUITY_HEIGHT, EQUITY_WIDTH};
+use crate::gui::heatmap::{Heatmap, HEATMAP_SIZE};
+use crate::gui::histogram::{HISTOGRAM_HEIGHT, HISTOGRAM_WIDTH};
+use crate::gui::payoff::{PAYOFF_HEIGHT, PAYOFF_WIDTH};
+use crate::gui::qq::QQ_SIZE;
 use crate::gui::update::Message;
 
 const API_KEY_INPUT_WIDTH:u16 = 300;
 const STOCK_INPUT_WIDTH: u16 = 150;
 const PARAM_WIDTH: u16 = 70;
 const PARAM_DESCRIPTION_WIDTH: u16 = 170;
+const FILE_PATH_INPUT_WIDTH: u16 = 250;
+const SWEEP_TABLE_ROWS: usize = 20;
 
 impl OptiRust {
-    pub fn view(&self) -> Element<Message> {
+    pub fn view(&self) -> Element<'_, Message> {
         let main_content = column![
             row![
                 button("Import").on_press(Message::ShowImport),
                 text!("Current Index: {}", self.imported_index),
             ].spacing(10),
-            canvas(&self.chart).width(chart::CHART_WIDTH).height(chart::CHART_HEIGHT),
-            self.display_monte_carlo_params()
-
+            self.display_charts(),
+            self.display_statistics(),
+            self.display_monte_carlo_params(),
+            self.display_strategy(),
+            self.display_hedging(),
+            self.display_risk(),
+            self.display_scenarios(),
+            self.display_systematic(),
+            self.display_backtest(),
+            self.display_sweep(),
+            self.display_multi_asset(),
+            self.display_calibration(),
         ].spacing(20);
         if let Some(value) = &self.error_message {
             modal(main_content, text!["Error occured: {}", value], Message::ClearError)
@@ -31,6 +67,290 @@ impl OptiRust {
         }
     }
 
+    fn display_charts(&self) -> Row<'_, Message> {
+        let mut content = row![canvas(&self.chart).width(chart::CHART_WIDTH).height(chart::CHART_HEIGHT)].spacing(20);
+        if let Some(diagram) = &self.payoff_diagram {
+            content = content.push(canvas(diagram).width(PAYOFF_WIDTH).height(PAYOFF_HEIGHT));
+        }
+        if let Some(qq_plot) = &self.qq_plot {
+            content = content.push(canvas(qq_plot).width(QQ_SIZE).height(QQ_SIZE));
+        }
+        content
+    }
+
+    fn display_statistics(&self) -> Row<'_, Message> {
+        let params = &self.monte_carlo_params;
+        let mut inputs = column![
+            row![text!["Returns: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(ReturnKind::ALL, Some(params.return_kind), Message::ReturnKindSelected)],
+            row![text!["Autocorrelation lags: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.autocorrelation_lags, &params.autocorrelation_lags).width(PARAM_WIDTH).on_input(Message::AutocorrelationLagsChanged)],
+        ].spacing(5);
+        let periods_per_year = self.chart.periods_per_year(params.day_count, &params.calendar);
+        let lags = params.autocorrelation_lags.parse::<usize>().unwrap_or(10);
+        let Some(stats) = self.chart.return_statistics(params.return_kind, periods_per_year, lags) else {
+            return row![inputs.push(text!["Not enough price history for return statistics"])];
+        };
+        inputs = inputs.extend([
+            text!["Observations: {}", stats.count].into(),
+            text!["Mean: {:.4}%", 100.0 * stats.mean].into(),
+            text!["Annualised volatility: {:.2}%", 100.0 * stats.annualised_vol].into(),
+            text!["Skewness: {:.3}", stats.skewness].into(),
+            text!["Excess kurtosis: {:.3}", stats.excess_kurtosis].into(),
+            text!["Jarque-Bera: {:.2} (p = {:.4})", stats.jarque_bera, stats.jarque_bera_p_value].into(),
+            text!["Ljung-Box: {:.2} (p = {:.4})", stats.ljung_box, stats.ljung_box_p_value].into(),
+        ]);
+        let mut autocorrelations = column![text!["Autocorrelation"]].spacing(2);
+        for (i, rho) in stats.autocorrelations.iter().enumerate() {
+            autocorrelations = autocorrelations.push(text!["Lag {}: {:+.3}", i + 1, rho]);
+        }
+        row![inputs, autocorrelations, canvas(stats.histogram).width(HISTOGRAM_WIDTH).height(HISTOGRAM_HEIGHT)].spacing(20)
+    }
+
+    fn display_strategy(&self) -> Row<'_, Message> {
+        let params = &self.monte_carlo_params;
+        let mut legs = column![
+            row![
+                pick_list(StrategyTemplate::ALL, params.strategy_template, Message::StrategyTemplateSelected).placeholder("Strategy template"),
+                text!["Strike width: "],
+                text_input(&params.strategy_width, &params.strategy_width).width(PARAM_WIDTH).on_input(Message::StrategyWidthChanged),
+                button("Add leg").on_press(Message::AddLeg),
+            ].spacing(10),
+        ].spacing(5);
+        for (i, leg) in params.strategy_legs.iter().enumerate() {
+            legs = legs.push(row![
+                pick_list(LegKind::ALL, Some(leg.kind), move |kind| Message::LegKindSelected(i, kind)),
+                text!["Quantity: "],
+                text_input(&leg.quantity, &leg.quantity).width(PARAM_WIDTH).on_input(move |value| Message::LegQuantityChanged(i, value)),
+                text!["Strike: "],
+                text_input(&leg.strike, &leg.strike).width(PARAM_WIDTH).on_input(move |value| Message::LegStrikeChanged(i, value)),
+                text!["Days: "],
+                text_input(&leg.days_to_expire, &leg.days_to_expire).width(PARAM_WIDTH).on_input(move |value| Message::LegDaysChanged(i, value)),
+                button("Remove").on_press(Message::RemoveLeg(i)),
+            ].spacing(10));
+        }
+        let mut results = column![button("Price strategy").on_press(Message::PriceStrategy)].spacing(5);
+        if let Some(g) = &self.strategy_greeks {
+            results = results.extend([
+                text!["Value: {:.4}", g.price].into(),
+                text!["Delta: {:.4}  Gamma: {:.4}", g.delta, g.gamma].into(),
+                text!["Vega: {:.4}  Theta: {:.4}  Rho: {:.4}", g.vega, g.theta, g.rho].into(),
+            ]);
+        }
+        row![legs, results].spacing(20)
+    }
+
+    fn display_hedging(&self) -> Row<'_, Message> {
+        let params = &self.monte_carlo_params;
+        let mut results = column![
+            button("Hedge on simulated paths").on_press(Message::HedgeSimulatedPaths),
+            button("Hedge on price history").on_press(Message::HedgeHistory),
+        ].spacing(10);
+        if let Some(d) = &self.hedging_distribution {
+            results = results.extend([
+                text!["Hedging P&L over {} paths: mean {:.4}, std dev {:.4}", d.pnls.len(), d.mean, d.std_dev].into(),
+                text!["Percentiles 1%: {:.4}  5%: {:.4}  50%: {:.4}  95%: {:.4}  99%: {:.4}", d.percentile_1, d.percentile_5, d.median, d.percentile_95, d.percentile_99].into(),
+            ]);
+        }
+        row![
+            column![
+                row![text!["Short option: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(OptionType::ALL, Some(params.hedge_option_type), Message::HedgeOptionTypeSelected)],
+                row![text!["Rebalance every (steps): "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.hedge_rebalance_steps, &params.hedge_rebalance_steps).width(PARAM_WIDTH).on_input(Message::HedgeRebalanceStepsChanged)],
+                row![text!["Transaction cost rate: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.hedge_cost, &params.hedge_cost).width(PARAM_WIDTH).on_input(Message::HedgeCostChanged)],
+                row![text!["Hedge volatility: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.hedge_vol, &params.hedge_vol).width(PARAM_WIDTH).on_input(Message::HedgeVolChanged)],
+            ].spacing(5),
+            results,
+        ].spacing(20)
+    }
+
+    fn display_risk(&self) -> Row<'_, Message> {
+        let params = &self.monte_carlo_params;
+        let inputs = column![
+            row![text!["Confidence levels: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.var_confidences, &params.var_confidences).width(FILE_PATH_INPUT_WIDTH).on_input(Message::VarConfidencesChanged)],
+            button("Compute VaR and ES").on_press(Message::ComputeRisk),
+            row![text!["Backtest method: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(VarMethod::ALL, Some(params.var_method), Message::VarMethodSelected)],
+            row![text!["Volatility window (bars): "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.var_window, &params.var_window).width(PARAM_WIDTH).on_input(Message::VarWindowChanged)],
+            button("Backtest VaR").on_press(Message::BacktestVar),
+        ].spacing(5);
+        let mut estimates = column![].spacing(2);
+        for e in &self.risk_estimates {
+            estimates = estimates.push(text![
+                "{}, {} bar{}, {:.1}%: VaR {:.4}, ES {:.4}",
+                e.method, e.horizon, if e.horizon == 1 { "" } else { "s" }, 100.0 * e.confidence, e.value_at_risk, e.expected_shortfall
+            ]);
+        }
+        if let Some(b) = &self.var_backtest {
+            estimates = estimates.extend([
+                text!["Exceedances: {} of {} bars, {:.1} expected", b.exceedances, b.exceeded.len(), b.expected_rate * b.exceeded.len() as f64].into(),
+                text!["Kupiec: {:.3} (p = {:.4})", b.kupiec, b.kupiec_p_value].into(),
+                text!["Christoffersen: {:.3} (p = {:.4})", b.christoffersen, b.christoffersen_p_value].into(),
+            ]);
+        }
+        row![inputs, estimates].spacing(20)
+    }
+
+    fn display_scenarios(&self) -> Row<'_, Message> {
+        let params = &self.monte_carlo_params;
+        let inputs = column![
+            row![text!["Spot shocks: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.scenario_spot_shocks, &params.scenario_spot_shocks).width(FILE_PATH_INPUT_WIDTH).on_input(Message::ScenarioSpotShocksChanged)],
+            row![text!["Vol shocks: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.scenario_vol_shocks, &params.scenario_vol_shocks).width(FILE_PATH_INPUT_WIDTH).on_input(Message::ScenarioVolShocksChanged)],
+            row![text!["Days elapsed: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.scenario_decay_days, &params.scenario_decay_days).width(FILE_PATH_INPUT_WIDTH).on_input(Message::ScenarioDecayDaysChanged)],
+            row![text!["Rate shock: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.scenario_rate_shock, &params.scenario_rate_shock).width(PARAM_WIDTH).on_input(Message::ScenarioRateShockChanged)],
+            row![text!["Pricer: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(ScenarioPricer::ALL, Some(params.scenario_pricer), Message::ScenarioPricerSelected)],
+            row![text!["Revalue: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(ScenarioTarget::ALL, Some(params.scenario_target), Message::ScenarioTargetSelected)],
+            button("Run scenarios").on_press(Message::RunScenarios),
+            row![
+                text_input("CSV file", &self.scenario_csv_path).width(FILE_PATH_INPUT_WIDTH).on_input(Message::ScenarioCsvPathChanged),
+                button("Export CSV").on_press(Message::ExportScenarios),
+            ].spacing(10),
+        ].spacing(5);
+        let mut content = row![inputs].spacing(20);
+        let Some(grid) = &self.scenario_grid else {
+            return content;
+        };
+
+        // One P&L heatmap per number of days elapsed, on a common scale centred on zero
+        let slices: Vec<Vec<Vec<f64>>> = (0..grid.settings.decay_days.len()).map(|d| grid.pnl_slice(d)).collect();
+        let largest = slices.iter().flatten().flatten().fold(0.0, |a: f64, &b| a.max(b.abs()));
+        for (days, values) in grid.settings.decay_days.iter().zip(slices) {
+            content = content.push(column![
+                text!["P&L after {} days, spot shock by row and vol points by column (value today {:.4})", days, grid.base_value],
+                canvas(Heatmap {
+                    row_labels: grid.settings.spot_shocks.iter().map(|s| format!("{:+.0}%", 100.0 * s)).collect(),
+                    column_labels: grid.settings.vol_shocks.iter().map(|v| format!("{:+.0}", 100.0 * v)).collect(),
+                    values,
+                    range: (-largest, largest),
+                }).width(HEATMAP_SIZE).height(HEATMAP_SIZE),
+            ].spacing(5));
+        }
+        content
+    }
+
+    fn display_systematic(&self) -> Column<'_, Message> {
+        let params = &self.monte_carlo_params;
+        column![
+            row![
+                pick_list(SystematicStrategy::ALL, Some(params.systematic_strategy), Message::SystematicStrategySelected),
+                text!["Quantity: "],
+                text_input(&params.systematic_quantity, &params.systematic_quantity).width(PARAM_WIDTH).on_input(Message::SystematicQuantityChanged),
+                text!["Days to expiry: "],
+                text_input(&params.systematic_days, &params.systematic_days).width(PARAM_WIDTH).on_input(Message::SystematicDaysChanged),
+                text!["Roll days before expiry: "],
+                text_input(&params.systematic_roll_days, &params.systematic_roll_days).width(PARAM_WIDTH).on_input(Message::SystematicRollDaysChanged),
+            ].spacing(10),
+            row![
+                text!["Short delta: "],
+                text_input(&params.systematic_delta, &params.systematic_delta).width(PARAM_WIDTH).on_input(Message::SystematicDeltaChanged),
+                text!["Wing delta: "],
+                text_input(&params.systematic_wing_delta, &params.systematic_wing_delta).width(PARAM_WIDTH).on_input(Message::SystematicWingDeltaChanged),
+                text!["Profit take: "],
+                text_input("none", &params.profit_take).width(PARAM_WIDTH).on_input(Message::ProfitTakeChanged),
+                text!["Stop loss: "],
+                text_input("none", &params.stop_loss).width(PARAM_WIDTH).on_input(Message::StopLossChanged),
+                button("Backtest systematic strategy").on_press(Message::RunSystematicBacktest),
+            ].spacing(10),
+        ].spacing(5)
+    }
+
+    fn display_backtest(&self) -> Row<'_, Message> {
+        let params = &self.monte_carlo_params;
+        let mut results = column![
+            row![text!["Initial cash: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.backtest_initial_cash, &params.backtest_initial_cash).width(PARAM_WIDTH).on_input(Message::BacktestInitialCashChanged)],
+            row![text!["Commission per order: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.cost_commission_order, &params.cost_commission_order).width(PARAM_WIDTH).on_input(Message::CostCommissionOrderChanged)],
+            row![text!["Commission per contract: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.cost_commission, &params.cost_commission).width(PARAM_WIDTH).on_input(Message::CostCommissionChanged)],
+            row![text!["Shares per contract: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.cost_contract_size, &params.cost_contract_size).width(PARAM_WIDTH).on_input(Message::CostContractSizeChanged)],
+            row![text!["Fee rate on notional: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.cost_notional_rate, &params.cost_notional_rate).width(PARAM_WIDTH).on_input(Message::CostNotionalRateChanged)],
+            row![
+                text!["Bid-ask spread: "].width(PARAM_DESCRIPTION_WIDTH),
+                text_input(&params.cost_spread, &params.cost_spread).width(PARAM_WIDTH).on_input(Message::CostSpreadChanged),
+                pick_list(SpreadQuote::ALL, Some(params.cost_spread_quote), Message::CostSpreadQuoteSelected),
+            ].spacing(10),
+            row![text!["Slippage impact: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.cost_slippage_impact, &params.cost_slippage_impact).width(PARAM_WIDTH).on_input(Message::CostSlippageImpactChanged)],
+            button("Backtest strategy legs").on_press(Message::RunBacktest),
+        ].spacing(5);
+        let Some(report) = &self.backtest_report else {
+            return row![results];
+        };
+        let m = &report.metrics;
+        let costs = report.result.total_costs();
+        results = results.extend([
+            text!["Total return: {:.2}%  Annualised: {:.2}%", 100.0 * m.total_return, 100.0 * m.annualised_return].into(),
+            text!["Volatility: {:.2}%", 100.0 * m.volatility].into(),
+            text!["Sharpe: {}  Sortino: {}  Calmar: {:.2}", format_ratio(m.sharpe_ratio, 2), format_ratio(m.sortino_ratio, 2), m.calmar_ratio].into(),
+            text!["Max drawdown: {:.2}% over {} bars", 100.0 * m.max_drawdown, m.max_drawdown_duration].into(),
+            text!["Win rate: {:.1}%  Profit factor: {:.2}", 100.0 * m.win_rate, m.profit_factor].into(),
+            text!["Exposure: {:.1}%  Turnover: {:.2} per year", 100.0 * m.exposure, m.turnover].into(),
+            text!["Trades: {}", report.result.trades.len()].into(),
+            text!["Costs: commission {:.2}, fees {:.2}, spread {:.2}, slippage {:.2}", costs.commission, costs.fees, costs.spread, costs.slippage].into(),
+        ]);
+        row![results, canvas(report).width(EQUITY_WIDTH).height(EQUITY_HEIGHT)].spacing(20)
+    }
+
+    fn display_sweep(&self) -> Row<'_, Message> {
+        let params = &self.monte_carlo_params;
+        let mut inputs = column![
+            row![
+                text!["Rolling option: "].width(PARAM_DESCRIPTION_WIDTH),
+                pick_list(LegKind::ALL, Some(params.sweep_option_kind), Message::SweepOptionKindSelected),
+                text!["Quantity: "],
+                text_input(&params.sweep_quantity, &params.sweep_quantity).width(PARAM_WIDTH).on_input(Message::SweepQuantityChanged),
+            ].spacing(10),
+            row![text!["Strike offsets: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.sweep_strike_offsets, &params.sweep_strike_offsets).width(FILE_PATH_INPUT_WIDTH).on_input(Message::SweepStrikeOffsetsChanged)],
+            row![text!["Days to expiry: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.sweep_days_to_expiry, &params.sweep_days_to_expiry).width(FILE_PATH_INPUT_WIDTH).on_input(Message::SweepDaysToExpiryChanged)],
+            row![text!["Roll days before expiry: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.sweep_roll_days, &params.sweep_roll_days).width(FILE_PATH_INPUT_WIDTH).on_input(Message::SweepRollDaysChanged)],
+            row![
+                text!["In/out of sample bars: "].width(PARAM_DESCRIPTION_WIDTH),
+                text_input(&params.in_sample_bars, &params.in_sample_bars).width(PARAM_WIDTH).on_input(Message::InSampleBarsChanged),
+                text_input(&params.out_of_sample_bars, &params.out_of_sample_bars).width(PARAM_WIDTH).on_input(Message::OutOfSampleBarsChanged),
+            ].spacing(10),
+            row![
+                button("Sweep parameters").on_press(Message::RunSweep),
+                button("Walk forward").on_press(Message::RunWalkForward),
+            ].spacing(10),
+        ].spacing(5);
+        for window in &self.walk_forward_windows {
+            inputs = inputs.push(text![
+                "{} to {}: offset {:+.3}, {}d, roll {}d, in sample {}, out of sample {}",
+                window.out_of_sample_start, window.out_of_sample_end, window.chosen.strike_offset, window.chosen.days_to_expiry,
+                window.chosen.roll_days, format_ratio(window.in_sample_score, 2), format_ratio(window.out_of_sample_score, 2)
+            ]);
+        }
+        let mut content = row![inputs].spacing(20);
+        let Some(grid) = &self.sweep_grid else {
+            return content;
+        };
+
+        // Clicking a metric's header sorts the table by it and shows it on the heatmap
+        let mut header = row![text!["Offset"].width(PARAM_WIDTH), text!["Days"].width(PARAM_WIDTH), text!["Roll"].width(PARAM_WIDTH)].spacing(5);
+        for metric in SweepMetric::ALL {
+            header = header.push(button(text!["{}", metric]).width(PARAM_WIDTH + 30).on_press(Message::SweepMetricSelected(metric)));
+        }
+        let mut table = column![header].spacing(2);
+        for result in self.sweep_results.iter().take(SWEEP_TABLE_ROWS) {
+            let mut line = row![
+                text!["{:+.3}", result.point.strike_offset].width(PARAM_WIDTH),
+                text!["{}", result.point.days_to_expiry].width(PARAM_WIDTH),
+                text!["{}", result.point.roll_days].width(PARAM_WIDTH),
+            ].spacing(5);
+            for metric in SweepMetric::ALL {
+                line = line.push(text!["{}", format_ratio(metric.value(&result.metrics), 3)].width(PARAM_WIDTH + 30));
+            }
+            table = table.push(line);
+        }
+
+        let values = metric_grid(&self.sweep_results, grid, params.sweep_metric);
+        let low = values.iter().flatten().fold(f64::INFINITY, |a, &b| a.min(b));
+        let high = values.iter().flatten().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+        content = content.push(table).push(column![
+            text!["Mean {} over roll days", params.sweep_metric],
+            canvas(Heatmap {
+                row_labels: grid.strike_offsets.iter().map(|o| format!("{:+.3}", o)).collect(),
+                column_labels: grid.days_to_expiry.iter().map(|d| format!("{}d", d)).collect(),
+                values,
+                range: (low, high),
+            }).width(HEATMAP_SIZE).height(HEATMAP_SIZE),
+        ].spacing(5));
+        content
+    }
+
     fn display_monte_carlo_params(&self) -> Row<'_, Message> {
         let mut mc_result_text = String::from("");
         let mut mc_output = String::from("");
@@ -46,13 +366,22 @@ impl OptiRust {
                 row![text!["Implied volatility: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&self.monte_carlo_params.implied_vol, &self.monte_carlo_params.implied_vol).width(PARAM_WIDTH).on_input(Message::ImpliedVolChanged)],
                 row![text!["Risk free rate: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&self.monte_carlo_params.risk_free_rate, &self.monte_carlo_params.risk_free_rate).width(PARAM_WIDTH).on_input(Message::RiskFreeRateChanged)],
                 row![text!["Days to expire: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&self.monte_carlo_params.days_to_expire, &self.monte_carlo_params.days_to_expire).width(PARAM_WIDTH).on_input(Message::DaysToExpireChanged)],
+                row![text!["Valuation date: "].width(PARAM_DESCRIPTION_WIDTH), text_input(VALUATION_DATE_FORMAT, &self.valuation_date_text).width(PARAM_WIDTH).on_input(Message::ValuationDateChanged)],
+                row![text!["Dividend yield: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&self.monte_carlo_params.dividend_yield, &self.monte_carlo_params.dividend_yield).width(PARAM_WIDTH).on_input(Message::DividendYieldChanged)],
+                row![text!["Payoff: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(PayoffKind::ALL, Some(self.monte_carlo_params.payoff), Message::PayoffSelected)],
+                row![text!["Exercise: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(ExerciseStyle::ALL, Some(self.monte_carlo_params.exercise), Message::ExerciseSelected)],
+                row![text!["Day count: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(DayCount::ALL, Some(self.monte_carlo_params.day_count), Message::DayCountSelected)],
+                row![text!["Holiday calendar: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(self.calendars(), Some(self.monte_carlo_params.calendar.clone()), Message::CalendarSelected)],
 
                 row![text!["Number of simulations: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&self.monte_carlo_params.num_simulations, &self.monte_carlo_params.num_simulations).width(PARAM_WIDTH).on_input(Message::NumSimulationsChanged)],
                 row![text!["Number of steps: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&self.monte_carlo_params.num_steps, &self.monte_carlo_params.num_steps).width(PARAM_WIDTH).on_input(Message::NumStepsChanged)],
             ].spacing(5),
+            self.display_model_params(),
+            self.display_payoff_params(),
             column![
                 button("Run Monte Carlo").on_press(Message::RunMonteCarlo),
-                button("Calculate implied volatility").on_press(Message::UpdateParameters)
+                button("Calculate implied volatility").on_press(Message::UpdateParameters),
+                button("Use historical volatility").on_press(Message::UseHistoricalVol),
             ].spacing(10),
             rich_text![
                 span(mc_result_text).color(color!(0xff0000)),
@@ -62,6 +391,243 @@ impl OptiRust {
         ].spacing(20)
     }
 
+    fn display_model_params(&self) -> Column<'_, Message> {
+        let params = &self.monte_carlo_params;
+        let mut content = column![
+            row![text!["Model: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(SimulationModel::ALL, Some(params.model), Message::ModelSelected)],
+        ].spacing(5);
+        if params.model == SimulationModel::Gbm {
+            content = content.push(row![text!["Innovations: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(InnovationDistribution::ALL, Some(params.innovations), Message::InnovationsSelected)]);
+            if matches!(params.innovations, InnovationDistribution::StudentT | InnovationDistribution::SkewedT) {
+                content = content.push(row![text!["Degrees of freedom: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.innovation_nu, &params.innovation_nu).width(PARAM_WIDTH).on_input(Message::InnovationNuChanged)]);
+            }
+            if params.innovations == InnovationDistribution::SkewedT {
+                content = content.push(row![text!["Skew (lambda): "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.innovation_skew, &params.innovation_skew).width(PARAM_WIDTH).on_input(Message::InnovationSkewChanged)]);
+            }
+            if params.innovations == InnovationDistribution::Nig {
+                content = content.extend([
+                    row![text!["Tail heaviness (alpha): "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.nig_alpha, &params.nig_alpha).width(PARAM_WIDTH).on_input(Message::NigAlphaChanged)].into(),
+                    row![text!["Asymmetry (beta): "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.nig_beta, &params.nig_beta).width(PARAM_WIDTH).on_input(Message::NigBetaChanged)].into(),
+                ]);
+            }
+            if params.innovations != InnovationDistribution::Normal {
+                content = content.push(button("Fit to history").on_press(Message::FitInnovations));
+            }
+        }
+        if params.model == SimulationModel::Heston {
+            content = content.extend([
+                row![text!["Scheme: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(HestonScheme::ALL, Some(params.heston_scheme), Message::HestonSchemeSelected)].into(),
+                row![text!["Mean reversion (kappa): "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.heston_kappa, &params.heston_kappa).width(PARAM_WIDTH).on_input(Message::HestonKappaChanged)].into(),
+                row![text!["Long run variance (theta): "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.heston_theta, &params.heston_theta).width(PARAM_WIDTH).on_input(Message::HestonThetaChanged)].into(),
+                row![text!["Vol of variance (xi): "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.heston_xi, &params.heston_xi).width(PARAM_WIDTH).on_input(Message::HestonXiChanged)].into(),
+                row![text!["Correlation (rho): "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.heston_rho, &params.heston_rho).width(PARAM_WIDTH).on_input(Message::HestonRhoChanged)].into(),
+                row![text!["Initial variance (v0): "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.heston_v0, &params.heston_v0).width(PARAM_WIDTH).on_input(Message::HestonV0Changed)].into(),
+            ]);
+        }
+        if params.model == SimulationModel::Merton {
+            content = content.extend([
+                row![text!["Jumps per year: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.merton_lambda, &params.merton_lambda).width(PARAM_WIDTH).on_input(Message::MertonLambdaChanged)].into(),
+                row![text!["Mean log jump: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.merton_mu_jump, &params.merton_mu_jump).width(PARAM_WIDTH).on_input(Message::MertonMuJumpChanged)].into(),
+                row![text!["Log jump volatility: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.merton_sigma_jump, &params.merton_sigma_jump).width(PARAM_WIDTH).on_input(Message::MertonSigmaJumpChanged)].into(),
+            ]);
+        }
+        if params.model == SimulationModel::Kou {
+            content = content.extend([
+                row![text!["Jumps per year: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.kou_lambda, &params.kou_lambda).width(PARAM_WIDTH).on_input(Message::KouLambdaChanged)].into(),
+                row![text!["Up jump probability: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.kou_p_up, &params.kou_p_up).width(PARAM_WIDTH).on_input(Message::KouPUpChanged)].into(),
+                row![text!["Up jump rate: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.kou_eta_up, &params.kou_eta_up).width(PARAM_WIDTH).on_input(Message::KouEtaUpChanged)].into(),
+                row![text!["Down jump rate: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.kou_eta_down, &params.kou_eta_down).width(PARAM_WIDTH).on_input(Message::KouEtaDownChanged)].into(),
+            ]);
+        }
+        if params.model == SimulationModel::Sabr {
+            content = content.extend([
+                row![text!["Initial vol (alpha): "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.sabr_alpha, &params.sabr_alpha).width(PARAM_WIDTH).on_input(Message::SabrAlphaChanged)].into(),
+                row![text!["CEV exponent (beta): "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.sabr_beta, &params.sabr_beta).width(PARAM_WIDTH).on_input(Message::SabrBetaChanged)].into(),
+                row![text!["Correlation (rho): "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.sabr_rho, &params.sabr_rho).width(PARAM_WIDTH).on_input(Message::SabrRhoChanged)].into(),
+                row![text!["Vol of vol (nu): "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.sabr_nu, &params.sabr_nu).width(PARAM_WIDTH).on_input(Message::SabrNuChanged)].into(),
+                button("Use SABR vol for strike").on_press(Message::UseSabrVol).into(),
+            ]);
+        }
+        if params.model == SimulationModel::Bootstrap {
+            content = content.push(row![text!["Resampling: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(BootstrapScheme::ALL, Some(params.bootstrap_scheme), Message::BootstrapSchemeSelected)]);
+            if params.bootstrap_scheme != BootstrapScheme::Iid {
+                content = content.push(row![text!["Block length (bars): "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.bootstrap_block_length, &params.bootstrap_block_length).width(PARAM_WIDTH).on_input(Message::BootstrapBlockLengthChanged)]);
+            }
+            content = content.push(row![text!["Target vol (optional): "].width(PARAM_DESCRIPTION_WIDTH), text_input("Historical", &params.bootstrap_target_vol).width(PARAM_WIDTH).on_input(Message::BootstrapTargetVolChanged)]);
+        }
+        if matches!(params.model, SimulationModel::Merton | SimulationModel::Kou) {
+            content = content.push(button("Estimate jumps from history").on_press(Message::EstimateJumps));
+        }
+        content
+    }
+
+    fn display_payoff_params(&self) -> Column<'_, Message> {
+        let params = &self.monte_carlo_params;
+        let mut content = column![].spacing(5);
+        if params.payoff == PayoffKind::Barrier {
+            content = content.extend([
+                row![text!["Barrier direction: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(BarrierDirection::ALL, Some(params.barrier_direction), Message::BarrierDirectionSelected)].into(),
+                row![text!["Barrier type: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(BarrierKnock::ALL, Some(params.barrier_knock), Message::BarrierKnockSelected)].into(),
+                row![text!["Barrier level: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.barrier_level, &params.barrier_level).width(PARAM_WIDTH).on_input(Message::BarrierLevelChanged)].into(),
+                row![text!["Rebate: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.barrier_rebate, &params.barrier_rebate).width(PARAM_WIDTH).on_input(Message::BarrierRebateChanged)].into(),
+                checkbox("Brownian bridge correction", params.brownian_bridge).on_toggle(Message::BrownianBridgeToggled).into(),
+            ]);
+        }
+        if params.payoff == PayoffKind::Asian {
+            content = content.extend([
+                row![text!["Average: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(AsianAverage::ALL, Some(params.asian_average), Message::AsianAverageSelected)].into(),
+                row![text!["Strike: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(AsianStrike::ALL, Some(params.asian_strike), Message::AsianStrikeSelected)].into(),
+                row![text!["Averaging days: "].width(PARAM_DESCRIPTION_WIDTH), text_input("every step", &params.averaging_days).width(PARAM_DESCRIPTION_WIDTH).on_input(Message::AveragingDaysChanged)].into(),
+            ]);
+            if let Some(estimate) = &self.asian_estimate {
+                content = content.extend([
+                    text!["Plain: {:.4} ± {:.4}", estimate.plain_price, estimate.plain_std_error].into(),
+                    text!["Control variate: {:.4} ± {:.4}", estimate.price, estimate.std_error].into(),
+                    text!["Variance reduction: {:.1}x", estimate.variance_reduction()].into(),
+                ]);
+            }
+        }
+        if params.payoff == PayoffKind::Lookback {
+            content = content.push(row![text!["Strike: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(LookbackStrike::ALL, Some(params.lookback_strike), Message::LookbackStrikeSelected)]);
+        }
+        if params.payoff == PayoffKind::Digital {
+            content = content.extend([
+                row![text!["Digital type: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(DigitalKind::ALL, Some(params.digital_kind), Message::DigitalKindSelected)].into(),
+                row![text!["Cash amount: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.digital_cash, &params.digital_cash).width(PARAM_WIDTH).on_input(Message::DigitalCashChanged)].into(),
+                row![text!["Smoothing width: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.digital_smoothing, &params.digital_smoothing).width(PARAM_WIDTH).on_input(Message::DigitalSmoothingChanged)].into(),
+            ]);
+        }
+        if params.payoff == PayoffKind::Gap {
+            content = content.push(row![text!["Trigger: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.gap_trigger, &params.gap_trigger).width(PARAM_WIDTH).on_input(Message::GapTriggerChanged)]);
+        }
+        if let Some(price) = self.monte_carlo_pricing.closed_form_price(self.chart.underlying_price()) {
+            content = content.push(text!["Closed form: {:.4}", price]);
+        }
+        content
+    }
+
+    fn display_multi_asset(&self) -> Row<'_, Message> {
+        let params = &self.monte_carlo_params;
+        let mut results = column![button("Price multi-asset option").on_press(Message::PriceMultiAsset)].spacing(5);
+        if let Some(estimate) = &self.multi_asset_estimate {
+            results = results.push(text!["Monte Carlo: {:.4} ± {:.4}", estimate.price, estimate.std_error]);
+            if let Some(price) = estimate.closed_form {
+                results = results.push(text!["Kirk / Margrabe: {:.4}", price]);
+            }
+            if let Some(repaired) = &estimate.repaired_correlation {
+                let rows: Vec<String> = repaired.iter().map(|row| row.iter().map(|c| format!("{:.3}", c)).collect::<Vec<_>>().join(", ")).collect();
+                results = results.push(text!["Correlation was not positive definite, used: {}", rows.join("; ")]);
+            }
+        }
+        row![
+            column![
+                row![text!["Multi-asset payoff: "].width(PARAM_DESCRIPTION_WIDTH), pick_list(MultiAssetPayoff::ALL, Some(params.multi_asset_payoff), Message::MultiAssetPayoffSelected)],
+                row![text!["Asset spots: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.asset_spots, &params.asset_spots).width(FILE_PATH_INPUT_WIDTH).on_input(Message::AssetSpotsChanged)],
+                row![text!["Asset volatilities: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.asset_vols, &params.asset_vols).width(FILE_PATH_INPUT_WIDTH).on_input(Message::AssetVolsChanged)],
+                row![text!["Asset dividend yields: "].width(PARAM_DESCRIPTION_WIDTH), text_input(&params.asset_dividend_yields, &params.asset_dividend_yields).width(FILE_PATH_INPUT_WIDTH).on_input(Message::AssetDividendYieldsChanged)],
+            ].spacing(5),
+            column![
+                row![text!["Basket weights: "].width(PARAM_DESCRIPTION_WIDTH), text_input("equal weights", &params.basket_weights).width(FILE_PATH_INPUT_WIDTH).on_input(Message::BasketWeightsChanged)],
+                row![text!["Correlation matrix: "].width(PARAM_DESCRIPTION_WIDTH), text_input("1, 0.5; 0.5, 1", &params.correlation).width(FILE_PATH_INPUT_WIDTH).on_input(Message::CorrelationChanged)],
+            ].spacing(5),
+            results,
+            self.display_correlation_estimate(),
+        ].spacing(20)
+    }
+
+    fn display_correlation_estimate(&self) -> Column<'_, Message> {
+        let params = &self.monte_carlo_params;
+        let mut content = column![
+            row![
+                text_input("Tickers, comma separated", &self.tickers_text).width(FILE_PATH_INPUT_WIDTH).on_input(Message::TickersChanged),
+                button("Import tickers").on_press(Message::ImportTickers),
+            ].spacing(10),
+            row![
+                pick_list(CovarianceEstimator::ALL, Some(params.covariance_estimator), Message::CovarianceEstimatorSelected),
+                text!["EWMA decay: "],
+                text_input(&params.ewma_decay, &params.ewma_decay).width(PARAM_WIDTH).on_input(Message::EwmaDecayChanged),
+            ].spacing(10),
+        ].spacing(5);
+        if let Some(estimate) = &self.correlation_estimate {
+            let vols: Vec<String> = estimate.volatilities().iter().map(|v| format!("{:.3}", v)).collect();
+            content = content.push(canvas(Heatmap {
+                row_labels: estimate.tickers.clone(),
+                column_labels: estimate.tickers.clone(),
+                values: estimate.correlation.clone(),
+                range: (-1.0, 1.0),
+            }).width(HEATMAP_SIZE).height(HEATMAP_SIZE));
+            content = content.push(text!["{} common returns, volatilities: {}", estimate.observations, vols.join(", ")]);
+            content = content.push(text!["Annualised covariance:"]);
+            for (ticker, row) in estimate.tickers.iter().zip(&estimate.covariance) {
+                let cells: Vec<String> = row.iter().map(|c| format!("{:.5}", c)).collect();
+                content = content.push(text!["{}: {}", ticker, cells.join(", ")]);
+            }
+            if let Some(shrinkage) = estimate.shrinkage {
+                content = content.push(text!["Shrinkage intensity: {:.3}", shrinkage]);
+            }
+            content = content.push(button("Use as multi-asset input").on_press(Message::UseCorrelationEstimate));
+        }
+        content
+    }
+
+    fn display_calibration(&self) -> Column<'_, Message> {
+        let mut content = column![
+            row![
+                text_input("Option chain CSV", &self.option_chain_path).width(FILE_PATH_INPUT_WIDTH).on_input(Message::OptionChainPathChanged),
+                button("Load option chain").on_press(Message::LoadOptionChain),
+                text!["{} quotes loaded", self.option_chain.len()],
+                pick_list(CalibrationTarget::ALL, Some(self.calibration_target), Message::CalibrationTargetSelected),
+                button("Calibrate Heston").on_press(Message::CalibrateHeston),
+                button("Calibrate SABR").on_press(Message::CalibrateSabr),
+            ].spacing(10),
+            row![
+                text_input("Calibration file", &self.calibration_path).width(FILE_PATH_INPUT_WIDTH).on_input(Message::CalibrationPathChanged),
+                button("Save calibration").on_press(Message::SaveCalibration),
+                button("Load calibration").on_press(Message::LoadCalibration),
+            ].spacing(10),
+            row![
+                text_input("Implied vol surface CSV", &self.vol_surface_path).width(FILE_PATH_INPUT_WIDTH).on_input(Message::VolSurfacePathChanged),
+                button("Load vol surface").on_press(Message::LoadVolSurface),
+                text!["{}", if self.implied_surface.is_some() { "Surface loaded" } else { "No surface loaded" }],
+            ].spacing(10),
+            row![
+                text_input("Dividend schedule CSV", &self.dividends_path).width(FILE_PATH_INPUT_WIDTH).on_input(Message::DividendsPathChanged),
+                button("Load dividends").on_press(Message::LoadDividends),
+                text!["{} dividends loaded", self.dividend_schedule.dividends.len()],
+            ].spacing(10),
+            row![
+                text_input("Yield curve CSV", &self.yield_curve_path).width(FILE_PATH_INPUT_WIDTH).on_input(Message::YieldCurvePathChanged),
+                button("Load yield curve").on_press(Message::LoadYieldCurve),
+                pick_list(CurveInterpolation::ALL, Some(self.monte_carlo_params.curve_interpolation), Message::CurveInterpolationSelected),
+                pick_list(InterpolatedQuantity::ALL, Some(self.monte_carlo_params.curve_quantity), Message::CurveQuantitySelected),
+                text!["{}", match &self.yield_curve {
+                    Some(curve) => format!("{} pillars loaded", curve.tenors.len()),
+                    None => String::from("Flat at the risk free rate"),
+                }],
+            ].spacing(10),
+            row![
+                text_input("Holiday calendar file", &self.calendar_path).width(FILE_PATH_INPUT_WIDTH).on_input(Message::CalendarPathChanged),
+                button("Load calendar").on_press(Message::LoadCalendar),
+            ].spacing(10),
+        ].spacing(5);
+        if let Some(result) = &self.calibration_result {
+            let p = &result.params;
+            let residuals: Vec<String> = result.residuals.iter().map(|r| format!("{:.4}", r)).collect();
+            content = content.extend([
+                text!["kappa: {:.4}  theta: {:.4}  xi: {:.4}  rho: {:.4}  v0: {:.4}  RMSE: {:.5}", p.kappa, p.theta, p.xi, p.rho, p.v0, result.rmse].into(),
+                text!["Residuals: {}", residuals.join(", ")].into(),
+            ]);
+        }
+        for slice in &self.sabr_calibrations {
+            let p = &slice.params;
+            content = content.push(text![
+                "SABR {:.3}y  alpha: {:.4}  beta: {:.2}  rho: {:.4}  nu: {:.4}  RMSE: {:.5}",
+                slice.years_to_expire, p.alpha, p.beta, p.rho, p.nu, slice.rmse
+            ]);
+        }
+        content
+    }
+
     fn display_api_key_input(&self) -> Container<'_, Message> {
         container(
             row![
@@ -109,3 +675,8 @@ where
     ]
     .into()
 }
+
+/// A metric to `decimals` places, or "n/a" where it is undefined.
+fn format_ratio(value: Option<f64>, decimals: usize) -> String {
+    value.map_or(String::from("n/a"), |v| format!("{:.*}", decimals, v))
+}
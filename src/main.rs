@@ -1,11 +1,9 @@
-mod gui;
-mod model;
-
-use iced::Theme;
-use crate::model::application::OptiRust;
+use chrono::Local;
+use iced::{Task, Theme};
+use opti_rust::model::application::OptiRust;
 
 fn main() -> iced::Result {
     iced::application("OptiRust", OptiRust::update, OptiRust::view)
     .theme(|_| {Theme::TokyoNight})
-    .run()
-}
\ No newline at end of file
+    .run_with(|| (OptiRust::new(Local::now().date_naive()), Task::none()))
+}
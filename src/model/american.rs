@@ -0,0 +1,93 @@
+use super::monte_carlo::MonteCarloPricing;
+use super::optimize::least_squares;
+
+const MIN_REGRESSION_PATHS: usize = 3;
+
+impl MonteCarloPricing {
+    /// Prices an American call on pre-simulated paths with the Longstaff-Schwartz algorithm: at every step
+    /// the continuation value of in-the-money paths is regressed on a cubic in moneyness, and the option
+    /// is exercised where the intrinsic value beats it. The paths already carry the ex-date spot drops, so
+    /// exercising just before a dividend is captured by the step preceding the ex-date.
+    pub fn longstaff_schwartz(&self, paths: &[Vec<f64>]) -> f64 {
+        if paths.is_empty() {
+            return 0.0;
+        }
+        let steps = paths[0].len() - 1;
+        let dt = self.years_to_expire / steps as f64;
+        let curve = self.curve();
+        let step_discount = |step: usize| (-curve.forward_rate(step as f64 * dt, (step + 1) as f64 * dt) * dt).exp();
+        let intrinsic = |s: f64| (s - self.strike_price).max(0.0);
+
+        let mut cash_flows: Vec<f64> = paths.iter().map(|p| intrinsic(p[steps])).collect();
+        for step in (1..steps).rev() {
+            let discount = step_discount(step);
+            cash_flows.iter_mut().for_each(|cf| *cf *= discount);
+
+            let in_the_money: Vec<usize> = (0..paths.len()).filter(|&i| intrinsic(paths[i][step]) > 0.0).collect();
+            if in_the_money.len() < MIN_REGRESSION_PATHS {
+                continue;
+            }
+            let basis = |s: f64| {
+                let x = s / self.strike_price;
+                vec![1.0, x, x * x, x * x * x]
+            };
+            let design: Vec<Vec<f64>> = in_the_money.iter().map(|&i| basis(paths[i][step])).collect();
+            let continuation: Vec<f64> = in_the_money.iter().map(|&i| cash_flows[i]).collect();
+            let Some(coefficients) = least_squares(&design, &continuation) else {
+                continue;
+            };
+
+            for (&i, row) in in_the_money.iter().zip(&design) {
+                let expected: f64 = row.iter().zip(&coefficients).map(|(b, c)| b * c).sum();
+                let exercise = intrinsic(paths[i][step]);
+                if exercise > expected {
+                    cash_flows[i] = exercise;
+                }
+            }
+        }
+
+        let held = cash_flows.iter().sum::<f64>() / cash_flows.len() as f64 * step_discount(0);
+        held.max(intrinsic(paths[0][0]))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::model::calendar::Calendar;
+    use crate::model::chart::PriceChart;
+    use crate::model::dividends::{Dividend, DividendKind, DividendSchedule};
+    use crate::model::monte_carlo::{ExerciseStyle, MonteCarloPricing};
+
+    fn pricing(exercise: ExerciseStyle, dividends: DividendSchedule) -> MonteCarloPricing {
+        MonteCarloPricing {
+            current_asset_price: 100.0,
+            strike_price: 90.0,
+            num_simulations: 20000,
+            num_steps: 20,
+            risk_free_rate: 0.03,
+            implied_vol: 0.2,
+            years_to_expire: 1.0,
+            dividends,
+            exercise,
+            ..MonteCarloPricing::default()
+        }
+    }
+
+    #[test]
+    fn test_american_call_without_dividends_is_european() {
+        let chart = PriceChart::from_prices_and_date(vec![100.0], chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), &Calendar::Nyse);
+        let american = pricing(ExerciseStyle::American, DividendSchedule::default()).price(&chart).unwrap();
+        let european = pricing(ExerciseStyle::European, DividendSchedule::default()).black_scholes_call_price(0.2);
+        assert!((american - european).abs() < 0.3, "{american} vs {european}");
+    }
+
+    #[test]
+    fn test_large_dividend_makes_early_exercise_valuable() {
+        let chart = PriceChart::from_prices_and_date(vec![100.0], chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), &Calendar::Nyse);
+        let schedule = DividendSchedule::new(vec![Dividend { years_to_ex_date: 0.52, amount: 15.0, kind: DividendKind::Cash }]);
+        let american = pricing(ExerciseStyle::American, schedule.clone()).price(&chart).unwrap();
+        let european = pricing(ExerciseStyle::European, schedule).price(&chart).unwrap();
+        assert!(american > european + 1.0, "{american} vs {european}");
+    }
+}
@@ -0,0 +1,412 @@
+use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+use std::fmt;
+use std::ops::{Add, Mul};
+
+use super::asian::{AsianAverage, AsianOption, AsianStrike};
+use super::barrier::{BarrierDirection, BarrierKnock, BarrierOption};
+use super::exotics::{DigitalKind, DigitalOption, GapOption, LookbackStrike};
+use super::monte_carlo::{MonteCarloPricing, PayoffKind, SimulationModel};
+use super::multi_asset::Asset;
+
+const MIN_LOOKBACK_CARRY: f64 = 1e-6;
+
+fn norm_cdf(x: f64) -> f64 {
+    Normal::new(0.0, 1.0).unwrap().cdf(x)
+}
+
+fn norm_pdf(x: f64) -> f64 {
+    Normal::new(0.0, 1.0).unwrap().pdf(x)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OptionType {
+    #[default]
+    Call,
+    Put,
+}
+
+impl OptionType {
+    pub const ALL: [OptionType; 2] = [OptionType::Call, OptionType::Put];
+}
+
+impl fmt::Display for OptionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptionType::Call => write!(f, "Call"),
+            OptionType::Put => write!(f, "Put"),
+        }
+    }
+}
+
+/// Value and sensitivities of a position. Theta is per year and vega and rho per unit change.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Greeks {
+    pub price: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub theta: f64,
+    pub rho: f64,
+}
+
+impl Add for Greeks {
+    type Output = Greeks;
+
+    fn add(self, other: Greeks) -> Greeks {
+        Greeks {
+            price: self.price + other.price,
+            delta: self.delta + other.delta,
+            gamma: self.gamma + other.gamma,
+            vega: self.vega + other.vega,
+            theta: self.theta + other.theta,
+            rho: self.rho + other.rho,
+        }
+    }
+}
+
+impl Mul<f64> for Greeks {
+    type Output = Greeks;
+
+    fn mul(self, quantity: f64) -> Greeks {
+        Greeks {
+            price: self.price * quantity,
+            delta: self.delta * quantity,
+            gamma: self.gamma * quantity,
+            vega: self.vega * quantity,
+            theta: self.theta * quantity,
+            rho: self.rho * quantity,
+        }
+    }
+}
+
+/// Generalised Black-Scholes price and Greeks, with `carry` the cost of carry. At expiry only the
+/// intrinsic value and its delta remain.
+pub fn black_scholes_greeks(option_type: OptionType, spot: f64, strike: f64, rate: f64, carry: f64, vol: f64, years: f64) -> Greeks {
+    let sign = match option_type {
+        OptionType::Call => 1.0,
+        OptionType::Put => -1.0,
+    };
+    if years <= 0.0 || vol <= 0.0 {
+        let intrinsic = (sign * (spot - strike)).max(0.0);
+        return Greeks { price: intrinsic, delta: if intrinsic > 0.0 { sign } else { 0.0 }, ..Greeks::default() };
+    }
+    let sd = vol * years.sqrt();
+    let d1 = d1(spot, strike, carry, vol, years);
+    let d2 = d1 - sd;
+    let spot_discount = ((carry - rate) * years).exp();
+    let strike_discount = (-rate * years).exp();
+    let density = spot * spot_discount * norm_pdf(d1);
+    Greeks {
+        price: sign * (spot * spot_discount * norm_cdf(sign * d1) - strike * strike_discount * norm_cdf(sign * d2)),
+        delta: sign * spot_discount * norm_cdf(sign * d1),
+        gamma: density / (spot.powi(2) * sd),
+        vega: density * years.sqrt(),
+        theta: -density * vol / (2.0 * years.sqrt()) - sign * (carry - rate) * spot * spot_discount * norm_cdf(sign * d1)
+            - sign * rate * strike * strike_discount * norm_cdf(sign * d2),
+        rho: sign * strike * years * strike_discount * norm_cdf(sign * d2),
+    }
+}
+
+/// Strike at which the option's delta has the absolute value `delta`, found by bisection on the log
+/// strike since the delta is monotone in the strike. Targets beyond the attainable deltas give the
+/// nearest end of a ten standard deviation range.
+pub fn strike_for_delta(option_type: OptionType, delta: f64, spot: f64, rate: f64, carry: f64, vol: f64, years: f64) -> f64 {
+    let sd = vol * years.max(0.0).sqrt();
+    if sd <= 0.0 {
+        return spot;
+    }
+    let (mut low, mut high) = (spot.ln() - 10.0 * sd, spot.ln() + 10.0 * sd);
+    for _ in 0..100 {
+        let mid = 0.5 * (low + high);
+        let current = black_scholes_greeks(option_type, spot, mid.exp(), rate, carry, vol, years).delta.abs();
+        // Call deltas fall as the strike rises, put deltas grow
+        let strike_too_low = match option_type {
+            OptionType::Call => current > delta,
+            OptionType::Put => current < delta,
+        };
+        if strike_too_low { low = mid } else { high = mid }
+    }
+    (0.5 * (low + high)).exp()
+}
+
+/// Reiner-Rubinstein price of a continuously monitored barrier call, with `carry` the cost of carry
+/// (rate net of the dividend yield). Assumes the barrier has not been breached yet.
+pub fn barrier_call(barrier: &BarrierOption, spot: f64, strike: f64, rate: f64, carry: f64, vol: f64, years: f64) -> f64 {
+    let h = barrier.level;
+    let sd = vol * years.sqrt();
+    let mu = (carry - 0.5 * vol.powi(2)) / vol.powi(2);
+    let lambda = (mu.powi(2) + 2.0 * rate / vol.powi(2)).sqrt();
+    let eta = match barrier.direction {
+        BarrierDirection::Down => 1.0,
+        BarrierDirection::Up => -1.0,
+    };
+    let spot_discount = ((carry - rate) * years).exp();
+    let strike_discount = (-rate * years).exp();
+
+    let x1 = (spot / strike).ln() / sd + (1.0 + mu) * sd;
+    let x2 = (spot / h).ln() / sd + (1.0 + mu) * sd;
+    let y1 = (h.powi(2) / (spot * strike)).ln() / sd + (1.0 + mu) * sd;
+    let y2 = (h / spot).ln() / sd + (1.0 + mu) * sd;
+    let z = (h / spot).ln() / sd + lambda * sd;
+    let ratio = h / spot;
+
+    let a = spot * spot_discount * norm_cdf(x1) - strike * strike_discount * norm_cdf(x1 - sd);
+    let b = spot * spot_discount * norm_cdf(x2) - strike * strike_discount * norm_cdf(x2 - sd);
+    let c = spot * spot_discount * ratio.powf(2.0 * (mu + 1.0)) * norm_cdf(eta * y1)
+        - strike * strike_discount * ratio.powf(2.0 * mu) * norm_cdf(eta * y1 - eta * sd);
+    let d = spot * spot_discount * ratio.powf(2.0 * (mu + 1.0)) * norm_cdf(eta * y2)
+        - strike * strike_discount * ratio.powf(2.0 * mu) * norm_cdf(eta * y2 - eta * sd);
+    // Rebate paid at expiry when an in option never knocks in, and at the hit for an out option
+    let e = barrier.rebate * strike_discount * (norm_cdf(eta * x2 - eta * sd) - ratio.powf(2.0 * mu) * norm_cdf(eta * y2 - eta * sd));
+    let f = barrier.rebate * (ratio.powf(mu + lambda) * norm_cdf(eta * z) + ratio.powf(mu - lambda) * norm_cdf(eta * z - 2.0 * eta * lambda * sd));
+
+    let above_barrier = strike > h;
+    match (barrier.direction, barrier.knock) {
+        (BarrierDirection::Down, BarrierKnock::In) if above_barrier => c + e,
+        (BarrierDirection::Down, BarrierKnock::In) => a - b + d + e,
+        (BarrierDirection::Up, BarrierKnock::In) if above_barrier => a + e,
+        (BarrierDirection::Up, BarrierKnock::In) => b - c + d + e,
+        (BarrierDirection::Down, BarrierKnock::Out) if above_barrier => a - c + f,
+        (BarrierDirection::Down, BarrierKnock::Out) => b - d + f,
+        (BarrierDirection::Up, BarrierKnock::Out) if above_barrier => f,
+        (BarrierDirection::Up, BarrierKnock::Out) => a - b + c - d + f,
+    }
+}
+
+
+/// Price of a discretely sampled geometric Asian call averaging the spot at the option's averaging
+/// times, under GBM with cost of carry `carry`. The log of the geometric average is normal, which gives
+/// a Black-Scholes type formula for the fixed strike and a Margrabe type formula against the final spot
+/// for the floating one.
+pub fn geometric_asian_call(asian: &AsianOption, spot: f64, strike: f64, rate: f64, carry: f64, vol: f64, years: f64) -> f64 {
+    let times = &asian.averaging_times;
+    let n = times.len() as f64;
+    let mean_time = times.iter().sum::<f64>() / n;
+    let log_mean = spot.ln() + (carry - 0.5 * vol.powi(2)) * mean_time;
+    let log_variance = vol.powi(2) * times.iter().map(|ti| times.iter().map(|tj| ti.min(*tj)).sum::<f64>()).sum::<f64>() / n.powi(2);
+    let expected_average = (log_mean + 0.5 * log_variance).exp();
+    let discount = (-rate * years).exp();
+
+    match asian.strike {
+        AsianStrike::Fixed => {
+            let sd = log_variance.sqrt();
+            if sd == 0.0 {
+                return discount * (expected_average - strike).max(0.0);
+            }
+            let d1 = (log_mean - strike.ln() + log_variance) / sd;
+            discount * (expected_average * norm_cdf(d1) - strike * norm_cdf(d1 - sd))
+        }
+        AsianStrike::Floating => {
+            let expected_spot = spot * (carry * years).exp();
+            let covariance = vol.powi(2) * times.iter().map(|t| t.min(years)).sum::<f64>() / n;
+            let sd = (vol.powi(2) * years + log_variance - 2.0 * covariance).max(0.0).sqrt();
+            if sd == 0.0 {
+                return discount * (expected_spot - expected_average).max(0.0);
+            }
+            let d1 = ((expected_spot / expected_average).ln() + 0.5 * sd.powi(2)) / sd;
+            discount * (expected_spot * norm_cdf(d1) - expected_average * norm_cdf(d1 - sd))
+        }
+    }
+}
+
+
+fn d1(spot: f64, strike: f64, carry: f64, vol: f64, years: f64) -> f64 {
+    ((spot / strike).ln() + (carry + 0.5 * vol.powi(2)) * years) / (vol * years.sqrt())
+}
+
+/// Continuously monitored lookback call struck at inception, so the running extremum is the spot:
+/// Goldman-Sosin-Gatto for the floating strike and Conze-Viswanathan for the fixed one. Their
+/// reflection terms divide by the cost of carry, so near zero carry their limits are used instead.
+pub fn lookback_call(strike_type: LookbackStrike, spot: f64, strike: f64, rate: f64, carry: f64, vol: f64, years: f64) -> f64 {
+    let sd = vol * years.sqrt();
+    let spot_discount = ((carry - rate) * years).exp();
+    let strike_discount = (-rate * years).exp();
+    let zero_carry = carry.abs() < MIN_LOOKBACK_CARRY;
+    let reflection = spot * strike_discount * vol.powi(2) / (2.0 * carry);
+    match strike_type {
+        LookbackStrike::Floating => {
+            let a1 = d1(spot, spot, carry, vol, years);
+            let reflected = if zero_carry {
+                spot * strike_discount * (sd * norm_pdf(a1) - 0.5 * sd * sd * norm_cdf(-a1))
+            } else {
+                reflection * (norm_cdf(-a1 + 2.0 * carry * years.sqrt() / vol) - (carry * years).exp() * norm_cdf(-a1))
+            };
+            spot * spot_discount * norm_cdf(a1) - spot * strike_discount * norm_cdf(a1 - sd) + reflected
+        }
+        LookbackStrike::Fixed => {
+            // Above the running maximum the option is a call plus the reflection term, below it also
+            // locks in the maximum over the strike
+            let level = strike.max(spot);
+            let e1 = d1(spot, level, carry, vol, years);
+            let intrinsic = (spot - strike).max(0.0) * strike_discount;
+            let reflected = if zero_carry {
+                spot * strike_discount * (((spot / level).ln() + 0.5 * sd * sd) * norm_cdf(e1) + sd * norm_pdf(e1))
+            } else {
+                reflection * (-(spot / level).powf(-2.0 * carry / vol.powi(2)) * norm_cdf(e1 - 2.0 * carry * years.sqrt() / vol)
+                    + (carry * years).exp() * norm_cdf(e1))
+            };
+            intrinsic + spot * spot_discount * norm_cdf(e1) - level * strike_discount * norm_cdf(e1 - sd) + reflected
+        }
+    }
+}
+
+/// Cash-or-nothing or asset-or-nothing call paying when the spot ends above the strike.
+pub fn digital_call(digital: &DigitalOption, spot: f64, strike: f64, rate: f64, carry: f64, vol: f64, years: f64) -> f64 {
+    let d1 = d1(spot, strike, carry, vol, years);
+    match digital.kind {
+        DigitalKind::CashOrNothing => digital.cash * (-rate * years).exp() * norm_cdf(d1 - vol * years.sqrt()),
+        DigitalKind::AssetOrNothing => spot * ((carry - rate) * years).exp() * norm_cdf(d1),
+    }
+}
+
+pub fn gap_call(gap: &GapOption, spot: f64, strike: f64, rate: f64, carry: f64, vol: f64, years: f64) -> f64 {
+    let d1 = d1(spot, gap.trigger, carry, vol, years);
+    spot * ((carry - rate) * years).exp() * norm_cdf(d1) - strike * (-rate * years).exp() * norm_cdf(d1 - vol * years.sqrt())
+}
+
+/// Margrabe price of the option to exchange the second asset for the first at expiry.
+pub fn margrabe_exchange(first: &Asset, second: &Asset, rho: f64, years: f64) -> f64 {
+    let vol = (first.vol.powi(2) - 2.0 * rho * first.vol * second.vol + second.vol.powi(2)).sqrt();
+    let sd = vol * years.sqrt();
+    let s1 = first.spot * (-first.dividend_yield * years).exp();
+    let s2 = second.spot * (-second.dividend_yield * years).exp();
+    let d1 = ((s1 / s2).ln() + 0.5 * sd.powi(2)) / sd;
+    s1 * norm_cdf(d1) - s2 * norm_cdf(d1 - sd)
+}
+
+/// Kirk's approximation for a call on the first asset less the second, treating the second asset plus
+/// the strike as lognormal. Reduces to Margrabe for a zero strike.
+pub fn kirk_spread_call(first: &Asset, second: &Asset, strike: f64, rate: f64, rho: f64, years: f64) -> f64 {
+    let f1 = first.spot * ((rate - first.dividend_yield) * years).exp();
+    let f2 = second.spot * ((rate - second.dividend_yield) * years).exp();
+    let ratio = f2 / (f2 + strike);
+    let vol = (first.vol.powi(2) - 2.0 * rho * first.vol * second.vol * ratio + (second.vol * ratio).powi(2)).sqrt();
+    let sd = vol * years.sqrt();
+    let d1 = ((f1 / (f2 + strike)).ln() + 0.5 * sd.powi(2)) / sd;
+    (-rate * years).exp() * (f1 * norm_cdf(d1) - (f2 + strike) * norm_cdf(d1 - sd))
+}
+
+impl MonteCarloPricing {
+    /// Closed-form reference for the selected payoff, when the simulation matches the Black-Scholes
+    /// assumptions: GBM on a flat curve without discrete dividends. Arithmetic Asians have none.
+    pub fn closed_form_price(&self, spot: f64) -> Option<f64> {
+        if self.model != SimulationModel::Gbm || self.yield_curve.is_some() || !self.dividends.is_empty() {
+            return None;
+        }
+        let (strike, rate, carry, vol, years) = (self.strike_price, self.risk_free_rate, self.carry(), self.implied_vol, self.years_to_expire);
+        match self.payoff {
+            PayoffKind::Vanilla => Some(gap_call(&GapOption { trigger: strike }, spot, strike, rate, carry, vol, years)),
+            PayoffKind::Barrier => Some(barrier_call(&self.barrier, spot, strike, rate, carry, vol, years)),
+            PayoffKind::Asian => match self.asian.average {
+                AsianAverage::Geometric => self.geometric_asian_price(spot),
+                AsianAverage::Arithmetic => None,
+            },
+            PayoffKind::Lookback => Some(lookback_call(self.lookback, spot, strike, rate, carry, vol, years)),
+            PayoffKind::Digital => Some(digital_call(&self.digital, spot, strike, rate, carry, vol, years)),
+            PayoffKind::Gap => Some(gap_call(&self.gap, spot, strike, rate, carry, vol, years)),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::utils::black_scholes_call;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn test_barrier_in_out_parity() {
+        // Without rebates a knock-in plus the matching knock-out is the vanilla call
+        for (direction, level) in [(BarrierDirection::Down, 90.0), (BarrierDirection::Down, 105.0), (BarrierDirection::Up, 110.0), (BarrierDirection::Up, 95.0)] {
+            let out = BarrierOption { direction, knock: BarrierKnock::Out, level, rebate: 0.0, brownian_bridge: true };
+            let knock_in = BarrierOption { knock: BarrierKnock::In, ..out };
+            let total = barrier_call(&out, 100.0, 100.0, 0.05, 0.05, 0.25, 0.5) + barrier_call(&knock_in, 100.0, 100.0, 0.05, 0.05, 0.25, 0.5);
+            assert_abs_diff_eq!(total, black_scholes_call(100.0, 100.0, 0.05, 0.5, 0.25), epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_barrier_reference_values() {
+        // Haug, The Complete Guide to Option Pricing Formulas, table 4-13 (S = 100, r = 0.08, b = 0.04, T = 0.5, rebate 3)
+        let down_out = BarrierOption { direction: BarrierDirection::Down, knock: BarrierKnock::Out, level: 95.0, rebate: 3.0, brownian_bridge: true };
+        assert_abs_diff_eq!(barrier_call(&down_out, 100.0, 90.0, 0.08, 0.04, 0.25, 0.5), 9.0246, epsilon = 1e-4);
+        let up_in = BarrierOption { direction: BarrierDirection::Up, knock: BarrierKnock::In, level: 105.0, rebate: 3.0, brownian_bridge: true };
+        assert_abs_diff_eq!(barrier_call(&up_in, 100.0, 90.0, 0.08, 0.04, 0.25, 0.5), 14.1112, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn test_geometric_asian_single_date() {
+        // Averaging only the final spot is a European call for the fixed strike, and worthless for the floating one
+        let mut asian = AsianOption { averaging_times: vec![0.75], ..AsianOption::default() };
+        let fixed = geometric_asian_call(&asian, 100.0, 95.0, 0.05, 0.05, 0.2, 0.75);
+        assert_abs_diff_eq!(fixed, black_scholes_call(100.0, 95.0, 0.05, 0.75, 0.2), epsilon = 1e-10);
+        asian.strike = AsianStrike::Floating;
+        let floating = geometric_asian_call(&asian, 100.0, 95.0, 0.05, 0.05, 0.2, 0.75);
+        assert_abs_diff_eq!(floating, 0.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_lookback_properties() {
+        // Below the running maximum the fixed strike lookback is linear in the strike
+        let at_90 = lookback_call(LookbackStrike::Fixed, 100.0, 90.0, 0.05, 0.03, 0.25, 0.5);
+        let at_95 = lookback_call(LookbackStrike::Fixed, 100.0, 95.0, 0.05, 0.03, 0.25, 0.5);
+        assert_abs_diff_eq!(at_90 - at_95, 5.0 * (-0.05f64 * 0.5).exp(), epsilon = 1e-10);
+        // Both lookbacks are worth more than the at-the-money call
+        let vanilla = gap_call(&GapOption { trigger: 100.0 }, 100.0, 100.0, 0.05, 0.03, 0.25, 0.5);
+        assert!(lookback_call(LookbackStrike::Fixed, 100.0, 100.0, 0.05, 0.03, 0.25, 0.5) > vanilla);
+        assert!(lookback_call(LookbackStrike::Floating, 100.0, 100.0, 0.05, 0.03, 0.25, 0.5) > vanilla);
+
+        // Zero carry takes the limit of small carries rather than dividing by zero
+        for (strike_type, strike) in [(LookbackStrike::Floating, 100.0), (LookbackStrike::Fixed, 95.0), (LookbackStrike::Fixed, 105.0)] {
+            let limit = lookback_call(strike_type, 100.0, strike, 0.05, 0.0, 0.25, 0.5);
+            assert!(limit.is_finite());
+            let above = lookback_call(strike_type, 100.0, strike, 0.05, 1e-4, 0.25, 0.5);
+            let below = lookback_call(strike_type, 100.0, strike, 0.05, -1e-4, 0.25, 0.5);
+            assert_abs_diff_eq!(limit, 0.5 * (above + below), epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_digital_and_gap_decompose_vanilla() {
+        // A vanilla call is long the asset-or-nothing and short strike times the cash-or-nothing
+        let asset = digital_call(&DigitalOption { kind: DigitalKind::AssetOrNothing, ..DigitalOption::default() }, 100.0, 105.0, 0.05, 0.05, 0.2, 1.0);
+        let cash = digital_call(&DigitalOption { cash: 105.0, ..DigitalOption::default() }, 100.0, 105.0, 0.05, 0.05, 0.2, 1.0);
+        let vanilla = black_scholes_call(100.0, 105.0, 0.05, 1.0, 0.2);
+        assert_abs_diff_eq!(asset - cash, vanilla, epsilon = 1e-10);
+        assert_abs_diff_eq!(gap_call(&GapOption { trigger: 105.0 }, 100.0, 105.0, 0.05, 0.05, 0.2, 1.0), vanilla, epsilon = 1e-10);
+    }
+
+    #[test]
+    fn test_margrabe_and_kirk() {
+        // Exchanging a riskless zero coupon bond paying the strike is a vanilla call
+        let stock = Asset { spot: 100.0, vol: 0.2, dividend_yield: 0.0 };
+        let bond = Asset { spot: 105.0 * (-0.05f64).exp(), vol: 0.0, dividend_yield: 0.0 };
+        assert_abs_diff_eq!(margrabe_exchange(&stock, &bond, 0.0, 1.0), black_scholes_call(100.0, 105.0, 0.05, 1.0, 0.2), epsilon = 1e-10);
+
+        let other = Asset { spot: 95.0, vol: 0.3, dividend_yield: 0.02 };
+        assert_abs_diff_eq!(kirk_spread_call(&stock, &other, 0.0, 0.05, 0.4, 0.5), margrabe_exchange(&stock, &other, 0.4, 0.5), epsilon = 1e-10);
+        assert!(kirk_spread_call(&stock, &other, 5.0, 0.05, 0.4, 0.5) < kirk_spread_call(&stock, &other, 0.0, 0.05, 0.4, 0.5));
+    }
+
+    #[test]
+    fn test_black_scholes_greeks() {
+        let (spot, strike, rate, carry, vol, years) = (100.0, 95.0, 0.05, 0.03, 0.25, 0.75);
+        let call = black_scholes_greeks(OptionType::Call, spot, strike, rate, carry, vol, years);
+        let put = black_scholes_greeks(OptionType::Put, spot, strike, rate, carry, vol, years);
+        assert_abs_diff_eq!(call.price - put.price, spot * ((carry - rate) * years).exp() - strike * (-rate * years).exp(), epsilon = 1e-10);
+
+        let h = 1e-4;
+        for option_type in [OptionType::Call, OptionType::Put] {
+            let g = black_scholes_greeks(option_type, spot, strike, rate, carry, vol, years);
+            let price = |s: f64, r: f64, b: f64, v: f64, t: f64| black_scholes_greeks(option_type, s, strike, r, b, v, t).price;
+            assert_abs_diff_eq!(g.delta, (price(spot + h, rate, carry, vol, years) - price(spot - h, rate, carry, vol, years)) / (2.0 * h), epsilon = 1e-6);
+            assert_abs_diff_eq!(g.gamma, (price(spot + h, rate, carry, vol, years) - 2.0 * g.price + price(spot - h, rate, carry, vol, years)) / h.powi(2), epsilon = 1e-4);
+            assert_abs_diff_eq!(g.vega, (price(spot, rate, carry, vol + h, years) - price(spot, rate, carry, vol - h, years)) / (2.0 * h), epsilon = 1e-6);
+            assert_abs_diff_eq!(g.theta, -(price(spot, rate, carry, vol, years + h) - price(spot, rate, carry, vol, years - h)) / (2.0 * h), epsilon = 1e-6);
+            // Rho moves the rate and the cost of carry together, as when the dividend yield is fixed
+            assert_abs_diff_eq!(g.rho, (price(spot, rate + h, carry + h, vol, years) - price(spot, rate - h, carry - h, vol, years)) / (2.0 * h), epsilon = 1e-6);
+            assert_abs_diff_eq!(strike_for_delta(option_type, g.delta.abs(), spot, rate, carry, vol, years), strike, epsilon = 1e-8);
+        }
+    }
+}
@@ -1,7 +1,34 @@
 use crate::model::chart::PriceChart;
 use crate::model::request::StockData;
+use chrono::NaiveDate;
 
-use super::{monte_carlo::MonteCarloPricing, params::MonteCarloParams};
+use super::asian::ControlVariateEstimate;
+use super::backtest::{Backtester, BuyAndHold, RollingOption, TradingStrategy};
+use super::bootstrap::HistoricalBootstrap;
+use super::calendar::Calendar;
+use super::correlation::{estimate_correlation, CorrelationEstimate, CorrelationSettings};
+use super::costs::CostModel;
+use super::calibration::{calibrate_heston, load_option_chain, CalibrationResult, CalibrationTarget, OptionQuote};
+use super::dividends::DividendSchedule;
+use super::hedging::{HedgeSettings, PnlDistribution};
+use super::heston::HestonParams;
+use super::innovations::{fit_innovations, QqPlot};
+use super::jumps::estimate_jumps;
+use super::analytic::Greeks;
+use super::local_vol::{ImpliedSurface, LocalVolSurface};
+use super::multi_asset::{format_correlation, MultiAssetEstimate, MultiAssetPricing};
+use super::performance::BacktestReport;
+use super::risk::{PortfolioRisk, RiskEstimate, VarBacktest};
+use super::sabr::{calibrate_sabr, SabrCalibration};
+use super::scenario::{scenario_grid, ScenarioGrid, ScenarioSettings};
+use super::utils::implied_vol_newton;
+use super::strategy::{PayoffDiagram, Strategy};
+use super::systematic::{SystematicSettings, SystematicTrader};
+use super::sweep::{sort_results, sweep, walk_forward, ParameterGrid, SweepMetric, SweepPoint, SweepResult, WalkForwardSplit, WalkForwardWindow};
+use super::yield_curve::YieldCurve;
+use super::{monte_carlo::{MonteCarloPricing, SimulationModel}, params::MonteCarloParams};
+
+pub const VALUATION_DATE_FORMAT: &str = "%Y-%m-%d";
 
 pub struct OptiRust {
     pub chart: PriceChart,
@@ -13,27 +40,96 @@ pub struct OptiRust {
     pub error_message: Option<String>,
     pub monte_carlo_pricing: MonteCarloPricing,
     pub monte_carlo_params: MonteCarloParams,
+    pub valuation_date_text: String,
     pub pricing_result: Option<f64>,
+    pub asian_estimate: Option<ControlVariateEstimate>,
+    pub option_chain_path: String,
+    pub option_chain: Vec<OptionQuote>,
+    pub calibration_target: CalibrationTarget,
+    pub calibration_path: String,
+    pub calibration_result: Option<CalibrationResult>,
+    pub vol_surface_path: String,
+    pub implied_surface: Option<ImpliedSurface>,
+    pub sabr_calibrations: Vec<SabrCalibration>,
+    pub dividends_path: String,
+    pub dividend_schedule: DividendSchedule,
+    pub yield_curve_path: String,
+    pub yield_curve: Option<YieldCurve>,
+    pub calendar_path: String,
+    pub custom_calendar: Option<Calendar>,
+    pub multi_asset_estimate: Option<MultiAssetEstimate>,
+    pub tickers_text: String,
+    pub tickers: Vec<String>,
+    pub ticker_charts: Vec<PriceChart>,
+    pub correlation_estimate: Option<CorrelationEstimate>,
+    pub strategy_greeks: Option<Greeks>,
+    pub payoff_diagram: Option<PayoffDiagram>,
+    pub hedging_distribution: Option<PnlDistribution>,
+    pub backtest_report: Option<BacktestReport>,
+    pub sweep_grid: Option<ParameterGrid>,
+    pub sweep_results: Vec<SweepResult>,
+    pub walk_forward_windows: Vec<WalkForwardWindow>,
+    pub qq_plot: Option<QqPlot>,
+    pub risk_estimates: Vec<RiskEstimate>,
+    pub var_backtest: Option<VarBacktest>,
+    pub scenario_grid: Option<ScenarioGrid>,
+    pub scenario_csv_path: String,
 }
 
-impl Default for OptiRust {
-    fn default() -> OptiRust {
-        OptiRust{
-            chart: PriceChart::default(), 
-            show_import: false, 
-            imported_index: String::new(), 
-            api_key: String::from(""), 
+impl OptiRust {
+    pub fn new(valuation_date: NaiveDate) -> OptiRust {
+        OptiRust {
+            chart: PriceChart::default(),
+            show_import: false,
+            imported_index: String::new(),
+            index_value_text: String::new(),
+            api_key: String::new(),
             require_api_key: false,
             error_message: None,
             monte_carlo_pricing: MonteCarloPricing::default(),
-            monte_carlo_params: MonteCarloParams::default(),
-            index_value_text: String::from(""),
+            monte_carlo_params: MonteCarloParams { valuation_date, ..MonteCarloParams::default() },
+            valuation_date_text: valuation_date.format(VALUATION_DATE_FORMAT).to_string(),
             pricing_result: None,
+            asian_estimate: None,
+            option_chain_path: String::new(),
+            option_chain: Vec::new(),
+            calibration_target: CalibrationTarget::default(),
+            calibration_path: String::new(),
+            calibration_result: None,
+            vol_surface_path: String::new(),
+            implied_surface: None,
+            sabr_calibrations: Vec::new(),
+            dividends_path: String::new(),
+            dividend_schedule: DividendSchedule::default(),
+            yield_curve_path: String::new(),
+            yield_curve: None,
+            calendar_path: String::new(),
+            custom_calendar: None,
+            multi_asset_estimate: None,
+            tickers_text: String::new(),
+            tickers: Vec::new(),
+            ticker_charts: Vec::new(),
+            correlation_estimate: None,
+            strategy_greeks: None,
+            payoff_diagram: None,
+            hedging_distribution: None,
+            backtest_report: None,
+            sweep_grid: None,
+            sweep_results: Vec::new(),
+            walk_forward_windows: Vec::new(),
+            qq_plot: None,
+            risk_estimates: Vec::new(),
+            var_backtest: None,
+            scenario_grid: None,
+            scenario_csv_path: String::new(),
         }
     }
-}
 
-impl OptiRust {
+    pub fn set_valuation_date(&mut self, date: NaiveDate) {
+        self.monte_carlo_params.valuation_date = date;
+        self.valuation_date_text = date.format(VALUATION_DATE_FORMAT).to_string();
+    }
+
     pub fn get_index_data(& mut self) {
         if self.api_key.is_empty() {
             self.error_message = Some(String::from("No Alpha Vantage API key was specified"));
@@ -44,24 +140,471 @@ impl OptiRust {
             return;
         }
         
+        let chart = match self.fetch_daily_prices(&self.index_value_text) {
+            Ok(chart) => chart,
+            Err(e) => {
+                self.error_message = Some(e);
+                return;
+            }
+        };
+        self.imported_index = self.index_value_text.clone();
+        self.chart = chart;
+        let stock_price = self.chart.underlying_price();
+        if let Some(last) = self.chart.data.last() {
+            self.set_valuation_date(last.date);
+        }
+        self.monte_carlo_params.current_asset_price = stock_price.to_string();
+        self.monte_carlo_params.strike_price = (stock_price * 1.05).to_string();
+    }
+
+    fn fetch_daily_prices(&self, symbol: &str) -> Result<PriceChart, String> {
         let daily_prices_url = format!(
             "https://www.alphavantage.co/query?function=TIME_SERIES_DAILY&symbol={}&apikey={}&outputsize=30",
-            self.index_value_text, self.api_key
+            symbol, self.api_key
         );
-        let daily_prices_response = reqwest::blocking::get(&daily_prices_url);
-        if daily_prices_response.is_err() {
-            self.error_message = Some(String::from(format!("Error while making request. {}", daily_prices_response.err().unwrap())));
+        let response = reqwest::blocking::get(&daily_prices_url).map_err(|e| format!("Error while making request. {}", e))?;
+        let stock_data = response.json::<StockData>().map_err(|e| format!("Error while parsing response for {}. {}", symbol, e))?;
+        Ok(PriceChart::from_json(stock_data))
+    }
+
+    /// Imports every comma separated ticker and estimates their correlation.
+    pub fn import_tickers(&mut self) {
+        if self.api_key.is_empty() {
+            self.error_message = Some(String::from("No Alpha Vantage API key was specified"));
             return;
         }
-        let parsed_response = daily_prices_response.unwrap().json::<StockData>();
-        if parsed_response.is_err() {
-            self.error_message = Some(String::from(format!("Error while parsing response. {}", parsed_response.err().unwrap())));
+        let tickers: Vec<String> = self.tickers_text.split(',').map(|t| t.trim().to_uppercase()).filter(|t| !t.is_empty()).collect();
+        let mut charts = Vec::new();
+        for ticker in &tickers {
+            match self.fetch_daily_prices(ticker) {
+                Ok(chart) => charts.push(chart),
+                Err(e) => {
+                    self.error_message = Some(e);
+                    return;
+                }
+            }
+        }
+        self.tickers = tickers;
+        self.ticker_charts = charts;
+        self.estimate_correlation();
+    }
+
+    pub fn estimate_correlation(&mut self) {
+        if self.ticker_charts.is_empty() {
             return;
         }
-        self.imported_index = self.index_value_text.clone();
-        self.chart = PriceChart::from_json(parsed_response.unwrap());
-        let stock_price = self.chart.data.iter().map(|d| &d.price).last().unwrap();
-        self.monte_carlo_params.current_asset_price = stock_price.to_string();
-        self.monte_carlo_params.strike_price = (stock_price * 1.05).to_string();
+        let result = CorrelationSettings::from_params(&self.monte_carlo_params)
+            .and_then(|settings| estimate_correlation(self.tickers.clone(), &self.ticker_charts, &settings));
+        match result {
+            Ok(estimate) => self.correlation_estimate = Some(estimate),
+            Err(e) => self.error_message = Some(format!("Error while estimating correlation. {}", e)),
+        }
+    }
+
+    /// Uses the estimated correlation, volatilities and latest common prices as the multi-asset inputs.
+    pub fn use_correlation_estimate(&mut self) {
+        let Some(estimate) = &self.correlation_estimate else {
+            return;
+        };
+        let join = |values: Vec<f64>| values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ");
+        let n = estimate.tickers.len();
+        let params = &mut self.monte_carlo_params;
+        params.asset_spots = join(estimate.last_prices.clone());
+        params.asset_vols = join(estimate.volatilities());
+        params.correlation = format_correlation(&estimate.correlation);
+        params.asset_dividend_yields = join(vec![0.0; n]);
+        params.basket_weights = join(vec![1.0 / n as f64; n]);
+    }
+
+    fn periods_per_year(&self) -> f64 {
+        self.chart.periods_per_year(self.monte_carlo_params.day_count, &self.monte_carlo_params.calendar)
+    }
+
+    pub fn use_historical_vol(&mut self) {
+        self.monte_carlo_params.implied_vol = self.chart.historical_volatility(self.periods_per_year()).to_string();
+    }
+
+    pub fn estimate_jumps(&mut self) {
+        let estimate = estimate_jumps(&self.chart, self.periods_per_year());
+        self.monte_carlo_params.set_jump_estimate(&estimate);
+    }
+
+    /// Fits the selected innovation distribution to the returns of the chart and compares the two in a QQ-plot.
+    pub fn fit_innovations(&mut self) {
+        let returns = self.chart.log_returns();
+        match fit_innovations(self.monte_carlo_params.innovations, &returns) {
+            Ok(innovations) => {
+                self.monte_carlo_params.set_innovations(&innovations);
+                self.qq_plot = Some(QqPlot::new(&innovations, &returns));
+            }
+            Err(e) => self.error_message = Some(format!("Error while fitting the innovations. {}", e)),
+        }
+    }
+
+    /// Calendars to choose from: the built-in ones and the last one loaded from file.
+    pub fn calendars(&self) -> Vec<Calendar> {
+        let mut calendars = vec![Calendar::Nyse, Calendar::WeekendsOnly];
+        calendars.extend(self.custom_calendar.clone());
+        calendars
+    }
+
+    pub fn load_calendar(&mut self) {
+        match Calendar::load(&self.calendar_path) {
+            Ok(calendar) => {
+                self.monte_carlo_params.calendar = calendar.clone();
+                self.custom_calendar = Some(calendar);
+            }
+            Err(e) => self.error_message = Some(format!("Error while loading holiday calendar. {}", e)),
+        }
+    }
+
+    pub fn load_option_chain(&mut self) {
+        match load_option_chain(&self.option_chain_path, |days| self.monte_carlo_params.year_fraction_for_days(days)) {
+            Ok(quotes) => self.option_chain = quotes,
+            Err(e) => self.error_message = Some(format!("Error while loading option chain. {}", e)),
+        }
+    }
+
+    pub fn calibrate_heston(&mut self) {
+        if self.option_chain.is_empty() {
+            self.error_message = Some(String::from("No option chain was loaded"));
+            return;
+        }
+        let pricing = self.build_pricing();
+        let spot = self.chart.underlying_price();
+        let spots: Vec<f64> = self.option_chain.iter().map(|q| pricing.spot_net_of_dividends(spot, q.years_to_expire)).collect();
+        let result = calibrate_heston(
+            &self.option_chain,
+            &spots,
+            &pricing.curve(),
+            self.calibration_target,
+            pricing.heston,
+        );
+        self.use_heston_params(&result.params);
+        self.calibration_result = Some(result);
+    }
+
+    pub fn save_heston_calibration(&mut self) {
+        let params = MonteCarloPricing::from_params(&self.monte_carlo_params).heston;
+        if let Err(e) = params.save(&self.calibration_path) {
+            self.error_message = Some(format!("Error while saving calibration. {}", e));
+        }
+    }
+
+    pub fn load_heston_calibration(&mut self) {
+        match HestonParams::load(&self.calibration_path) {
+            Ok(params) => self.use_heston_params(&params),
+            Err(e) => self.error_message = Some(format!("Error while loading calibration. {}", e)),
+        }
+    }
+
+    /// Calibrates one SABR slice per expiry of the loaded option chain, keeping the entered beta, and
+    /// selects the slice closest to the option's expiry.
+    pub fn calibrate_sabr(&mut self) {
+        if self.option_chain.is_empty() {
+            self.error_message = Some(String::from("No option chain was loaded"));
+            return;
+        }
+        let pricing = self.build_pricing();
+        let spot = self.chart.underlying_price();
+
+        let mut expiries: Vec<f64> = self.option_chain.iter().map(|q| q.years_to_expire).collect();
+        expiries.sort_by(f64::total_cmp);
+        expiries.dedup();
+        self.sabr_calibrations = expiries
+            .iter()
+            .map(|years| {
+                let slice: Vec<&OptionQuote> = self.option_chain.iter().filter(|q| q.years_to_expire == *years).collect();
+                let rate = pricing.zero_rate(*years);
+                let net_spot = pricing.spot_net_of_dividends(spot, *years);
+                let strikes: Vec<f64> = slice.iter().map(|q| q.strike).collect();
+                let vols: Vec<f64> = slice.iter().map(|q| implied_vol_newton(q.price, net_spot, q.strike, rate, *years, pricing.implied_vol)).collect();
+                calibrate_sabr(net_spot * (rate * years).exp(), *years, &strikes, &vols, pricing.sabr.beta)
+            })
+            .collect();
+
+        if let Some(nearest) = self.sabr_calibrations.iter().min_by(|a, b| {
+            (a.years_to_expire - pricing.years_to_expire).abs().total_cmp(&(b.years_to_expire - pricing.years_to_expire).abs())
+        }) {
+            self.monte_carlo_params.set_sabr_params(&nearest.params);
+        }
+    }
+
+    pub fn use_sabr_vol(&mut self) {
+        let pricing = self.build_pricing();
+        self.monte_carlo_params.implied_vol = pricing.sabr_implied_vol().to_string();
+    }
+
+    pub fn load_vol_surface(&mut self) {
+        match ImpliedSurface::load(&self.vol_surface_path, |days| self.monte_carlo_params.year_fraction_for_days(days)) {
+            Ok(surface) => {
+                self.implied_surface = Some(surface);
+                self.monte_carlo_params.model = SimulationModel::LocalVol;
+            }
+            Err(e) => self.error_message = Some(format!("Error while loading volatility surface. {}", e)),
+        }
+    }
+
+    pub fn load_dividends(&mut self) {
+        match DividendSchedule::load(&self.dividends_path, |days| self.monte_carlo_params.year_fraction_for_days(days)) {
+            Ok(schedule) => self.dividend_schedule = schedule,
+            Err(e) => self.error_message = Some(format!("Error while loading dividends. {}", e)),
+        }
+    }
+
+    pub fn load_yield_curve(&mut self) {
+        match YieldCurve::load(&self.yield_curve_path, |days| self.monte_carlo_params.year_fraction_for_days(days)) {
+            Ok(curve) => self.yield_curve = Some(curve),
+            Err(e) => self.error_message = Some(format!("Error while loading yield curve. {}", e)),
+        }
+    }
+
+    /// Builds the pricer from the entered parameters together with the loaded dividend schedule and yield
+    /// curve and, when the local vol model is selected, the Dupire surface for the current spot and expiry.
+    /// The bootstrap model resamples the returns of the imported history.
+    pub fn build_pricing(&self) -> MonteCarloPricing {
+        let mut pricing = MonteCarloPricing::from_params(&self.monte_carlo_params);
+        pricing.dividends = self.dividend_schedule.clone();
+        pricing.yield_curve = self
+            .yield_curve
+            .as_ref()
+            .map(|curve| curve.with_interpolation(self.monte_carlo_params.curve_interpolation, self.monte_carlo_params.curve_quantity));
+        if pricing.model == SimulationModel::Bootstrap {
+            let params = &self.monte_carlo_params;
+            pricing.bootstrap = HistoricalBootstrap::from_chart(
+                &self.chart,
+                self.periods_per_year(),
+                params.bootstrap_scheme,
+                params.bootstrap_block_length.parse::<usize>().unwrap_or(5),
+                params.bootstrap_target_vol.trim().parse::<f64>().ok(),
+            );
+        }
+        if pricing.model == SimulationModel::LocalVol {
+            if let Some(surface) = &self.implied_surface {
+                pricing.local_vol = Some(LocalVolSurface::from_implied(
+                    surface,
+                    self.chart.underlying_price(),
+                    pricing.carry(),
+                    pricing.years_to_expire,
+                ));
+            }
+        }
+        pricing
+    }
+
+    /// Prices the multi-asset option, discounting at the zero rate to expiry of the loaded curve.
+    pub fn price_multi_asset(&mut self) {
+        let result = MultiAssetPricing::from_params(&self.monte_carlo_params).and_then(|mut pricing| {
+            pricing.risk_free_rate = self.build_pricing().zero_rate(pricing.years_to_expire);
+            pricing.price()
+        });
+        match result {
+            Ok(estimate) => self.multi_asset_estimate = Some(estimate),
+            Err(e) => self.error_message = Some(format!("Error while pricing multi-asset option. {}", e)),
+        }
+    }
+
+    /// Values the strategy leg by leg at the entered asset price and draws its payoff diagram.
+    pub fn price_strategy(&mut self) {
+        if self.monte_carlo_params.strategy_legs.is_empty() {
+            self.error_message = Some(String::from("No strategy legs were specified"));
+            return;
+        }
+        let pricing = self.build_pricing();
+        let strategy = Strategy::from_params(&self.monte_carlo_params);
+        self.strategy_greeks = Some(strategy.greeks(&pricing, pricing.current_asset_price, 0.0));
+        self.payoff_diagram = Some(strategy.payoff_diagram(&pricing, pricing.current_asset_price));
+    }
+
+    /// The strategy legs as a book on the imported underlying, for VaR and expected shortfall.
+    fn portfolio_risk(&self) -> PortfolioRisk {
+        PortfolioRisk {
+            strategy: Strategy::from_params(&self.monte_carlo_params),
+            pricing: self.build_pricing(),
+            periods_per_year: self.periods_per_year(),
+        }
+    }
+
+    pub fn compute_risk(&mut self) {
+        if self.monte_carlo_params.strategy_legs.is_empty() {
+            self.error_message = Some(String::from("No strategy legs were specified"));
+            return;
+        }
+        let confidences: Vec<f64> = self.monte_carlo_params.var_confidences
+            .split(',')
+            .filter_map(|c| c.trim().parse::<f64>().ok())
+            .filter(|c| *c > 0.0 && *c < 1.0)
+            .collect();
+        if confidences.is_empty() {
+            self.error_message = Some(String::from("No confidence levels between 0 and 1 were specified"));
+            return;
+        }
+        let risk = self.portfolio_risk();
+        match risk.estimates(&confidences, risk.pricing.current_asset_price) {
+            Ok(estimates) => self.risk_estimates = estimates,
+            Err(e) => self.error_message = Some(format!("Error while computing value at risk. {}", e)),
+        }
+    }
+
+    /// Backtests the one bar VaR of the selected method at the first confidence level.
+    pub fn backtest_var(&mut self) {
+        let params = &self.monte_carlo_params;
+        let confidence = params.var_confidences.split(',').next().and_then(|c| c.trim().parse::<f64>().ok()).unwrap_or(0.99);
+        let window = params.var_window.parse::<usize>().unwrap_or(20);
+        match self.portfolio_risk().backtest(&self.chart, params.var_method, confidence, window) {
+            Ok(backtest) => self.var_backtest = Some(backtest),
+            Err(e) => self.error_message = Some(format!("Error while backtesting value at risk. {}", e)),
+        }
+    }
+
+    pub fn run_scenarios(&mut self) {
+        let pricing = self.build_pricing();
+        let strategy = Strategy::from_params(&self.monte_carlo_params);
+        let result = ScenarioSettings::from_params(&self.monte_carlo_params).and_then(|settings| scenario_grid(&pricing, &strategy, &settings));
+        match result {
+            Ok(grid) => self.scenario_grid = Some(grid),
+            Err(e) => self.error_message = Some(format!("Error while running scenarios. {}", e)),
+        }
+    }
+
+    pub fn export_scenarios(&mut self) {
+        let Some(grid) = &self.scenario_grid else {
+            self.error_message = Some(String::from("No scenarios were run"));
+            return;
+        };
+        if let Err(e) = grid.save_csv(&self.scenario_csv_path) {
+            self.error_message = Some(format!("Error while exporting scenarios. {}", e));
+        }
+    }
+
+    pub fn hedge_on_simulated_paths(&mut self) {
+        let pricing = self.build_pricing();
+        let settings = HedgeSettings::from_params(&self.monte_carlo_params);
+        match pricing.hedging_distribution(&settings) {
+            Ok(distribution) => self.hedging_distribution = Some(distribution),
+            Err(e) => self.error_message = Some(format!("Error while hedging on simulated paths. {}", e)),
+        }
+    }
+
+    /// Hedges options written on every bar of the imported history, each lasting the number of steps.
+    pub fn hedge_on_history(&mut self) {
+        let pricing = self.build_pricing();
+        let settings = HedgeSettings::from_params(&self.monte_carlo_params);
+        let params = &self.monte_carlo_params;
+        match pricing.historical_hedging_distribution(&settings, &self.chart, params.day_count, &params.calendar) {
+            Some(distribution) => self.hedging_distribution = Some(distribution),
+            None => self.error_message = Some(format!("The price history is shorter than {} steps", pricing.num_steps)),
+        }
+    }
+
+    /// Backtests buying the strategy legs on the first bar of the imported history and holding them.
+    pub fn run_backtest(&mut self) {
+        let params = &self.monte_carlo_params;
+        if self.chart.data.len() < 2 {
+            self.error_message = Some(String::from("No price history was imported"));
+            return;
+        }
+        if params.strategy_legs.is_empty() {
+            self.error_message = Some(String::from("No strategy legs were specified"));
+            return;
+        }
+        let mut strategy = BuyAndHold::from_params(params, self.chart.data[0].date);
+        let backtester = self.build_backtester();
+        self.report_backtest(&backtester, &mut strategy);
+    }
+
+    /// Backtests the selected systematic strategy on the imported history.
+    pub fn run_systematic_backtest(&mut self) {
+        if self.chart.data.len() < 2 {
+            self.error_message = Some(String::from("No price history was imported"));
+            return;
+        }
+        let params = &self.monte_carlo_params;
+        let backtester = self.build_backtester();
+        let years = params.year_fraction_for_days(params.systematic_days.parse::<u64>().unwrap_or(30));
+        let settings = SystematicSettings::from_params(params, backtester.pricing.zero_rate(years), backtester.pricing.dividend_yield);
+        let mut trader = SystematicTrader::new(params.systematic_strategy, settings);
+        self.report_backtest(&backtester, &mut trader);
+    }
+
+    fn report_backtest(&mut self, backtester: &Backtester, strategy: &mut dyn TradingStrategy) {
+        let result = backtester.run(&self.chart, strategy);
+        self.backtest_report = Some(BacktestReport::new(result, self.periods_per_year(), backtester.pricing.zero_rate(1.0)));
+    }
+
+    fn build_backtester(&self) -> Backtester {
+        let params = &self.monte_carlo_params;
+        let initial_cash = params.backtest_initial_cash.parse::<f64>().unwrap_or(10000.0);
+        let mut backtester = Backtester::new(self.build_pricing(), initial_cash, params.day_count, params.calendar.clone());
+        backtester.costs = CostModel::from_params(params);
+        backtester
+    }
+
+    /// Builds the rolling option strategy for a point of the parameter sweep.
+    fn rolling_option_factory(&self) -> impl Fn(&SweepPoint) -> Box<dyn TradingStrategy> + Sync {
+        let kind = self.monte_carlo_params.sweep_option_kind;
+        let quantity = self.monte_carlo_params.sweep_quantity.parse::<f64>().unwrap_or(-1.0);
+        move |point: &SweepPoint| -> Box<dyn TradingStrategy> {
+            Box::new(RollingOption {
+                kind,
+                quantity,
+                strike_offset: point.strike_offset,
+                days_to_expiry: point.days_to_expiry,
+                roll_days: point.roll_days,
+            })
+        }
+    }
+
+    /// Backtests the rolling option over every combination of the swept parameters on the imported history.
+    pub fn run_sweep(&mut self) {
+        if self.chart.data.len() < 2 {
+            self.error_message = Some(String::from("No price history was imported"));
+            return;
+        }
+        let grid = match ParameterGrid::from_params(&self.monte_carlo_params) {
+            Ok(grid) => grid,
+            Err(e) => {
+                self.error_message = Some(format!("Error while reading the parameter grid. {}", e));
+                return;
+            }
+        };
+        let mut results = sweep(&self.build_backtester(), &self.chart, &grid.points(), &self.rolling_option_factory());
+        sort_results(&mut results, self.monte_carlo_params.sweep_metric);
+        self.sweep_results = results;
+        self.sweep_grid = Some(grid);
+    }
+
+    pub fn sort_sweep(&mut self, metric: SweepMetric) {
+        self.monte_carlo_params.sweep_metric = metric;
+        sort_results(&mut self.sweep_results, metric);
+    }
+
+    pub fn run_walk_forward(&mut self) {
+        let params = &self.monte_carlo_params;
+        let grid = match ParameterGrid::from_params(params) {
+            Ok(grid) => grid,
+            Err(e) => {
+                self.error_message = Some(format!("Error while reading the parameter grid. {}", e));
+                return;
+            }
+        };
+        let split = WalkForwardSplit {
+            in_sample_bars: params.in_sample_bars.parse::<usize>().unwrap_or(60),
+            out_of_sample_bars: params.out_of_sample_bars.parse::<usize>().unwrap_or(20),
+        };
+        let windows = walk_forward(&self.build_backtester(), &self.chart, &grid.points(), &self.rolling_option_factory(), params.sweep_metric, split);
+        if windows.is_empty() {
+            self.error_message = Some(format!(
+                "The price history of {} bars is shorter than one walk-forward window",
+                self.chart.data.len()
+            ));
+            return;
+        }
+        self.walk_forward_windows = windows;
+    }
+
+    fn use_heston_params(&mut self, params: &HestonParams) {
+        self.monte_carlo_params.set_heston_params(params);
+        self.monte_carlo_params.model = SimulationModel::Heston;
     }
 }
\ No newline at end of file
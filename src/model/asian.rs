@@ -0,0 +1,211 @@
+use std::fmt;
+use rayon::prelude::*;
+
+use super::analytic::geometric_asian_call;
+use super::innovations::InnovationDistribution;
+use super::monte_carlo::{MonteCarloPricing, SimulationModel};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AsianAverage {
+    #[default]
+    Arithmetic,
+    Geometric,
+}
+
+impl AsianAverage {
+    pub const ALL: [AsianAverage; 2] = [AsianAverage::Arithmetic, AsianAverage::Geometric];
+}
+
+impl fmt::Display for AsianAverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AsianAverage::Arithmetic => write!(f, "Arithmetic"),
+            AsianAverage::Geometric => write!(f, "Geometric"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AsianStrike {
+    #[default]
+    Fixed,    // pays the average over the strike
+    Floating, // pays the final spot over the average
+}
+
+impl AsianStrike {
+    pub const ALL: [AsianStrike; 2] = [AsianStrike::Fixed, AsianStrike::Floating];
+}
+
+impl fmt::Display for AsianStrike {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AsianStrike::Fixed => write!(f, "Fixed strike"),
+            AsianStrike::Floating => write!(f, "Floating strike"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AsianOption {
+    pub average: AsianAverage,
+    pub strike: AsianStrike,
+    pub averaging_times: Vec<f64>, // in years, every simulation step when empty
+}
+
+/// Monte Carlo estimate with and without the control variate, from the same paths.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ControlVariateEstimate {
+    pub price: f64,
+    pub std_error: f64,
+    pub plain_price: f64,
+    pub plain_std_error: f64,
+    pub beta: f64, // zero when no control variate applies
+}
+
+impl ControlVariateEstimate {
+    /// Ratio of the plain estimator variance to the controlled one.
+    pub fn variance_reduction(&self) -> f64 {
+        if self.std_error > 0.0 { (self.plain_std_error / self.std_error).powi(2) } else { f64::INFINITY }
+    }
+}
+
+fn mean_and_variance(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    (mean, values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0))
+}
+
+impl MonteCarloPricing {
+    /// Path indices of the averaging dates, each snapped to the nearest simulation step. A date at
+    /// inception averages in the starting spot.
+    pub fn averaging_indices(&self) -> Vec<usize> {
+        let steps = self.num_steps as usize;
+        if self.asian.averaging_times.is_empty() {
+            return (1..=steps).collect();
+        }
+        let dt = self.years_to_expire / steps as f64;
+        self.asian.averaging_times.iter().map(|t| ((t / dt).round().max(0.0) as usize).min(steps)).collect()
+    }
+
+    fn average_payoff(&self, path: &[f64], indices: &[usize], average: AsianAverage) -> f64 {
+        let n = indices.len() as f64;
+        let mean = match average {
+            AsianAverage::Arithmetic => indices.iter().map(|&i| path[i]).sum::<f64>() / n,
+            AsianAverage::Geometric => (indices.iter().map(|&i| path[i].ln()).sum::<f64>() / n).exp(),
+        };
+        let payoff = match self.asian.strike {
+            AsianStrike::Fixed => mean - self.strike_price,
+            AsianStrike::Floating => path[path.len() - 1] - mean,
+        };
+        payoff.max(0.0) * self.discount_factor(self.years_to_expire)
+    }
+
+    pub fn asian_payoff(&self, path: &[f64]) -> f64 {
+        self.average_payoff(path, &self.averaging_indices(), self.asian.average)
+    }
+
+    /// Closed-form price of the geometric Asian on the snapped averaging dates, when the simulation
+    /// matches its assumptions: GBM with normal shocks, whose log steps are then exact, on a flat curve
+    /// without discrete dividends.
+    pub fn geometric_asian_price(&self, spot: f64) -> Option<f64> {
+        let normal = self.innovations.distribution == InnovationDistribution::Normal;
+        if self.model != SimulationModel::Gbm || !normal || self.yield_curve.is_some() || !self.dividends.is_empty() {
+            return None;
+        }
+        let dt = self.years_to_expire / self.num_steps as f64;
+        let snapped = AsianOption {
+            averaging_times: self.averaging_indices().iter().map(|&i| i as f64 * dt).collect(),
+            ..self.asian.clone()
+        };
+        Some(geometric_asian_call(&snapped, spot, self.strike_price, self.risk_free_rate, self.carry(), self.implied_vol, self.years_to_expire))
+    }
+
+    /// Prices the Asian from `spot`, using the geometric Asian as a control variate for arithmetic
+    /// averages when its closed form applies.
+    pub fn asian_estimate(&self, spot: f64) -> ControlVariateEstimate {
+        let indices = self.averaging_indices();
+        let control = match self.asian.average {
+            AsianAverage::Arithmetic => self.geometric_asian_price(spot),
+            AsianAverage::Geometric => None,
+        };
+        let samples: Vec<(f64, f64)> = (0..self.num_simulations)
+            .into_par_iter()
+            .map(|_| {
+                let path = self.simulate_path(spot, &mut rand::rng());
+                let geometric = if control.is_some() { self.average_payoff(&path, &indices, AsianAverage::Geometric) } else { 0.0 };
+                (self.average_payoff(&path, &indices, self.asian.average), geometric)
+            })
+            .collect();
+
+        let n = samples.len() as f64;
+        let payoffs: Vec<f64> = samples.iter().map(|s| s.0).collect();
+        let (plain_price, plain_variance) = mean_and_variance(&payoffs);
+        let plain_std_error = (plain_variance / n).sqrt();
+        let Some(expected_control) = control else {
+            return ControlVariateEstimate { price: plain_price, std_error: plain_std_error, plain_price, plain_std_error, beta: 0.0 };
+        };
+
+        let controls: Vec<f64> = samples.iter().map(|s| s.1).collect();
+        let (control_mean, control_variance) = mean_and_variance(&controls);
+        let covariance = samples.iter().map(|(y, x)| (y - plain_price) * (x - control_mean)).sum::<f64>() / (n - 1.0).max(1.0);
+        let beta = if control_variance > 0.0 { covariance / control_variance } else { 0.0 };
+        let adjusted: Vec<f64> = samples.iter().map(|(y, x)| y - beta * (x - expected_control)).collect();
+        let (price, variance) = mean_and_variance(&adjusted);
+        ControlVariateEstimate { price, std_error: (variance / n).sqrt(), plain_price, plain_std_error, beta }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::monte_carlo::PayoffKind;
+
+    fn pricing(average: AsianAverage, strike: AsianStrike) -> MonteCarloPricing {
+        MonteCarloPricing {
+            current_asset_price: 100.0,
+            strike_price: 100.0,
+            num_simulations: 20000,
+            num_steps: 24,
+            risk_free_rate: 0.05,
+            implied_vol: 0.3,
+            years_to_expire: 1.0,
+            dividend_yield: 0.02,
+            payoff: PayoffKind::Asian,
+            asian: AsianOption { average, strike, averaging_times: Vec::new() },
+            ..MonteCarloPricing::default()
+        }
+    }
+
+    #[test]
+    fn test_averaging_dates_snap_to_steps() {
+        let mut mc = pricing(AsianAverage::Arithmetic, AsianStrike::Fixed);
+        assert_eq!(mc.averaging_indices().len(), 24);
+        mc.asian.averaging_times = vec![0.0, 0.26, 0.5, 2.0];
+        assert_eq!(mc.averaging_indices(), vec![0, 6, 12, 24]);
+        assert!(mc.geometric_asian_price(100.0).unwrap().is_finite());
+        // Fat tailed shocks leave the control without a closed form
+        mc.innovations.distribution = InnovationDistribution::StudentT;
+        assert!(mc.geometric_asian_price(100.0).is_none());
+    }
+
+    #[test]
+    fn test_geometric_monte_carlo_matches_closed_form() {
+        for strike in AsianStrike::ALL {
+            let mc = pricing(AsianAverage::Geometric, strike);
+            let estimate = mc.asian_estimate(100.0);
+            let analytic = mc.geometric_asian_price(100.0).unwrap();
+            assert!((estimate.price - analytic).abs() < 4.0 * estimate.std_error, "{strike}: {} vs {analytic}", estimate.price);
+        }
+    }
+
+    #[test]
+    fn test_control_variate_reduces_variance() {
+        let mc = pricing(AsianAverage::Arithmetic, AsianStrike::Fixed);
+        let estimate = mc.asian_estimate(100.0);
+        assert!(estimate.variance_reduction() > 20.0, "reduction {}", estimate.variance_reduction());
+        assert!((estimate.price - estimate.plain_price).abs() < 4.0 * estimate.plain_std_error);
+        // The arithmetic average dominates the geometric one
+        assert!(estimate.price > mc.geometric_asian_price(100.0).unwrap());
+    }
+}
@@ -0,0 +1,400 @@
+use chrono::{Days, NaiveDate};
+
+use super::calendar::{Calendar, DayCount};
+use super::chart::{DataPoint, PriceChart};
+use super::costs::{CostModel, FillCosts, SpreadQuote};
+use super::dividends::DividendSchedule;
+use super::monte_carlo::{ExerciseStyle, MonteCarloPricing, PayoffKind};
+use super::params::MonteCarloParams;
+use super::strategy::LegKind;
+
+const VEGA_BUMP: f64 = 0.01;
+const DELTA_BUMP: f64 = 0.001; // relative to the spot
+
+/// An instrument held or traded: the underlying, or a European call or put.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contract {
+    pub kind: LegKind,
+    pub strike: f64,
+    pub expiry: NaiveDate,
+}
+
+impl Contract {
+    pub fn underlying() -> Contract {
+        Contract { kind: LegKind::Underlying, strike: 0.0, expiry: NaiveDate::MAX }
+    }
+
+    pub fn call(strike: f64, expiry: NaiveDate) -> Contract {
+        Contract { kind: LegKind::Call, strike, expiry }
+    }
+
+    pub fn put(strike: f64, expiry: NaiveDate) -> Contract {
+        Contract { kind: LegKind::Put, strike, expiry }
+    }
+
+    pub fn intrinsic_value(&self, spot: f64) -> f64 {
+        match self.kind {
+            LegKind::Underlying => spot,
+            LegKind::Call => (spot - self.strike).max(0.0),
+            LegKind::Put => (self.strike - spot).max(0.0),
+        }
+    }
+}
+
+/// Signed quantity to buy or sell at the bar's model price.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Order {
+    pub contract: Contract,
+    pub quantity: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub contract: Contract,
+    pub quantity: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeReason {
+    Order,
+    Expiry,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trade {
+    pub date: NaiveDate,
+    pub contract: Contract,
+    pub quantity: f64,
+    pub price: f64, // model mid price, before costs
+    pub costs: FillCosts,
+    pub reason: TradeReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EquityPoint {
+    pub date: NaiveDate,
+    pub cash: f64,
+    pub positions_value: f64,
+    pub equity: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BacktestResult {
+    pub trades: Vec<Trade>,
+    pub equity_curve: Vec<EquityPoint>,
+}
+
+impl BacktestResult {
+    pub fn total_costs(&self) -> FillCosts {
+        self.trades.iter().fold(FillCosts::default(), |total, trade| total + trade.costs)
+    }
+}
+
+/// What a strategy sees on each bar: the prices so far, the volatility used for pricing and the book.
+pub struct MarketState<'a> {
+    pub date: NaiveDate,
+    pub spot: f64,
+    pub vol: f64,
+    pub history: &'a [DataPoint], // bars up to and including the current one
+    pub positions: &'a [Position],
+    pub cash: f64,
+    pub backtester: &'a Backtester,
+}
+
+impl MarketState<'_> {
+    /// Price of one unit of the contract on this bar, the same the backtester fills and marks at.
+    pub fn contract_price(&self, contract: &Contract) -> f64 {
+        self.backtester.contract_price(contract, self.date, self.spot, self.vol)
+    }
+
+    /// Years from this bar to `date` under the backtester's day count.
+    pub fn years_until(&self, date: NaiveDate) -> f64 {
+        self.backtester.day_count.year_fraction(self.date, date, &self.backtester.calendar).max(0.0)
+    }
+}
+
+pub trait TradingStrategy {
+    /// Orders to fill at the close of the current bar.
+    fn on_bar(&mut self, market: &MarketState) -> Vec<Order>;
+}
+
+/// Buys the given contracts on the first bar and holds them, letting options expire.
+pub struct BuyAndHold {
+    pub orders: Vec<Order>,
+}
+
+impl BuyAndHold {
+    /// Orders for the entered strategy legs, expiring the given calendar days after `start`.
+    pub fn from_params(params: &MonteCarloParams, start: NaiveDate) -> BuyAndHold {
+        BuyAndHold {
+            orders: params
+                .strategy_legs
+                .iter()
+                .map(|leg| {
+                    let strike = leg.strike.parse::<f64>().unwrap_or(0.0);
+                    let days = leg.days_to_expire.parse::<u64>().unwrap_or(0);
+                    let expiry = start.checked_add_days(Days::new(days)).unwrap_or(start);
+                    let contract = match leg.kind {
+                        LegKind::Call => Contract::call(strike, expiry),
+                        LegKind::Put => Contract::put(strike, expiry),
+                        LegKind::Underlying => Contract::underlying(),
+                    };
+                    Order { contract, quantity: leg.quantity.parse::<f64>().unwrap_or(0.0) }
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TradingStrategy for BuyAndHold {
+    fn on_bar(&mut self, _market: &MarketState) -> Vec<Order> {
+        std::mem::take(&mut self.orders)
+    }
+}
+
+/// Holds `quantity` of an option struck `strike_offset` away from the spot, as a fraction of it, and
+/// expiring `days_to_expiry` calendar days out. The option is rolled into a new one once it is within
+/// `roll_days` of expiry, or reopened when it expires.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RollingOption {
+    pub kind: LegKind,
+    pub quantity: f64,
+    pub strike_offset: f64,
+    pub days_to_expiry: u64,
+    pub roll_days: u64,
+}
+
+impl TradingStrategy for RollingOption {
+    fn on_bar(&mut self, market: &MarketState) -> Vec<Order> {
+        let mut orders = Vec::new();
+        if let Some(position) = market.positions.iter().find(|p| p.contract.kind == self.kind) {
+            if (position.contract.expiry - market.date).num_days() > self.roll_days as i64 {
+                return orders;
+            }
+            orders.push(Order { contract: position.contract, quantity: -position.quantity });
+        }
+        let contract = Contract {
+            kind: self.kind,
+            strike: market.spot * (1.0 + self.strike_offset),
+            expiry: market.date.checked_add_days(Days::new(self.days_to_expiry)).unwrap_or(market.date),
+        };
+        orders.push(Order { contract, quantity: self.quantity });
+        orders
+    }
+}
+
+/// Event driven backtest over the bars of a chart. Options are European and priced with the configured
+/// model on each bar, with the volatility estimated from the trailing returns. Orders fill at the model
+/// price plus the costs of the cost model.
+pub struct Backtester {
+    pub pricing: MonteCarloPricing,
+    pub costs: CostModel,
+    pub initial_cash: f64,
+    pub vol_window: usize,
+    pub day_count: DayCount,
+    pub calendar: Calendar,
+}
+
+impl Backtester {
+    pub fn new(pricing: MonteCarloPricing, initial_cash: f64, day_count: DayCount, calendar: Calendar) -> Backtester {
+        Backtester { pricing, costs: CostModel::default(), initial_cash, vol_window: 20, day_count, calendar }
+    }
+
+    /// Annualised volatility of the returns in the trailing window ending at bar `index`. Without
+    /// quotes to imply it from, this is what options are priced with; the configured implied vol is
+    /// used until enough history has accumulated.
+    pub fn historical_vol(&self, chart: &PriceChart, index: usize) -> f64 {
+        if index < self.vol_window.max(2) {
+            return self.pricing.implied_vol;
+        }
+        let window = PriceChart::new(&chart.data[index - self.vol_window..=index]);
+        window.historical_volatility(window.periods_per_year(self.day_count, &self.calendar))
+    }
+
+    /// Model price of one unit of the contract on `date`. Models without a closed form are valued on
+    /// seeded paths, so the marks of successive bars share their random numbers.
+    pub fn contract_price(&self, contract: &Contract, date: NaiveDate, spot: f64, vol: f64) -> f64 {
+        if contract.kind == LegKind::Underlying || contract.expiry <= date {
+            return contract.intrinsic_value(spot);
+        }
+        let pricing = MonteCarloPricing {
+            current_asset_price: spot,
+            strike_price: contract.strike,
+            implied_vol: vol,
+            years_to_expire: self.day_count.year_fraction(date, contract.expiry, &self.calendar),
+            payoff: PayoffKind::Vanilla,
+            exercise: ExerciseStyle::European,
+            dividends: DividendSchedule::default(),
+            ..self.pricing.clone()
+        };
+        let call = pricing
+            .closed_form_price(spot)
+            .or_else(|| pricing.seeded_price(spot).ok())
+            .unwrap_or_else(|| contract.intrinsic_value(spot));
+        match contract.kind {
+            LegKind::Put => call - pricing.dividend_adjusted_spot() + contract.strike * pricing.discount_factor(pricing.years_to_expire),
+            _ => call,
+        }
+    }
+
+    /// Costs of filling an order on the bar. The vega is only needed, and bumped, for vol quoted spreads,
+    /// and the delta of options for slippage.
+    pub fn fill_costs(&self, order: &Order, bar: &DataPoint, vol: f64, price: f64) -> FillCosts {
+        let vega = if self.costs.spread_quote == SpreadQuote::Vol && self.costs.spread > 0.0 {
+            (self.contract_price(&order.contract, bar.date, bar.price, vol + VEGA_BUMP) - price) / VEGA_BUMP
+        } else {
+            0.0
+        };
+        let delta = if order.contract.kind == LegKind::Underlying {
+            1.0
+        } else if self.costs.slippage_impact > 0.0 && bar.volume > 0.0 {
+            let bump = DELTA_BUMP * bar.price;
+            (self.contract_price(&order.contract, bar.date, bar.price + bump, vol) - price) / bump
+        } else {
+            0.0
+        };
+        self.costs.fill_costs(order.contract.kind, order.quantity, price, delta, vega, bar.volume)
+    }
+
+    pub fn run(&self, chart: &PriceChart, strategy: &mut dyn TradingStrategy) -> BacktestResult {
+        self.run_from(chart, 0, strategy)
+    }
+
+    /// Trades from bar `start` on. The earlier bars are only history, warming up the volatility estimate.
+    pub fn run_from(&self, chart: &PriceChart, start: usize, strategy: &mut dyn TradingStrategy) -> BacktestResult {
+        let mut result = BacktestResult::default();
+        let mut positions: Vec<Position> = Vec::new();
+        let mut cash = self.initial_cash;
+
+        for (i, bar) in chart.data.iter().enumerate().skip(start) {
+            let vol = self.historical_vol(chart, i);
+
+            // Settle expired options at their intrinsic value
+            positions.retain(|position| {
+                if position.contract.expiry > bar.date {
+                    return true;
+                }
+                let price = position.contract.intrinsic_value(bar.price);
+                cash += position.quantity * price;
+                result.trades.push(Trade {
+                    date: bar.date,
+                    contract: position.contract,
+                    quantity: -position.quantity,
+                    price,
+                    costs: FillCosts::default(),
+                    reason: TradeReason::Expiry,
+                });
+                false
+            });
+
+            let market = MarketState { date: bar.date, spot: bar.price, vol, history: &chart.data[..=i], positions: &positions, cash, backtester: self };
+            for order in strategy.on_bar(&market) {
+                let price = self.contract_price(&order.contract, bar.date, bar.price, vol);
+                let costs = self.fill_costs(&order, bar, vol, price);
+                cash -= order.quantity * price + costs.total();
+                result.trades.push(Trade { date: bar.date, contract: order.contract, quantity: order.quantity, price, costs, reason: TradeReason::Order });
+                match positions.iter_mut().find(|p| p.contract == order.contract) {
+                    Some(position) => position.quantity += order.quantity,
+                    None => positions.push(Position { contract: order.contract, quantity: order.quantity }),
+                }
+            }
+            positions.retain(|p| p.quantity != 0.0);
+
+            let positions_value: f64 = positions.iter().map(|p| p.quantity * self.contract_price(&p.contract, bar.date, bar.price, vol)).sum();
+            result.equity_curve.push(EquityPoint { date: bar.date, cash, positions_value, equity: cash + positions_value });
+        }
+        result
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::monte_carlo::SimulationModel;
+    use approx::assert_abs_diff_eq;
+
+    fn synthetic_chart() -> PriceChart {
+        let prices: Vec<f64> = (0..40).map(|i| 100.0 * (1.0 + 0.01 * (i as f64 * 0.7).sin()) + 0.25 * i as f64).collect();
+        PriceChart::from_prices_and_date(prices, NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(), &Calendar::WeekendsOnly)
+    }
+
+    fn backtester() -> Backtester {
+        let pricing = MonteCarloPricing { risk_free_rate: 0.03, implied_vol: 0.2, ..MonteCarloPricing::default() };
+        Backtester::new(pricing, 1000.0, DayCount::Bus252, Calendar::WeekendsOnly)
+    }
+
+    #[test]
+    fn test_buy_and_hold_tracks_the_underlying() {
+        let chart = synthetic_chart();
+        let result = backtester().run(&chart, &mut BuyAndHold { orders: vec![Order { contract: Contract::underlying(), quantity: 2.0 }] });
+        assert_eq!(result.trades.len(), 1);
+        assert_eq!(result.equity_curve.len(), chart.data.len());
+        for (point, bar) in result.equity_curve.iter().zip(&chart.data) {
+            assert_abs_diff_eq!(point.equity, 1000.0 + 2.0 * (bar.price - chart.data[0].price), epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_options_are_marked_and_settled() {
+        let chart = synthetic_chart();
+        let expiry = chart.data[10].date;
+        let contracts = [Contract::call(100.0, expiry), Contract::put(105.0, expiry)];
+        let orders = contracts.iter().map(|&contract| Order { contract, quantity: 1.0 }).collect();
+        let backtester = backtester();
+        let result = backtester.run(&chart, &mut BuyAndHold { orders });
+
+        // Both legs are bought at the model price, then settled at intrinsic value on the expiry bar
+        assert_eq!(result.trades.len(), 4);
+        let settled: Vec<&Trade> = result.trades.iter().filter(|t| t.reason == TradeReason::Expiry).collect();
+        assert_eq!(settled.len(), 2);
+        assert!(settled.iter().all(|t| t.date == expiry && t.quantity == -1.0));
+        assert_abs_diff_eq!(settled[0].price, (chart.data[10].price - 100.0).max(0.0), epsilon = 1e-12);
+        assert_eq!(result.equity_curve[10].positions_value, 0.0);
+        assert_abs_diff_eq!(result.equity_curve[39].equity, result.equity_curve[10].equity, epsilon = 1e-12);
+
+        // Before expiry the book is worth the model value of the options
+        let first = &chart.data[0];
+        let call = backtester.contract_price(&contracts[0], first.date, first.price, 0.2);
+        let put = backtester.contract_price(&contracts[1], first.date, first.price, 0.2);
+        assert_abs_diff_eq!(result.equity_curve[0].positions_value, call + put, epsilon = 1e-9);
+        assert_abs_diff_eq!(result.equity_curve[0].equity, 1000.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_costs_are_charged_on_fills() {
+        let chart = synthetic_chart();
+        let mut backtester = backtester();
+        backtester.costs = CostModel { commission_per_order: 0.5, commission_per_contract: 1.0, notional_rate: 0.001, ..CostModel::default() };
+        let orders = vec![Order { contract: Contract::underlying(), quantity: 2.0 }, Order { contract: Contract::call(100.0, chart.data[10].date), quantity: 1.0 }];
+        let result = backtester.run(&chart, &mut BuyAndHold { orders });
+
+        let costs = result.total_costs();
+        assert_abs_diff_eq!(costs.commission, 2.0 * 0.5 + 1.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(costs.fees, 0.001 * (2.0 * chart.data[0].price + result.trades[1].price), epsilon = 1e-12);
+        assert_abs_diff_eq!(result.equity_curve[0].equity, 1000.0 - costs.total(), epsilon = 1e-9);
+        // Settlement at expiry is free
+        assert_eq!(result.trades[2].costs, FillCosts::default());
+    }
+
+    #[test]
+    fn test_historical_vol_after_warm_up() {
+        let chart = synthetic_chart();
+        let backtester = backtester();
+        assert_eq!(backtester.historical_vol(&chart, 5), 0.2);
+        let vol = backtester.historical_vol(&chart, 30);
+        assert!(vol > 0.0 && vol != 0.2);
+    }
+
+    #[test]
+    fn test_monte_carlo_marks_are_repeatable() {
+        let mut backtester = backtester();
+        backtester.pricing = MonteCarloPricing { model: SimulationModel::Heston, num_simulations: 500, num_steps: 20, ..backtester.pricing };
+        let date = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+        let call = Contract::call(100.0, NaiveDate::from_ymd_opt(2025, 4, 7).unwrap());
+        let mark = backtester.contract_price(&call, date, 100.0, 0.2);
+        assert!(mark > 0.0);
+        assert_eq!(backtester.contract_price(&call, date, 100.0, 0.2), mark);
+        assert!(backtester.contract_price(&call, date, 101.0, 0.2) > mark);
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt;
+
+use super::monte_carlo::{MonteCarloPricing, SimulationModel};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BarrierDirection {
+    #[default]
+    Down,
+    Up,
+}
+
+impl BarrierDirection {
+    pub const ALL: [BarrierDirection; 2] = [BarrierDirection::Down, BarrierDirection::Up];
+}
+
+impl fmt::Display for BarrierDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BarrierDirection::Down => write!(f, "Down"),
+            BarrierDirection::Up => write!(f, "Up"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BarrierKnock {
+    In,
+    #[default]
+    Out,
+}
+
+impl BarrierKnock {
+    pub const ALL: [BarrierKnock; 2] = [BarrierKnock::In, BarrierKnock::Out];
+}
+
+impl fmt::Display for BarrierKnock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BarrierKnock::In => write!(f, "Knock-in"),
+            BarrierKnock::Out => write!(f, "Knock-out"),
+        }
+    }
+}
+
+/// A continuously monitored barrier on a call. Knock-out rebates are paid when the barrier is hit,
+/// knock-in rebates at expiry when it never was.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BarrierOption {
+    pub direction: BarrierDirection,
+    pub knock: BarrierKnock,
+    pub level: f64,
+    pub rebate: f64,
+    pub brownian_bridge: bool, // correct the per-step monitoring for crossings between steps
+}
+
+impl Default for BarrierOption {
+    fn default() -> Self {
+        BarrierOption { direction: BarrierDirection::Down, knock: BarrierKnock::Out, level: 90.0, rebate: 0.0, brownian_bridge: true }
+    }
+}
+
+impl BarrierOption {
+    pub fn is_breached(&self, spot: f64) -> bool {
+        match self.direction {
+            BarrierDirection::Down => spot <= self.level,
+            BarrierDirection::Up => spot >= self.level,
+        }
+    }
+
+    /// Probability that a Brownian bridge in log spot between two monitoring points touches the barrier.
+    pub fn crossing_probability(&self, start: f64, end: f64, vol: f64, dt: f64) -> f64 {
+        if self.is_breached(start) || self.is_breached(end) {
+            return 1.0;
+        }
+        if !self.brownian_bridge || vol <= 0.0 {
+            return 0.0;
+        }
+        let barrier = self.level.ln();
+        (-2.0 * (barrier - start.ln()) * (barrier - end.ln()) / (vol.powi(2) * dt)).exp()
+    }
+}
+
+impl MonteCarloPricing {
+    // The bridge needs a diffusion coefficient between monitoring points; stochastic vol models use
+    // their initial level.
+    fn bridge_vol(&self, t: f64, spot: f64) -> f64 {
+        match self.model {
+            SimulationModel::Heston => self.heston.v0.sqrt(),
+            SimulationModel::LocalVol => self.local_vol.as_ref().map_or(self.implied_vol, |s| s.vol(t, spot)),
+            SimulationModel::Sabr => self.sabr.alpha * spot.powf(self.sabr.beta - 1.0),
+            SimulationModel::Bootstrap => self.bootstrap.volatility(),
+            _ => self.implied_vol,
+        }
+    }
+
+    /// Present value of the barrier call along one path. Rather than a knocked/not knocked indicator,
+    /// every step contributes its crossing probability, which gives the conditional expectation of the
+    /// payoff given the monitoring points.
+    pub fn barrier_payoff(&self, path: &[f64]) -> f64 {
+        let barrier = &self.barrier;
+        let dt = self.years_to_expire / (path.len() - 1) as f64;
+        let mut survival = 1.0;
+        let mut rebate_value = 0.0;
+        for (i, w) in path.windows(2).enumerate() {
+            let hit = barrier.crossing_probability(w[0], w[1], self.bridge_vol(i as f64 * dt, w[0]), dt);
+            if barrier.knock == BarrierKnock::Out {
+                rebate_value += survival * hit * barrier.rebate * self.discount_factor((i + 1) as f64 * dt);
+            }
+            survival *= 1.0 - hit;
+        }
+
+        let expiry_discount = self.discount_factor(self.years_to_expire);
+        let vanilla = (path[path.len() - 1] - self.strike_price).max(0.0) * expiry_discount;
+        match barrier.knock {
+            BarrierKnock::Out => survival * vanilla + rebate_value,
+            BarrierKnock::In => (1.0 - survival) * vanilla + survival * barrier.rebate * expiry_discount,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::analytic::barrier_call;
+    use crate::model::calendar::Calendar;
+    use crate::model::chart::PriceChart;
+    use crate::model::monte_carlo::PayoffKind;
+    use approx::assert_abs_diff_eq;
+
+    fn pricing(barrier: BarrierOption) -> MonteCarloPricing {
+        MonteCarloPricing {
+            current_asset_price: 100.0,
+            strike_price: 100.0,
+            num_simulations: 40000,
+            num_steps: 25,
+            risk_free_rate: 0.05,
+            implied_vol: 0.25,
+            years_to_expire: 0.5,
+            dividend_yield: 0.02,
+            payoff: PayoffKind::Barrier,
+            barrier,
+            ..MonteCarloPricing::default()
+        }
+    }
+
+    #[test]
+    fn test_crossing_probability() {
+        let barrier = BarrierOption { level: 90.0, ..BarrierOption::default() };
+        assert_eq!(barrier.crossing_probability(100.0, 89.0, 0.2, 0.01), 1.0);
+        let near = barrier.crossing_probability(91.0, 91.0, 0.2, 0.01);
+        let far = barrier.crossing_probability(100.0, 100.0, 0.2, 0.01);
+        assert!(near > far && far > 0.0);
+        let discrete = BarrierOption { brownian_bridge: false, ..barrier };
+        assert_eq!(discrete.crossing_probability(91.0, 91.0, 0.2, 0.01), 0.0);
+    }
+
+    #[test]
+    fn test_bridge_corrected_price_matches_reiner_rubinstein() {
+        let chart = PriceChart::from_prices_and_date(vec![100.0], chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), &Calendar::Nyse);
+        let barriers = [
+            BarrierOption { direction: BarrierDirection::Down, knock: BarrierKnock::Out, level: 90.0, rebate: 3.0, brownian_bridge: true },
+            BarrierOption { direction: BarrierDirection::Down, knock: BarrierKnock::In, level: 95.0, rebate: 0.0, brownian_bridge: true },
+            BarrierOption { direction: BarrierDirection::Up, knock: BarrierKnock::Out, level: 120.0, rebate: 1.0, brownian_bridge: true },
+            BarrierOption { direction: BarrierDirection::Up, knock: BarrierKnock::In, level: 110.0, rebate: 2.0, brownian_bridge: true },
+        ];
+        for barrier in barriers {
+            let mc = pricing(barrier);
+            let simulated = mc.price(&chart).unwrap();
+            let analytic = barrier_call(&barrier, 100.0, 100.0, 0.05, 0.03, 0.25, 0.5);
+            assert!((simulated - analytic).abs() < 0.15, "{:?}: {simulated} vs {analytic}", barrier);
+        }
+    }
+
+    #[test]
+    fn test_discrete_monitoring_overprices_knock_out() {
+        let path_count = 20000;
+        let corrected = pricing(BarrierOption { level: 95.0, ..BarrierOption::default() });
+        let discrete = pricing(BarrierOption { level: 95.0, brownian_bridge: false, ..BarrierOption::default() });
+        let mut rng = rand::rng();
+        let (mut corrected_sum, mut discrete_sum) = (0.0, 0.0);
+        for _ in 0..path_count {
+            let path = corrected.simulate_path(100.0, &mut rng);
+            corrected_sum += corrected.barrier_payoff(&path);
+            discrete_sum += discrete.barrier_payoff(&path);
+        }
+        assert!(discrete_sum > corrected_sum);
+        assert_abs_diff_eq!(corrected.barrier_payoff(&[100.0, 80.0, 130.0]), 0.0, epsilon = 1e-12);
+    }
+}
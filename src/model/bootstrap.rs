@@ -0,0 +1,189 @@
+use rand::Rng;
+use std::fmt;
+
+use super::chart::PriceChart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BootstrapScheme {
+    #[default]
+    Iid,        // every return drawn independently
+    Block,      // circular blocks of a fixed length, keeping short range dependence
+    Stationary, // Politis-Romano blocks of geometric length, so the resampled series stays stationary
+}
+
+impl BootstrapScheme {
+    pub const ALL: [BootstrapScheme; 3] = [BootstrapScheme::Iid, BootstrapScheme::Block, BootstrapScheme::Stationary];
+}
+
+impl fmt::Display for BootstrapScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BootstrapScheme::Iid => write!(f, "IID"),
+            BootstrapScheme::Block => write!(f, "Block"),
+            BootstrapScheme::Stationary => write!(f, "Stationary"),
+        }
+    }
+}
+
+/// Resamples the demeaned historical log returns of a chart instead of drawing normal increments.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HistoricalBootstrap {
+    pub returns: Vec<f64>, // one per bar, demeaned and rescaled to the target vol
+    pub periods_per_year: f64,
+    pub scheme: BootstrapScheme,
+    pub block_length: usize, // fixed length of the blocks, or their mean length for the stationary bootstrap
+}
+
+impl HistoricalBootstrap {
+    /// Returns of the chart with their mean removed, leaving the drift to the pricing measure. When a
+    /// target vol is given they are rescaled to it, keeping the shape of their distribution.
+    pub fn from_chart(chart: &PriceChart, periods_per_year: f64, scheme: BootstrapScheme, block_length: usize, target_vol: Option<f64>) -> HistoricalBootstrap {
+        let log_returns = chart.log_returns();
+        let mean = log_returns.iter().sum::<f64>() / log_returns.len().max(1) as f64;
+        let mut bootstrap = HistoricalBootstrap {
+            returns: log_returns.iter().map(|r| r - mean).collect(),
+            periods_per_year,
+            scheme,
+            block_length: block_length.max(1),
+        };
+        let vol = bootstrap.volatility();
+        if let Some(target) = target_vol.filter(|_| vol > 0.0) {
+            bootstrap.returns.iter_mut().for_each(|r| *r *= target / vol);
+        }
+        bootstrap
+    }
+
+    /// Annualised volatility of the resampled returns.
+    pub fn volatility(&self) -> f64 {
+        let n = self.returns.len() as f64;
+        (self.returns.iter().map(|r| r * r).sum::<f64>() / n.max(1.0) * self.periods_per_year).sqrt()
+    }
+
+    /// Indices of `count` resampled returns, in the order they are used. Empty when there are no returns.
+    pub fn sample_indices<R: Rng + ?Sized>(&self, count: usize, rng: &mut R) -> Vec<usize> {
+        let n = self.returns.len();
+        if n == 0 {
+            return Vec::new();
+        }
+        let mut indices = Vec::with_capacity(count);
+        let mut current = rng.random_range(0..n);
+        for step in 0..count {
+            if step > 0 {
+                let new_block = match self.scheme {
+                    BootstrapScheme::Iid => true,
+                    BootstrapScheme::Block => step % self.block_length == 0,
+                    BootstrapScheme::Stationary => rng.random::<f64>() < 1.0 / self.block_length as f64,
+                };
+                current = if new_block { rng.random_range(0..n) } else { (current + 1) % n };
+            }
+            indices.push(current);
+        }
+        indices
+    }
+
+    /// Number of bars making up a step of `dt` years, at least one.
+    pub fn bars_per_step(&self, dt: f64) -> usize {
+        ((dt * self.periods_per_year).round() as usize).max(1)
+    }
+
+    /// Log of the mean gross return of a step summing `bars` consecutive returns scaled by `scale`. The
+    /// returns are independent under the IID scheme; the block schemes keep runs of consecutive returns,
+    /// so their steps are averaged over every circular window of the history instead.
+    fn step_convexity(&self, bars: usize, scale: f64) -> f64 {
+        let n = self.returns.len();
+        if n == 0 {
+            return 0.0;
+        }
+        if self.scheme == BootstrapScheme::Iid {
+            return bars as f64 * (self.returns.iter().map(|r| (scale * r).exp()).sum::<f64>() / n as f64).ln();
+        }
+        let window_sum = |start: usize| (0..bars).map(|j| self.returns[(start + j) % n]).sum::<f64>();
+        ((0..n).map(|start| (scale * window_sum(start)).exp()).sum::<f64>() / n as f64).ln()
+    }
+
+    /// Simulates a path whose steps sum as many consecutive resampled returns as there are bars in the
+    /// step. When the step is not a whole number of bars the sum is rescaled to the variance of the
+    /// step. Subtracting the log of the mean gross return makes each step earn the carry in expectation
+    /// under the empirical distribution, as the risk neutral measure requires.
+    pub fn simulate_path<R: Rng + ?Sized>(&self, spot: f64, carries: &[f64], dt: f64, rng: &mut R) -> Vec<f64> {
+        let bars = self.bars_per_step(dt);
+        let scale = (dt * self.periods_per_year / bars as f64).sqrt();
+        let convexity = self.step_convexity(bars, scale);
+        let indices = self.sample_indices(carries.len() * bars, rng);
+        let mut path = Vec::with_capacity(carries.len() + 1);
+        path.push(spot);
+        let mut log_spot = spot.ln();
+        for (step, carry) in carries.iter().enumerate() {
+            let step_return: f64 = indices.get(step * bars..(step + 1) * bars).unwrap_or(&[]).iter().map(|i| self.returns[*i]).sum();
+            log_spot += carry * dt + scale * step_return - convexity;
+            path.push(log_spot.exp());
+        }
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::calendar::Calendar;
+    use approx::assert_abs_diff_eq;
+    use chrono::NaiveDate;
+
+    fn bootstrap(scheme: BootstrapScheme, target_vol: Option<f64>) -> HistoricalBootstrap {
+        let prices: Vec<f64> = (0..60).map(|i| 100.0 * (0.01 * i as f64 + 0.05 * (i as f64 * 1.3).sin()).exp()).collect();
+        let chart = PriceChart::from_prices_and_date(prices, NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(), &Calendar::WeekendsOnly);
+        HistoricalBootstrap::from_chart(&chart, 252.0, scheme, 5, target_vol)
+    }
+
+    #[test]
+    fn test_returns_are_demeaned_and_rescaled() {
+        let plain = bootstrap(BootstrapScheme::Iid, None);
+        assert_eq!(plain.returns.len(), 59);
+        assert_abs_diff_eq!(plain.returns.iter().sum::<f64>(), 0.0, epsilon = 1e-12);
+        let scaled = bootstrap(BootstrapScheme::Iid, Some(0.3));
+        assert_abs_diff_eq!(scaled.volatility(), 0.3, epsilon = 1e-12);
+        assert_abs_diff_eq!(scaled.returns[7] / plain.returns[7], 0.3 / plain.volatility(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_blocks_are_consecutive() {
+        let mut rng = rand::rng();
+        let n = 59;
+        let block = bootstrap(BootstrapScheme::Block, None).sample_indices(20, &mut rng);
+        for (step, pair) in block.windows(2).enumerate() {
+            if (step + 1) % 5 != 0 {
+                assert_eq!(pair[1], (pair[0] + 1) % n);
+            }
+        }
+        // Stationary blocks break with probability 1/5 each step
+        let stationary = bootstrap(BootstrapScheme::Stationary, None).sample_indices(10000, &mut rng);
+        let breaks = stationary.windows(2).filter(|pair| pair[1] != (pair[0] + 1) % n).count();
+        assert!((breaks as f64 / 9999.0 - 0.2).abs() < 0.03, "{}", breaks);
+    }
+
+    #[test]
+    fn test_paths_are_martingales() {
+        let bootstrap = bootstrap(BootstrapScheme::Iid, Some(0.25));
+        let mut rng = rand::rng();
+        let carries = vec![0.05; 4];
+        let dt = 0.25;
+        let mean = (0..20000).map(|_| bootstrap.simulate_path(100.0, &carries, dt, &mut rng)[4]).sum::<f64>() / 20000.0;
+        assert_abs_diff_eq!(mean, 100.0 * 0.05f64.exp(), epsilon = 0.8);
+
+        // Weekly steps sum five consecutive daily returns of a block bootstrap
+        let block = HistoricalBootstrap { scheme: BootstrapScheme::Block, ..bootstrap };
+        assert_eq!(block.bars_per_step(5.0 / 252.0), 5);
+        let weekly = vec![0.05; 10];
+        let mean = (0..20000).map(|_| block.simulate_path(100.0, &weekly, 5.0 / 252.0, &mut rng)[10]).sum::<f64>() / 20000.0;
+        assert_abs_diff_eq!(mean, 100.0 * (0.05f64 * 50.0 / 252.0).exp(), epsilon = 0.5);
+    }
+
+    #[test]
+    fn test_empty_history_does_not_panic() {
+        let empty = HistoricalBootstrap { periods_per_year: 252.0, block_length: 5, ..HistoricalBootstrap::default() };
+        assert!(empty.sample_indices(10, &mut rand::rng()).is_empty());
+        let path = empty.simulate_path(100.0, &[0.0; 3], 1.0 / 252.0, &mut rand::rng());
+        assert_eq!(path.len(), 4);
+        assert_abs_diff_eq!(path[3], 100.0, epsilon = 1e-9);
+    }
+}
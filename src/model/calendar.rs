@@ -0,0 +1,244 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+
+const BUSINESS_DAYS_IN_YEAR: f64 = 252.0;
+const JUNETEENTH_FIRST_YEAR: i32 = 2022;
+const MLK_DAY_FIRST_YEAR: i32 = 1998;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DayCount {
+    #[default]
+    Act365Fixed,
+    Act360,
+    Thirty360, // US bond basis
+    Bus252,    // business days of the holiday calendar over 252
+}
+
+impl DayCount {
+    pub const ALL: [DayCount; 4] = [DayCount::Act365Fixed, DayCount::Act360, DayCount::Thirty360, DayCount::Bus252];
+
+    /// Year fraction between two dates. Only BUS/252 depends on the holiday calendar.
+    pub fn year_fraction(&self, start: NaiveDate, end: NaiveDate, calendar: &Calendar) -> f64 {
+        let actual_days = (end - start).num_days() as f64;
+        match self {
+            DayCount::Act365Fixed => actual_days / 365.0,
+            DayCount::Act360 => actual_days / 360.0,
+            DayCount::Thirty360 => {
+                let d1 = start.day().min(30);
+                let d2 = if d1 == 30 { end.day().min(30) } else { end.day() };
+                let days = 360 * (end.year() - start.year())
+                    + 30 * (end.month() as i32 - start.month() as i32)
+                    + (d2 as i32 - d1 as i32);
+                days as f64 / 360.0
+            }
+            DayCount::Bus252 => {
+                if end < start {
+                    return -self.year_fraction(end, start, calendar);
+                }
+                calendar.business_days_between(start, end) as f64 / BUSINESS_DAYS_IN_YEAR
+            }
+        }
+    }
+}
+
+impl fmt::Display for DayCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DayCount::Act365Fixed => write!(f, "ACT/365F"),
+            DayCount::Act360 => write!(f, "ACT/360"),
+            DayCount::Thirty360 => write!(f, "30/360"),
+            DayCount::Bus252 => write!(f, "BUS/252"),
+        }
+    }
+}
+
+/// Exchange holiday calendar. Weekends are never business days.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Calendar {
+    #[default]
+    Nyse,
+    WeekendsOnly,
+    Custom { name: String, holidays: BTreeSet<NaiveDate> },
+}
+
+impl Calendar {
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        match self {
+            Calendar::Nyse => nyse_holidays(date.year()).contains(&date),
+            Calendar::WeekendsOnly => false,
+            Calendar::Custom { holidays, .. } => holidays.contains(&date),
+        }
+    }
+
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.is_holiday(date)
+    }
+
+    /// The date itself when it is a business day, otherwise the next business day.
+    pub fn roll_forward(&self, date: NaiveDate) -> NaiveDate {
+        let mut date = date;
+        while !self.is_business_day(date) {
+            date = date.succ_opt().unwrap();
+        }
+        date
+    }
+
+    pub fn add_business_days(&self, date: NaiveDate, count: u64) -> NaiveDate {
+        let mut date = date;
+        for _ in 0..count {
+            date = self.roll_forward(date.succ_opt().unwrap());
+        }
+        date
+    }
+
+    /// Number of business days after `start` up to and including `end`.
+    pub fn business_days_between(&self, start: NaiveDate, end: NaiveDate) -> u32 {
+        start.iter_days().skip(1).take_while(|d| *d <= end).filter(|d| self.is_business_day(*d)).count() as u32
+    }
+
+    /// Reads holidays from a file with one `YYYY-MM-DD[,description]` date per line. The calendar is
+    /// named after the file.
+    pub fn load(path: &str) -> Result<Calendar, Box<dyn Error>> {
+        let name = Path::new(path).file_stem().map_or(path.to_string(), |s| s.to_string_lossy().into_owned());
+        Calendar::parse(&name, &fs::read_to_string(path)?)
+    }
+
+    pub fn parse(name: &str, contents: &str) -> Result<Calendar, Box<dyn Error>> {
+        let mut holidays = BTreeSet::new();
+        for line in contents.lines().map(|l| l.trim()).filter(|l| !l.is_empty() && !l.starts_with("date")) {
+            let date = line.split(',').next().unwrap_or_default().trim();
+            holidays.insert(NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|e| format!("Invalid date '{}': {}", date, e))?);
+        }
+        Ok(Calendar::Custom { name: name.to_string(), holidays })
+    }
+}
+
+impl fmt::Display for Calendar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Calendar::Nyse => write!(f, "NYSE"),
+            Calendar::WeekendsOnly => write!(f, "Weekends only"),
+            Calendar::Custom { name, .. } => write!(f, "{}", name),
+        }
+    }
+}
+
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u8) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, n).unwrap()
+}
+
+fn last_weekday(year: i32, month: u32, weekday: Weekday) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, 5).unwrap_or_else(|| nth_weekday(year, month, weekday, 4))
+}
+
+// Saturday holidays are observed on the Friday before, Sunday holidays on the Monday after
+fn observed(date: NaiveDate) -> NaiveDate {
+    match date.weekday() {
+        Weekday::Sat => date.pred_opt().unwrap(),
+        Weekday::Sun => date.succ_opt().unwrap(),
+        _ => date,
+    }
+}
+
+// Anonymous Gregorian algorithm
+fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).unwrap()
+}
+
+/// Full-day NYSE closures of a year. Special closures (national days of mourning, weather) are not
+/// rule based and belong in a custom calendar.
+fn nyse_holidays(year: i32) -> Vec<NaiveDate> {
+    let fixed = |month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    let mut holidays = vec![
+        nth_weekday(year, 2, Weekday::Mon, 3),  // Washington's Birthday
+        easter_sunday(year) - Days::new(2),     // Good Friday
+        last_weekday(year, 5, Weekday::Mon),    // Memorial Day
+        observed(fixed(7, 4)),                  // Independence Day
+        nth_weekday(year, 9, Weekday::Mon, 1),  // Labor Day
+        nth_weekday(year, 11, Weekday::Thu, 4), // Thanksgiving
+        observed(fixed(12, 25)),                // Christmas
+    ];
+    // A New Year's Day on a Saturday is not observed on the Friday, which closes the previous year
+    if fixed(1, 1).weekday() != Weekday::Sat {
+        holidays.push(observed(fixed(1, 1)));
+    }
+    if year >= MLK_DAY_FIRST_YEAR {
+        holidays.push(nth_weekday(year, 1, Weekday::Mon, 3));
+    }
+    if year >= JUNETEENTH_FIRST_YEAR {
+        holidays.push(observed(fixed(6, 19)));
+    }
+    holidays
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_nyse_holidays() {
+        let mut holidays = nyse_holidays(2025);
+        holidays.sort();
+        let expected = [
+            date(2025, 1, 1), date(2025, 1, 20), date(2025, 2, 17), date(2025, 4, 18), date(2025, 5, 26),
+            date(2025, 6, 19), date(2025, 7, 4), date(2025, 9, 1), date(2025, 11, 27), date(2025, 12, 25),
+        ];
+        assert_eq!(holidays, expected);
+        // Independence Day 2026 falls on a Saturday, New Year's Day 2022 on a Saturday
+        assert!(Calendar::Nyse.is_holiday(date(2026, 7, 3)));
+        assert!(Calendar::Nyse.is_business_day(date(2021, 12, 31)));
+    }
+
+    #[test]
+    fn test_business_days() {
+        let calendar = Calendar::Nyse;
+        assert_eq!(calendar.roll_forward(date(2025, 1, 1)), date(2025, 1, 2));
+        assert_eq!(calendar.add_business_days(date(2025, 1, 17), 1), date(2025, 1, 21));
+        assert_eq!(calendar.business_days_between(date(2025, 1, 1), date(2025, 12, 31)), 251);
+        assert_eq!(Calendar::WeekendsOnly.business_days_between(date(2025, 1, 3), date(2025, 1, 10)), 5);
+    }
+
+    #[test]
+    fn test_day_counts() {
+        let (start, end) = (date(2025, 1, 31), date(2025, 7, 31));
+        let calendar = Calendar::Nyse;
+        assert_abs_diff_eq!(DayCount::Act365Fixed.year_fraction(start, end, &calendar), 181.0 / 365.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(DayCount::Act360.year_fraction(start, end, &calendar), 181.0 / 360.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(DayCount::Thirty360.year_fraction(start, end, &calendar), 0.5, epsilon = 1e-12);
+        let business_days = calendar.business_days_between(start, end) as f64;
+        assert_abs_diff_eq!(DayCount::Bus252.year_fraction(start, end, &calendar), business_days / 252.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_parse() {
+        let calendar = Calendar::parse("lse", "date,description\n2025-08-25,Summer bank holiday\n2025-12-26\n").unwrap();
+        assert!(calendar.is_holiday(date(2025, 12, 26)));
+        assert!(!calendar.is_business_day(date(2025, 8, 25)));
+        assert_eq!(calendar.to_string(), "lse");
+        assert!(Calendar::parse("lse", "26/12/2025").is_err());
+    }
+}
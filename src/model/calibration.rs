@@ -0,0 +1,209 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use rayon::prelude::*;
+
+use super::heston::HestonParams;
+use super::optimize::nelder_mead;
+use super::utils::implied_vol_newton;
+use super::yield_curve::YieldCurve;
+
+const MAX_ITERATIONS: usize = 2000;
+const SIMPLEX_STEP: f64 = 0.3;
+const OBJECTIVE_TOLERANCE: f64 = 1e-10;
+const IMPLIED_VOL_GUESS: f64 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptionQuote {
+    pub strike: f64,
+    pub years_to_expire: f64,
+    pub price: f64,
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CalibrationTarget {
+    #[default]
+    Price,
+    ImpliedVol,
+}
+
+impl CalibrationTarget {
+    pub const ALL: [CalibrationTarget; 2] = [CalibrationTarget::Price, CalibrationTarget::ImpliedVol];
+}
+
+impl fmt::Display for CalibrationTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalibrationTarget::Price => write!(f, "Price errors"),
+            CalibrationTarget::ImpliedVol => write!(f, "Implied vol errors"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CalibrationResult {
+    pub params: HestonParams,
+    pub rmse: f64,
+    pub residuals: Vec<f64>, // model minus market, in units of the calibration target
+}
+
+/// Reads an option chain from a CSV file with the columns `strike,days_to_expire,price[,weight]`.
+/// A header line is allowed, and the weight defaults to 1 when it is missing. The calendar days to
+/// expire are converted to years by `to_years`.
+pub fn load_option_chain<F: Fn(u64) -> f64>(path: &str, to_years: F) -> Result<Vec<OptionQuote>, Box<dyn Error>> {
+    parse_option_chain(&fs::read_to_string(path)?, to_years)
+}
+
+pub fn parse_option_chain<F: Fn(u64) -> f64>(contents: &str, to_years: F) -> Result<Vec<OptionQuote>, Box<dyn Error>> {
+    let mut quotes = Vec::new();
+    for (line_number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("strike") {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+        if fields.len() < 3 {
+            return Err(format!("Line {}: expected strike, days to expire and price", line_number + 1).into());
+        }
+        quotes.push(OptionQuote {
+            strike: fields[0].parse()?,
+            years_to_expire: to_years(fields[1].parse()?),
+            price: fields[2].parse()?,
+            weight: fields.get(3).map(|w| w.parse()).transpose()?.unwrap_or(1.0),
+        });
+    }
+    if quotes.is_empty() {
+        return Err("Option chain contains no quotes".into());
+    }
+    Ok(quotes)
+}
+
+// The optimiser works on unconstrained coordinates, mapped onto the admissible parameter ranges
+fn to_unconstrained(params: &HestonParams) -> Vec<f64> {
+    vec![params.kappa.ln(), params.theta.ln(), params.xi.ln(), params.rho.clamp(-0.999, 0.999).atanh(), params.v0.ln()]
+}
+
+fn from_unconstrained(x: &[f64]) -> HestonParams {
+    HestonParams { kappa: x[0].exp(), theta: x[1].exp(), xi: x[2].exp(), rho: x[3].tanh(), v0: x[4].exp() }
+}
+
+/// Price errors, or implied vol errors against `market_vols` when given. Each model vol is inverted
+/// starting from the market vol of its quote, which is usually only a few Newton steps away.
+fn residuals(params: &HestonParams, quotes: &[OptionQuote], spots: &[f64], curve: &YieldCurve, market_vols: Option<&[f64]>) -> Vec<f64> {
+    quotes
+        .par_iter()
+        .enumerate()
+        .map(|(i, q)| {
+            let rate = curve.zero_rate(q.years_to_expire);
+            let model_price = params.call_price(spots[i], q.strike, rate, q.years_to_expire);
+            match market_vols {
+                None => model_price - q.price,
+                Some(vols) => implied_vol_newton(model_price, spots[i], q.strike, rate, q.years_to_expire, vols[i]) - vols[i],
+            }
+        })
+        .collect()
+}
+
+/// Fits Heston parameters to market call prices by minimising the weighted squared errors.
+/// Every quote is discounted at the zero rate of its own expiry and priced off `spots`, the spot net
+/// of the dividends paid before its expiry.
+pub fn calibrate_heston(quotes: &[OptionQuote], spots: &[f64], curve: &YieldCurve, target: CalibrationTarget, initial: HestonParams) -> CalibrationResult {
+    let total_weight: f64 = quotes.iter().map(|q| q.weight).sum();
+    // The market vols do not depend on the parameters, so they are inverted once up front
+    let market_vols: Option<Vec<f64>> = (target == CalibrationTarget::ImpliedVol).then(|| {
+        quotes
+            .iter()
+            .zip(spots)
+            .map(|(q, spot)| implied_vol_newton(q.price, *spot, q.strike, curve.zero_rate(q.years_to_expire), q.years_to_expire, IMPLIED_VOL_GUESS))
+            .collect()
+    });
+    let market_vols = market_vols.as_deref();
+    let objective = |x: &[f64]| {
+        let params = from_unconstrained(x);
+        residuals(&params, quotes, spots, curve, market_vols)
+            .iter()
+            .zip(quotes)
+            .map(|(r, q)| q.weight * r.powi(2))
+            .sum::<f64>() / total_weight
+    };
+
+    let (best, mse) = nelder_mead(objective, &to_unconstrained(&initial), SIMPLEX_STEP, MAX_ITERATIONS, OBJECTIVE_TOLERANCE);
+    let params = from_unconstrained(&best);
+    CalibrationResult {
+        params,
+        rmse: mse.sqrt(),
+        residuals: residuals(&params, quotes, spots, curve, market_vols),
+    }
+}
+
+impl HestonParams {
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &str) -> Result<HestonParams, Box<dyn Error>> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn synthetic_quotes(params: &HestonParams) -> Vec<OptionQuote> {
+        let mut quotes = Vec::new();
+        for years in [0.25, 1.0] {
+            for strike in [90.0, 100.0, 110.0] {
+                quotes.push(OptionQuote { strike, years_to_expire: years, price: params.call_price(100.0, strike, 0.02, years), weight: 1.0 });
+            }
+        }
+        quotes
+    }
+
+    #[test]
+    fn test_parse_option_chain() {
+        let quotes = parse_option_chain("strike,days_to_expire,price,weight\n100,365,10.5\n110,73,2.5,2\n", |days| days as f64 / 365.0).unwrap();
+        assert_eq!(quotes.len(), 2);
+        assert_abs_diff_eq!(quotes[0].years_to_expire, 1.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(quotes[0].weight, 1.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(quotes[1].weight, 2.0, epsilon = 1e-12);
+        assert!(parse_option_chain("100,abc,1.0", |days| days as f64 / 365.0).is_err());
+    }
+
+    #[test]
+    fn test_calibration_recovers_prices() {
+        let actual = HestonParams { kappa: 1.5, theta: 0.05, xi: 0.4, rho: -0.6, v0: 0.03 };
+        let quotes = synthetic_quotes(&actual);
+        let result = calibrate_heston(&quotes, &vec![100.0; quotes.len()], &YieldCurve::flat(0.02), CalibrationTarget::Price, HestonParams::default());
+        assert_eq!(result.residuals.len(), quotes.len());
+        assert!(result.rmse < 0.02, "rmse {}", result.rmse);
+        assert!(result.params.rho < 0.0);
+    }
+
+    #[test]
+    fn test_calibration_recovers_implied_vols() {
+        let actual = HestonParams { kappa: 1.5, theta: 0.05, xi: 0.4, rho: -0.6, v0: 0.03 };
+        let quotes = synthetic_quotes(&actual);
+        let result = calibrate_heston(&quotes, &vec![100.0; quotes.len()], &YieldCurve::flat(0.02), CalibrationTarget::ImpliedVol, HestonParams::default());
+        assert!(result.rmse < 1e-3, "rmse {}", result.rmse);
+        // At the true parameters every vol error vanishes to the inversion tolerance
+        let vols: Vec<f64> = quotes.iter().map(|q| implied_vol_newton(q.price, 100.0, q.strike, 0.02, q.years_to_expire, 0.2)).collect();
+        for r in residuals(&actual, &quotes, &vec![100.0; quotes.len()], &YieldCurve::flat(0.02), Some(&vols)) {
+            assert_abs_diff_eq!(r, 0.0, epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let params = HestonParams { kappa: 1.5, theta: 0.05, xi: 0.4, rho: -0.6, v0: 0.03 };
+        let path = std::env::temp_dir().join("opti_rust_heston_test.json");
+        let path = path.to_str().unwrap();
+        params.save(path).unwrap();
+        assert_eq!(HestonParams::load(path).unwrap(), params);
+        fs::remove_file(path).unwrap();
+    }
+}
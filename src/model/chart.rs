@@ -1,49 +1,52 @@
-use chrono::{Days, NaiveDate};
+use chrono::NaiveDate;
 use core::f64;
 
+use super::calendar::{Calendar, DayCount};
 use super::request::StockData;
 
 pub struct PriceChart {
     pub data: Vec<DataPoint>,
-    pub squared_sum: f64,
-    pub sum: f64,
     pub max_price: f64,
     pub min_price: f64,
     pub refresh_chart: bool,
+    pub barrier_levels: Vec<f64>,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct DataPoint {
     pub price: f64,
     pub date: NaiveDate,
+    pub volume: f64, // shares traded on the day, zero when unknown
 }
 
 impl DataPoint {
     pub fn new(price: f64, date: NaiveDate) -> DataPoint {
-        DataPoint{price, date}
+        DataPoint{price, date, volume: 0.0}
     }
 }
 
 impl PriceChart {
-    pub fn new<'a>(input_data: &'a Vec<DataPoint>) -> PriceChart {
-        let pc_sum = input_data.iter().map(|&d| (d.price)).sum();
-        let squared_sum = input_data.iter().map(|&d| (d.price).powi(2)).sum();
+    pub fn new(input_data: &[DataPoint]) -> PriceChart {
         PriceChart{
             data: input_data.to_vec(), 
-            squared_sum: squared_sum, 
-            sum: pc_sum, 
             max_price: input_data.iter().map(|&p| p.price).fold(f64::MIN, |a, b| a.max(b)),
             min_price: input_data.iter().map(|&p| p.price).fold(f64::INFINITY, |a, b| a.min(b)),
             refresh_chart: false,
+            barrier_levels: Vec::new(),
         }
     }
 
-    pub fn from_prices_and_date(input_data: Vec<f64>, starting_date: NaiveDate) -> PriceChart {
+    /// Places the prices on consecutive business days of the calendar, starting from the first business
+    /// day on or after `starting_date`.
+    pub fn from_prices_and_date(input_data: Vec<f64>, starting_date: NaiveDate, calendar: &Calendar) -> PriceChart {
+        let mut date = calendar.roll_forward(starting_date);
         let data_points: Vec<DataPoint> = input_data
         .iter()
         .enumerate() // Get index and value
         .map(|(i, &price)| {
-            let date = starting_date.checked_add_days(Days::new(i as u64)).unwrap();
+            if i > 0 {
+                date = calendar.add_business_days(date, 1);
+            }
             DataPoint::new(price, date)
         })
         .collect();
@@ -57,7 +60,8 @@ impl PriceChart {
             .map(|(date_str, daily_price)| {
                 let date = NaiveDate::parse_from_str(&date_str, "%Y-%m-%d").unwrap();
                 let price = daily_price.close.parse::<f64>().unwrap();
-                DataPoint { date, price }
+                let volume = daily_price.volume.parse::<f64>().unwrap_or(0.0);
+                DataPoint { date, price, volume }
             })
             .collect();
 
@@ -68,9 +72,6 @@ impl PriceChart {
     }
  
     pub fn change_price(& mut self, index: usize, new_price: f64) {
-        let current = self.data[index].price;
-        self.sum += new_price - current;
-        self.squared_sum += new_price.powi(2) - current.powi(2);
         self.data[index].price = new_price;
 
         for dp in &self.data {
@@ -79,8 +80,33 @@ impl PriceChart {
         }
     }
 
-    pub fn variance(&self) -> f64 {
-        self.squared_sum / (self.data.len() as f64) - (self.sum / (self.data.len() as f64)).powi(2)
+    pub fn log_returns(&self) -> Vec<f64> {
+        self.data.windows(2).map(|w| (w[1].price / w[0].price).ln()).collect()
+    }
+
+    pub fn simple_returns(&self) -> Vec<f64> {
+        self.data.windows(2).map(|w| w[1].price / w[0].price - 1.0).collect()
+    }
+
+    /// Number of observations per year implied by the dates spanned by the chart.
+    pub fn periods_per_year(&self, day_count: DayCount, calendar: &Calendar) -> f64 {
+        match (self.data.first(), self.data.last()) {
+            (Some(first), Some(last)) if last.date > first.date => {
+                (self.data.len() - 1) as f64 / day_count.year_fraction(first.date, last.date, calendar)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Annualised standard deviation of the log returns.
+    pub fn historical_volatility(&self, periods_per_year: f64) -> f64 {
+        let returns = self.log_returns();
+        if returns.len() < 2 {
+            return 0.0;
+        }
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+        (variance * periods_per_year).sqrt()
     }
 
     pub fn underlying_price(&self) -> f64 {
@@ -96,7 +122,8 @@ impl Default for PriceChart {
     fn default() -> Self {
         PriceChart::from_prices_and_date(
             vec!(113.0, 116.5, 137.0, 145.3, 101.32, 104.33, 120.32, 100.4), 
-            NaiveDate::from_ymd_opt(2025, 1, 3).unwrap())
+            NaiveDate::from_ymd_opt(2025, 1, 3).unwrap(),
+            &Calendar::Nyse)
     }
 }
 
@@ -104,6 +131,7 @@ impl Default for PriceChart {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::statistics::ReturnKind;
     use chrono::NaiveDate;
     use approx::assert_abs_diff_eq;
 
@@ -122,7 +150,6 @@ mod tests {
             DataPoint::new(105.0, NaiveDate::from_ymd_opt(2025, 1, 2).unwrap()),
         ];
         let chart = PriceChart::new(&data);
-        assert_abs_diff_eq!(chart.sum, 205.0, epsilon = 1e-6);
         assert_abs_diff_eq!(chart.max_price, 105.0, epsilon = 1e-6);
         assert_abs_diff_eq!(chart.min_price, 100.0, epsilon = 1e-6);
     }
@@ -137,10 +164,11 @@ mod tests {
     }
 
     #[test]
-    fn test_variance() {
+    fn test_return_statistics() {
         let chart = PriceChart::default();
-        let var = chart.variance();
-        assert!(var > 0.0);
+        let stats = chart.return_statistics(ReturnKind::Log, 252.0, 2).unwrap();
+        assert_eq!(stats.count, chart.data.len() - 1);
+        assert!(stats.annualised_vol > 0.0);
     }
 
     #[test]
@@ -149,4 +177,32 @@ mod tests {
         let last_price = chart.data.last().unwrap().price;
         assert_abs_diff_eq!(chart.underlying_price(), last_price, epsilon = 1e-6);
     }
+
+    #[test]
+    fn test_log_returns() {
+        let chart = PriceChart::from_prices_and_date(vec![100.0, 110.0, 99.0], NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), &Calendar::Nyse);
+        let returns = chart.log_returns();
+        assert_eq!(returns.len(), 2);
+        assert_abs_diff_eq!(returns[0], 1.1f64.ln(), epsilon = 1e-12);
+        assert_abs_diff_eq!(returns[1], 0.9f64.ln(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_dates_on_business_days() {
+        let chart = PriceChart::from_prices_and_date(vec![100.0; 4], NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), &Calendar::Nyse);
+        let dates: Vec<NaiveDate> = chart.data.iter().map(|d| d.date).collect();
+        let expected: Vec<NaiveDate> = [2, 3, 6, 7].iter().map(|d| NaiveDate::from_ymd_opt(2025, 1, *d).unwrap()).collect();
+        assert_eq!(dates, expected);
+    }
+
+    #[test]
+    fn test_historical_volatility() {
+        let prices: Vec<f64> = (0..253).map(|i| 100.0 * if i % 2 == 0 { 1.0 } else { 1.01 }).collect();
+        let chart = PriceChart::from_prices_and_date(prices, NaiveDate::from_ymd_opt(2025, 1, 2).unwrap(), &Calendar::Nyse);
+        let periods = chart.periods_per_year(DayCount::Bus252, &Calendar::Nyse);
+        assert_abs_diff_eq!(periods, 252.0, epsilon = 1e-12);
+        let daily = 1.01f64.ln();
+        let expected = (daily.powi(2) * 252.0 / 251.0 * 252.0).sqrt();
+        assert_abs_diff_eq!(chart.historical_volatility(periods), expected, epsilon = 1e-12);
+    }
 }
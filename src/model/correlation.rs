@@ -0,0 +1,255 @@
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+
+use super::calendar::{Calendar, DayCount};
+use super::chart::{DataPoint, PriceChart};
+use super::linalg::Matrix;
+use super::params::MonteCarloParams;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CovarianceEstimator {
+    #[default]
+    Sample,
+    Ewma,       // exponentially weighted around a zero mean, as in RiskMetrics
+    LedoitWolf, // sample covariance shrunk towards a multiple of the identity
+}
+
+impl CovarianceEstimator {
+    pub const ALL: [CovarianceEstimator; 3] = [CovarianceEstimator::Sample, CovarianceEstimator::Ewma, CovarianceEstimator::LedoitWolf];
+}
+
+impl fmt::Display for CovarianceEstimator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CovarianceEstimator::Sample => write!(f, "Sample"),
+            CovarianceEstimator::Ewma => write!(f, "EWMA"),
+            CovarianceEstimator::LedoitWolf => write!(f, "Ledoit-Wolf"),
+        }
+    }
+}
+
+/// Annualised covariance and correlation of the log returns of several tickers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrelationEstimate {
+    pub tickers: Vec<String>,
+    pub covariance: Matrix,
+    pub correlation: Matrix,
+    pub observations: usize,
+    pub shrinkage: Option<f64>, // Ledoit-Wolf intensity, 0 keeps the sample covariance
+    pub last_prices: Vec<f64>,
+}
+
+impl CorrelationEstimate {
+    pub fn volatilities(&self) -> Vec<f64> {
+        (0..self.covariance.len()).map(|i| self.covariance[i][i].sqrt()).collect()
+    }
+}
+
+/// Restricts every chart to the dates on which all of them have a price, so that returns line up
+/// even when the tickers trade on different calendars. Returns then span any skipped days.
+pub fn align_charts(charts: &[PriceChart]) -> Vec<PriceChart> {
+    let common = charts
+        .iter()
+        .map(|chart| chart.data.iter().map(|dp| dp.date).collect::<BTreeSet<NaiveDate>>())
+        .reduce(|a, b| &a & &b)
+        .unwrap_or_default();
+    charts
+        .iter()
+        .map(|chart| {
+            let points: Vec<DataPoint> = chart.data.iter().filter(|dp| common.contains(&dp.date)).copied().collect();
+            PriceChart::new(&points)
+        })
+        .collect()
+}
+
+fn means(returns: &[Vec<f64>]) -> Vec<f64> {
+    returns.iter().map(|r| r.iter().sum::<f64>() / r.len() as f64).collect()
+}
+
+/// Unbiased sample covariance of return series indexed by asset then observation.
+pub fn sample_covariance(returns: &[Vec<f64>]) -> Matrix {
+    let n = returns[0].len() as f64;
+    let means = means(returns);
+    returns
+        .iter()
+        .zip(&means)
+        .map(|(ri, mi)| {
+            returns
+                .iter()
+                .zip(&means)
+                .map(|(rj, mj)| ri.iter().zip(rj).map(|(a, b)| (a - mi) * (b - mj)).sum::<f64>() / (n - 1.0))
+                .collect()
+        })
+        .collect()
+}
+
+/// Exponentially weighted covariance with the most recent observation weighted highest, normalised
+/// so that the weights sum to one.
+pub fn ewma_covariance(returns: &[Vec<f64>], decay: f64) -> Matrix {
+    let n = returns[0].len();
+    let weights: Vec<f64> = (0..n).map(|t| decay.powi((n - 1 - t) as i32)).collect();
+    let total: f64 = weights.iter().sum();
+    returns
+        .iter()
+        .map(|ri| returns.iter().map(|rj| (0..n).map(|t| weights[t] * ri[t] * rj[t]).sum::<f64>() / total).collect())
+        .collect()
+}
+
+/// Ledoit and Wolf's (2004) shrinkage of the sample covariance towards the identity scaled by the
+/// average variance, with the intensity that minimises the expected Frobenius loss.
+pub fn ledoit_wolf_covariance(returns: &[Vec<f64>]) -> (Matrix, f64) {
+    let p = returns.len();
+    let n = returns[0].len();
+    let means = means(returns);
+    let demeaned: Vec<Vec<f64>> = returns.iter().zip(&means).map(|(r, m)| r.iter().map(|x| x - m).collect()).collect();
+    let sample: Matrix = (0..p)
+        .map(|i| (0..p).map(|j| (0..n).map(|t| demeaned[i][t] * demeaned[j][t]).sum::<f64>() / n as f64).collect())
+        .collect();
+    let target = (0..p).map(|i| sample[i][i]).sum::<f64>() / p as f64;
+    let identity = |i: usize, j: usize| if i == j { 1.0 } else { 0.0 };
+
+    let dispersion: f64 = (0..p).flat_map(|i| (0..p).map(move |j| (i, j))).map(|(i, j)| (sample[i][j] - target * identity(i, j)).powi(2)).sum::<f64>() / p as f64;
+    let estimation_error: f64 = (0..n)
+        .map(|t| (0..p).flat_map(|i| (0..p).map(move |j| (i, j))).map(|(i, j)| (demeaned[i][t] * demeaned[j][t] - sample[i][j]).powi(2)).sum::<f64>())
+        .sum::<f64>()
+        / (n as f64).powi(2)
+        / p as f64;
+    let shrinkage = if dispersion > 0.0 { estimation_error.min(dispersion) / dispersion } else { 0.0 };
+    let shrunk = (0..p)
+        .map(|i| (0..p).map(|j| shrinkage * target * identity(i, j) + (1.0 - shrinkage) * sample[i][j]).collect())
+        .collect();
+    (shrunk, shrinkage)
+}
+
+pub fn correlation_from_covariance(covariance: &Matrix) -> Matrix {
+    let n = covariance.len();
+    (0..n)
+        .map(|i| (0..n).map(|j| covariance[i][j] / (covariance[i][i] * covariance[j][j]).sqrt()).collect())
+        .collect()
+}
+
+/// Covariance estimator and the calendar used to annualise it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrelationSettings {
+    pub estimator: CovarianceEstimator,
+    pub decay: f64, // EWMA weight of each older observation relative to the next
+    pub day_count: DayCount,
+    pub calendar: Calendar,
+}
+
+impl CorrelationSettings {
+    pub fn from_params(params: &MonteCarloParams) -> Result<CorrelationSettings, Box<dyn Error>> {
+        let decay: f64 = params.ewma_decay.parse().map_err(|_| format!("Invalid EWMA decay '{}'", params.ewma_decay))?;
+        if decay <= 0.0 || decay >= 1.0 {
+            return Err(format!("EWMA decay must lie strictly between 0 and 1, got {}", decay).into());
+        }
+        Ok(CorrelationSettings { estimator: params.covariance_estimator, decay, day_count: params.day_count, calendar: params.calendar.clone() })
+    }
+}
+
+/// Estimates the correlation between the tickers on their common dates, annualising the covariance
+/// with the observation frequency of the aligned series.
+pub fn estimate_correlation(tickers: Vec<String>, charts: &[PriceChart], settings: &CorrelationSettings) -> Result<CorrelationEstimate, Box<dyn Error>> {
+    if charts.len() < 2 {
+        return Err("At least two tickers are needed to estimate a correlation".into());
+    }
+    let aligned = align_charts(charts);
+    let returns: Vec<Vec<f64>> = aligned.iter().map(|chart| chart.log_returns()).collect();
+    if returns[0].len() < 2 {
+        return Err(format!("Only {} dates are common to all tickers", aligned[0].data.len()).into());
+    }
+
+    let (daily, shrinkage) = match settings.estimator {
+        CovarianceEstimator::Sample => (sample_covariance(&returns), None),
+        CovarianceEstimator::Ewma => (ewma_covariance(&returns, settings.decay), None),
+        CovarianceEstimator::LedoitWolf => {
+            let (covariance, shrinkage) = ledoit_wolf_covariance(&returns);
+            (covariance, Some(shrinkage))
+        }
+    };
+    let periods_per_year = aligned[0].periods_per_year(settings.day_count, &settings.calendar);
+    let covariance: Matrix = daily.iter().map(|row| row.iter().map(|c| c * periods_per_year).collect()).collect();
+    Ok(CorrelationEstimate {
+        tickers,
+        correlation: correlation_from_covariance(&covariance),
+        covariance,
+        observations: returns[0].len(),
+        shrinkage,
+        last_prices: aligned.iter().map(|chart| chart.underlying_price()).collect(),
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn chart_from_returns(returns: &[f64], skip: Option<usize>) -> PriceChart {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let mut price = 100.0;
+        let mut points = vec![DataPoint::new(price, start)];
+        for (i, r) in returns.iter().enumerate() {
+            price *= r.exp();
+            if Some(i + 1) != skip {
+                points.push(DataPoint::new(price, Calendar::WeekendsOnly.add_business_days(start, i as u64 + 1)));
+            }
+        }
+        PriceChart::new(&points)
+    }
+
+    #[test]
+    fn test_alignment_drops_missing_days() {
+        let returns = [0.01, -0.02, 0.015, 0.005, -0.01];
+        let charts = [chart_from_returns(&returns, None), chart_from_returns(&returns, Some(2))];
+        let aligned = align_charts(&charts);
+        assert_eq!(aligned[0].data.len(), 5);
+        assert_eq!(aligned[1].data.len(), 5);
+        // The return across the missing day covers both days
+        assert_abs_diff_eq!(aligned[0].log_returns()[1], -0.005, epsilon = 1e-12);
+        assert_eq!(aligned[0].log_returns(), aligned[1].log_returns());
+    }
+
+    #[test]
+    fn test_estimators() {
+        let first = [0.01, -0.02, 0.015, 0.005, -0.01, 0.02, -0.005, 0.0];
+        let noise = [0.004, -0.003, 0.0, 0.002, -0.004, 0.001, 0.003, -0.002];
+        let second: Vec<f64> = first.iter().zip(&noise).map(|(r, e)| 0.5 * r + e).collect();
+        let returns = vec![first.to_vec(), second];
+
+        let sample = sample_covariance(&returns);
+        let correlation = correlation_from_covariance(&sample);
+        assert_abs_diff_eq!(correlation[0][0], 1.0, epsilon = 1e-12);
+        assert!(correlation[0][1] > 0.8 && correlation[0][1] < 1.0);
+
+        // Without decay the EWMA weights are equal around a zero mean
+        let flat = ewma_covariance(&returns, 1.0);
+        assert_abs_diff_eq!(flat[0][0], first.iter().map(|r| r * r).sum::<f64>() / 8.0, epsilon = 1e-15);
+
+        let (shrunk, intensity) = ledoit_wolf_covariance(&returns);
+        assert!((0.0..=1.0).contains(&intensity));
+        assert!(correlation_from_covariance(&shrunk)[0][1].abs() <= correlation[0][1]);
+    }
+
+    #[test]
+    fn test_estimate_correlation() {
+        let first = [0.01, -0.02, 0.015, 0.005, -0.01, 0.02];
+        let second = [-0.01, 0.02, -0.015, -0.005, 0.01, -0.02];
+        let charts = [chart_from_returns(&first, None), chart_from_returns(&second, Some(4))];
+        let tickers = vec![String::from("A"), String::from("B")];
+        let settings = CorrelationSettings { estimator: CovarianceEstimator::Sample, decay: 0.94, day_count: DayCount::Bus252, calendar: Calendar::WeekendsOnly };
+        let estimate = estimate_correlation(tickers, &charts, &settings).unwrap();
+        assert_eq!(estimate.observations, 5);
+        assert_abs_diff_eq!(estimate.correlation[0][1], -1.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(estimate.volatilities()[0], estimate.volatilities()[1], epsilon = 1e-12);
+        assert!(estimate_correlation(Vec::new(), &charts[..1], &settings).is_err());
+
+        for decay in ["0", "1", "1.2", "-0.5"] {
+            let params = MonteCarloParams { ewma_decay: String::from(decay), ..MonteCarloParams::default() };
+            assert!(CorrelationSettings::from_params(&params).is_err());
+        }
+        assert_eq!(CorrelationSettings::from_params(&MonteCarloParams::default()).unwrap().decay, 0.94);
+    }
+}
@@ -0,0 +1,156 @@
+use std::fmt;
+use std::ops::Add;
+
+use super::params::MonteCarloParams;
+use super::strategy::LegKind;
+
+/// How the bid-ask spread is quoted: as a fraction of the model price, or in vol points converted
+/// to price with the contract's vega. The underlying has no vega, so a vol quoted spread only
+/// applies to options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpreadQuote {
+    #[default]
+    Price,
+    Vol,
+}
+
+impl SpreadQuote {
+    pub const ALL: [SpreadQuote; 2] = [SpreadQuote::Price, SpreadQuote::Vol];
+}
+
+impl fmt::Display for SpreadQuote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpreadQuote::Price => write!(f, "Fraction of price"),
+            SpreadQuote::Vol => write!(f, "Vol points"),
+        }
+    }
+}
+
+/// Costs of a fill, by source.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FillCosts {
+    pub commission: f64,
+    pub fees: f64,
+    pub spread: f64,
+    pub slippage: f64,
+}
+
+impl FillCosts {
+    pub fn total(&self) -> f64 {
+        self.commission + self.fees + self.spread + self.slippage
+    }
+}
+
+impl Add for FillCosts {
+    type Output = FillCosts;
+
+    fn add(self, other: FillCosts) -> FillCosts {
+        FillCosts {
+            commission: self.commission + other.commission,
+            fees: self.fees + other.fees,
+            spread: self.spread + other.spread,
+            slippage: self.slippage + other.slippage,
+        }
+    }
+}
+
+/// Costs charged on every fill on top of the model mid price. All of them are zero by default.
+/// Quantities are in units of the underlying, options included, so an option contract covers
+/// `contract_size` of them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostModel {
+    pub commission_per_order: f64,
+    pub commission_per_contract: f64, // on options, for every started contract
+    pub contract_size: f64,
+    pub notional_rate: f64, // fraction of the traded notional
+    pub spread_quote: SpreadQuote,
+    pub spread: f64, // full bid-ask width, half of which is paid on each fill
+    pub slippage_impact: f64, // square root impact coefficient against the day's volume
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        CostModel {
+            commission_per_order: 0.0,
+            commission_per_contract: 0.0,
+            contract_size: 100.0,
+            notional_rate: 0.0,
+            spread_quote: SpreadQuote::Price,
+            spread: 0.0,
+            slippage_impact: 0.0,
+        }
+    }
+}
+
+impl CostModel {
+    pub fn from_params(params: &MonteCarloParams) -> CostModel {
+        CostModel {
+            commission_per_order: params.cost_commission_order.parse::<f64>().unwrap_or(0.0),
+            commission_per_contract: params.cost_commission.parse::<f64>().unwrap_or(0.0),
+            contract_size: params.cost_contract_size.parse::<f64>().ok().filter(|size| *size > 0.0).unwrap_or(100.0),
+            notional_rate: params.cost_notional_rate.parse::<f64>().unwrap_or(0.0),
+            spread_quote: params.cost_spread_quote,
+            spread: params.cost_spread.parse::<f64>().unwrap_or(0.0),
+            slippage_impact: params.cost_slippage_impact.parse::<f64>().unwrap_or(0.0),
+        }
+    }
+
+    /// Costs of trading `quantity` of a contract of the given kind at the mid `price`, where `delta`
+    /// and `vega` are the changes in price per unit of spot and of vol, and `volume` the underlying's
+    /// volume on the day. Slippage grows with the square root of the share of the day's volume taken
+    /// by the delta equivalent of the order, and is skipped when the volume is unknown.
+    pub fn fill_costs(&self, kind: LegKind, quantity: f64, price: f64, delta: f64, vega: f64, volume: f64) -> FillCosts {
+        let size = quantity.abs();
+        if size == 0.0 {
+            return FillCosts::default();
+        }
+        let notional = size * price.abs();
+        let half_spread = match self.spread_quote {
+            SpreadQuote::Price => 0.5 * self.spread * price.abs(),
+            SpreadQuote::Vol => 0.5 * self.spread * vega.abs(),
+        };
+        let contracts = match kind {
+            LegKind::Underlying => 0.0,
+            _ => (size / self.contract_size).ceil(),
+        };
+        let participation = size * delta.abs() / volume;
+        FillCosts {
+            commission: self.commission_per_order + self.commission_per_contract * contracts,
+            fees: self.notional_rate * notional,
+            spread: half_spread * size,
+            slippage: if volume > 0.0 { self.slippage_impact * notional * participation.sqrt() } else { 0.0 },
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn test_fill_costs() {
+        let model = CostModel { commission_per_order: 1.0, commission_per_contract: 0.65, notional_rate: 0.001, spread: 0.02, slippage_impact: 0.1, ..CostModel::default() };
+        // Three contracts' worth of calls with a delta of 0.4 take 120 shares of the day's 1200
+        let costs = model.fill_costs(LegKind::Call, -300.0, 5.0, 0.4, 0.2, 1200.0);
+        assert_abs_diff_eq!(costs.commission, 1.0 + 3.0 * 0.65, epsilon = 1e-12);
+        assert_abs_diff_eq!(costs.fees, 1.5, epsilon = 1e-12);
+        assert_abs_diff_eq!(costs.spread, 15.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(costs.slippage, 0.1 * 1500.0 * 0.1f64.sqrt(), epsilon = 1e-12);
+        assert_eq!(model.fill_costs(LegKind::Call, -300.0, 5.0, 0.4, 0.2, 0.0).slippage, 0.0);
+        // A started contract is charged in full, and shares only pay per order
+        assert_abs_diff_eq!(model.fill_costs(LegKind::Put, 150.0, 5.0, -0.4, 0.2, 0.0).commission, 1.0 + 2.0 * 0.65, epsilon = 1e-12);
+        assert_abs_diff_eq!(model.fill_costs(LegKind::Underlying, 500.0, 50.0, 1.0, 0.0, 0.0).commission, 1.0, epsilon = 1e-12);
+        assert_eq!(CostModel::default().fill_costs(LegKind::Call, 3.0, 5.0, 0.5, 0.2, 1000.0), FillCosts::default());
+    }
+
+    #[test]
+    fn test_vol_quoted_spread() {
+        // One vol point wide on an option with a vega of 20 costs 0.1 a contract
+        let model = CostModel { spread_quote: SpreadQuote::Vol, spread: 0.01, ..CostModel::default() };
+        assert_abs_diff_eq!(model.fill_costs(LegKind::Call, 2.0, 5.0, 0.5, 20.0, 0.0).spread, 0.2, epsilon = 1e-12);
+        assert_eq!(model.fill_costs(LegKind::Underlying, 100.0, 50.0, 1.0, 0.0, 0.0).total(), 0.0);
+    }
+}
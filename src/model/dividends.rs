@@ -0,0 +1,159 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DividendKind {
+    #[default]
+    Cash,         // a fixed amount per share
+    Proportional, // a fraction of the spot on the ex-date
+}
+
+impl fmt::Display for DividendKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DividendKind::Cash => write!(f, "cash"),
+            DividendKind::Proportional => write!(f, "proportional"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dividend {
+    pub years_to_ex_date: f64,
+    pub amount: f64,
+    pub kind: DividendKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DividendSchedule {
+    pub dividends: Vec<Dividend>, // sorted by ex-date
+}
+
+impl DividendSchedule {
+    pub fn new(mut dividends: Vec<Dividend>) -> DividendSchedule {
+        dividends.sort_by(|a, b| a.years_to_ex_date.total_cmp(&b.years_to_ex_date));
+        DividendSchedule { dividends }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dividends.is_empty()
+    }
+
+    /// The schedule as seen `years` from now: dividends already paid are dropped and the others are
+    /// brought that much closer.
+    pub fn after(&self, years: f64) -> DividendSchedule {
+        DividendSchedule {
+            dividends: self
+                .dividends
+                .iter()
+                .filter(|d| d.years_to_ex_date > years)
+                .map(|d| Dividend { years_to_ex_date: d.years_to_ex_date - years, ..*d })
+                .collect(),
+        }
+    }
+
+    fn before(&self, years: f64) -> impl Iterator<Item = &Dividend> {
+        self.dividends.iter().filter(move |d| d.years_to_ex_date > 0.0 && d.years_to_ex_date <= years)
+    }
+
+    /// Spot net of the dividends paid before `years` (the escrowed dividend model): cash dividends are
+    /// removed at their present value and proportional ones scale the remaining spot.
+    pub fn escrowed_spot<F: Fn(f64) -> f64>(&self, spot: f64, discount_factor: F, years: f64) -> f64 {
+        let cash_pv: f64 = self
+            .before(years)
+            .filter(|d| d.kind == DividendKind::Cash)
+            .map(|d| d.amount * discount_factor(d.years_to_ex_date))
+            .sum();
+        let proportional: f64 = self
+            .before(years)
+            .filter(|d| d.kind == DividendKind::Proportional)
+            .map(|d| 1.0 - d.amount)
+            .product();
+        ((spot - cash_pv) * proportional).max(0.0)
+    }
+
+    /// Drops the spot of a simulated path on every ex-date. Points after an ex-date keep the simulated
+    /// returns but start from the reduced spot, which is exact for models whose dynamics scale with the spot.
+    pub fn apply_to_path(&self, path: &mut [f64], dt: f64) {
+        if self.is_empty() {
+            return;
+        }
+        let mut previous_raw = path[0];
+        for i in 1..path.len() {
+            let raw = path[i];
+            let mut st = if previous_raw > 0.0 { path[i - 1] * raw / previous_raw } else { 0.0 };
+            let (start, end) = ((i - 1) as f64 * dt, i as f64 * dt);
+            for d in self.dividends.iter().filter(|d| d.years_to_ex_date > start && d.years_to_ex_date <= end) {
+                st = match d.kind {
+                    DividendKind::Cash => (st - d.amount).max(0.0),
+                    DividendKind::Proportional => st * (1.0 - d.amount),
+                };
+            }
+            previous_raw = raw;
+            path[i] = st;
+        }
+    }
+
+    /// Reads a schedule from CSV with the columns `days_to_ex_date,amount[,cash|proportional]`, the
+    /// calendar days being converted to years by `to_years`.
+    pub fn load<F: Fn(u64) -> f64>(path: &str, to_years: F) -> Result<DividendSchedule, Box<dyn Error>> {
+        DividendSchedule::parse(&fs::read_to_string(path)?, to_years)
+    }
+
+    pub fn parse<F: Fn(u64) -> f64>(contents: &str, to_years: F) -> Result<DividendSchedule, Box<dyn Error>> {
+        let mut dividends = Vec::new();
+        for line in contents.lines().map(|l| l.trim()).filter(|l| !l.is_empty() && !l.starts_with("days")) {
+            let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+            if fields.len() < 2 {
+                return Err(format!("Expected an ex-date and an amount in '{}'", line).into());
+            }
+            let kind = match fields.get(2).map(|k| k.to_lowercase()) {
+                None => DividendKind::Cash,
+                Some(k) if k == "cash" => DividendKind::Cash,
+                Some(k) if k == "proportional" => DividendKind::Proportional,
+                Some(k) => return Err(format!("Unknown dividend kind '{}'", k).into()),
+            };
+            dividends.push(Dividend { years_to_ex_date: to_years(fields[0].parse()?), amount: fields[1].parse()?, kind });
+        }
+        Ok(DividendSchedule::new(dividends))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn test_escrowed_spot() {
+        let schedule = DividendSchedule::new(vec![
+            Dividend { years_to_ex_date: 0.5, amount: 2.0, kind: DividendKind::Cash },
+            Dividend { years_to_ex_date: 0.25, amount: 0.01, kind: DividendKind::Proportional },
+            Dividend { years_to_ex_date: 2.0, amount: 2.0, kind: DividendKind::Cash },
+        ]);
+        let expected = (100.0 - 2.0 * (-0.05f64 * 0.5).exp()) * 0.99;
+        assert_abs_diff_eq!(schedule.escrowed_spot(100.0, |t| (-0.05 * t).exp(), 1.0), expected, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_apply_to_path() {
+        let schedule = DividendSchedule::new(vec![Dividend { years_to_ex_date: 0.15, amount: 5.0, kind: DividendKind::Cash }]);
+        let mut path = vec![100.0, 110.0, 121.0, 133.1];
+        schedule.apply_to_path(&mut path, 0.1);
+        assert_abs_diff_eq!(path[1], 110.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(path[2], 116.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(path[3], 127.6, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_parse() {
+        let schedule = DividendSchedule::parse("days_to_ex_date,amount,kind\n146,0.02,proportional\n73,1.5\n", |days| days as f64 / 365.0).unwrap();
+        assert_eq!(schedule.dividends.len(), 2);
+        assert_eq!(schedule.dividends[0].kind, DividendKind::Cash);
+        assert_eq!(schedule.dividends[1].kind, DividendKind::Proportional);
+        assert!(DividendSchedule::parse("73,1.5,stock", |days| days as f64 / 365.0).is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use std::fmt;
+
+use super::monte_carlo::MonteCarloPricing;
+
+// Broadie-Glasserman-Kou shift of discretely monitored extremes towards continuous ones, -zeta(1/2) / sqrt(2 pi)
+const EXTREMUM_SHIFT: f64 = 0.5826;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LookbackStrike {
+    #[default]
+    Fixed,    // pays the maximum over the strike
+    Floating, // pays the final spot over the minimum
+}
+
+impl LookbackStrike {
+    pub const ALL: [LookbackStrike; 2] = [LookbackStrike::Fixed, LookbackStrike::Floating];
+}
+
+impl fmt::Display for LookbackStrike {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LookbackStrike::Fixed => write!(f, "Fixed strike"),
+            LookbackStrike::Floating => write!(f, "Floating strike"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DigitalKind {
+    #[default]
+    CashOrNothing,
+    AssetOrNothing,
+}
+
+impl DigitalKind {
+    pub const ALL: [DigitalKind; 2] = [DigitalKind::CashOrNothing, DigitalKind::AssetOrNothing];
+}
+
+impl fmt::Display for DigitalKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DigitalKind::CashOrNothing => write!(f, "Cash-or-nothing"),
+            DigitalKind::AssetOrNothing => write!(f, "Asset-or-nothing"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DigitalOption {
+    pub kind: DigitalKind,
+    pub cash: f64,
+    pub smoothing: f64, // half width of the call spread replacing the jump at the strike, 0 for the exact payoff
+}
+
+impl Default for DigitalOption {
+    fn default() -> Self {
+        DigitalOption { kind: DigitalKind::CashOrNothing, cash: 1.0, smoothing: 0.0 }
+    }
+}
+
+impl DigitalOption {
+    /// Fraction of the payout received at `spot`: an indicator, or a linear ramp across the strike when smoothed.
+    pub fn exercise_weight(&self, spot: f64, strike: f64) -> f64 {
+        if self.smoothing > 0.0 {
+            ((spot - strike + self.smoothing) / (2.0 * self.smoothing)).clamp(0.0, 1.0)
+        } else if spot > strike {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Call paying the spot less the strike whenever the spot ends above the trigger, which may differ from the strike.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GapOption {
+    pub trigger: f64,
+}
+
+impl Default for GapOption {
+    fn default() -> Self {
+        GapOption { trigger: 100.0 }
+    }
+}
+
+impl MonteCarloPricing {
+    /// Present value of the lookback call along one path. The discretely monitored extremum is shifted
+    /// to approximate continuous monitoring.
+    pub fn lookback_payoff(&self, path: &[f64]) -> f64 {
+        let dt = self.years_to_expire / (path.len() - 1) as f64;
+        let shift = (EXTREMUM_SHIFT * self.implied_vol * dt.sqrt()).exp();
+        let payoff = match self.lookback {
+            LookbackStrike::Fixed => path.iter().fold(f64::MIN, |a, &b| a.max(b)) * shift - self.strike_price,
+            LookbackStrike::Floating => path[path.len() - 1] - path.iter().fold(f64::MAX, |a, &b| a.min(b)) / shift,
+        };
+        payoff.max(0.0) * self.discount_factor(self.years_to_expire)
+    }
+
+    pub fn digital_payoff(&self, path: &[f64]) -> f64 {
+        let st = path[path.len() - 1];
+        let payout = match self.digital.kind {
+            DigitalKind::CashOrNothing => self.digital.cash,
+            DigitalKind::AssetOrNothing => st,
+        };
+        payout * self.digital.exercise_weight(st, self.strike_price) * self.discount_factor(self.years_to_expire)
+    }
+
+    pub fn gap_payoff(&self, path: &[f64]) -> f64 {
+        let st = path[path.len() - 1];
+        if st > self.gap.trigger {
+            (st - self.strike_price) * self.discount_factor(self.years_to_expire)
+        } else {
+            0.0
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::calendar::Calendar;
+    use crate::model::chart::PriceChart;
+    use crate::model::monte_carlo::PayoffKind;
+    use approx::assert_abs_diff_eq;
+
+    fn pricing(payoff: PayoffKind) -> MonteCarloPricing {
+        MonteCarloPricing {
+            current_asset_price: 100.0,
+            strike_price: 100.0,
+            num_simulations: 20000,
+            num_steps: 50,
+            risk_free_rate: 0.05,
+            implied_vol: 0.25,
+            years_to_expire: 0.5,
+            dividend_yield: 0.01,
+            payoff,
+            ..MonteCarloPricing::default()
+        }
+    }
+
+    #[test]
+    fn test_smoothed_digital_weight() {
+        let exact = DigitalOption::default();
+        assert_eq!(exact.exercise_weight(100.5, 100.0), 1.0);
+        assert_eq!(exact.exercise_weight(100.0, 100.0), 0.0);
+        let smoothed = DigitalOption { smoothing: 1.0, ..exact };
+        assert_abs_diff_eq!(smoothed.exercise_weight(100.0, 100.0), 0.5, epsilon = 1e-12);
+        assert_abs_diff_eq!(smoothed.exercise_weight(100.5, 100.0), 0.75, epsilon = 1e-12);
+        assert_eq!(smoothed.exercise_weight(102.0, 100.0), 1.0);
+    }
+
+    #[test]
+    fn test_payoffs_match_closed_forms() {
+        let chart = PriceChart::from_prices_and_date(vec![100.0], chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), &Calendar::Nyse);
+        let mut cases = Vec::new();
+        for lookback in LookbackStrike::ALL {
+            cases.push(MonteCarloPricing { lookback, ..pricing(PayoffKind::Lookback) });
+        }
+        for kind in DigitalKind::ALL {
+            cases.push(MonteCarloPricing { digital: DigitalOption { kind, cash: 10.0, smoothing: 0.0 }, ..pricing(PayoffKind::Digital) });
+        }
+        cases.push(MonteCarloPricing { gap: GapOption { trigger: 105.0 }, ..pricing(PayoffKind::Gap) });
+
+        for mc in cases {
+            let simulated = mc.price(&chart).unwrap();
+            let analytic = mc.closed_form_price(100.0).unwrap();
+            assert!((simulated - analytic).abs() < 0.03 * analytic.max(5.0), "{}: {simulated} vs {analytic}", mc.payoff);
+        }
+    }
+}
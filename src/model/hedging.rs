@@ -0,0 +1,224 @@
+use rayon::prelude::*;
+use std::error::Error;
+
+use super::analytic::{black_scholes_greeks, OptionType};
+use super::calendar::{Calendar, DayCount};
+use super::chart::PriceChart;
+use super::monte_carlo::MonteCarloPricing;
+use super::params::MonteCarloParams;
+
+/// How the short option is hedged: the delta is recomputed every `rebalance_every` steps with the
+/// hedge vol, and each trade in the underlying costs `cost_rate` of its notional.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HedgeSettings {
+    pub option_type: OptionType,
+    pub rebalance_every: usize,
+    pub cost_rate: f64,
+    pub hedge_vol: f64,
+}
+
+impl Default for HedgeSettings {
+    fn default() -> Self {
+        HedgeSettings { option_type: OptionType::Call, rebalance_every: 1, cost_rate: 0.0, hedge_vol: 0.25 }
+    }
+}
+
+impl HedgeSettings {
+    pub fn from_params(params: &MonteCarloParams) -> HedgeSettings {
+        HedgeSettings {
+            option_type: params.hedge_option_type,
+            rebalance_every: params.hedge_rebalance_steps.parse::<usize>().unwrap_or(1),
+            cost_rate: params.hedge_cost.parse::<f64>().unwrap_or(0.0),
+            hedge_vol: params.hedge_vol.parse::<f64>().unwrap_or(0.25),
+        }
+    }
+}
+
+/// Summary of the final hedging P&L over many paths.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PnlDistribution {
+    pub pnls: Vec<f64>,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub percentile_1: f64,
+    pub percentile_5: f64,
+    pub median: f64,
+    pub percentile_95: f64,
+    pub percentile_99: f64,
+}
+
+/// Linearly interpolated percentile of sorted values, with `q` in [0, 1].
+pub fn percentile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let position = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let below = position.floor() as usize;
+    let above = position.ceil() as usize;
+    sorted[below] + (sorted[above] - sorted[below]) * (position - below as f64)
+}
+
+impl PnlDistribution {
+    pub fn from_pnls(pnls: Vec<f64>) -> PnlDistribution {
+        let n = pnls.len() as f64;
+        let mean = pnls.iter().sum::<f64>() / n;
+        let std_dev = (pnls.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0)).sqrt();
+        let mut sorted = pnls.clone();
+        sorted.sort_by(f64::total_cmp);
+        PnlDistribution {
+            mean,
+            std_dev,
+            percentile_1: percentile(&sorted, 0.01),
+            percentile_5: percentile(&sorted, 0.05),
+            median: percentile(&sorted, 0.5),
+            percentile_95: percentile(&sorted, 0.95),
+            percentile_99: percentile(&sorted, 0.99),
+            pnls,
+        }
+    }
+}
+
+impl MonteCarloPricing {
+    /// Final P&L of selling one option at the hedge vol price and delta hedging it along `path`, with
+    /// `times` the year fractions of the path points from inception. The cash account earns the zero
+    /// rate to expiry and the shares held earn the dividend yield.
+    pub fn hedge_path(&self, settings: &HedgeSettings, strike: f64, path: &[f64], times: &[f64]) -> f64 {
+        let expiry = times[times.len() - 1];
+        let rate = self.zero_rate(expiry);
+        let greeks = |spot: f64, t: f64| {
+            black_scholes_greeks(settings.option_type, spot, strike, rate, rate - self.dividend_yield, settings.hedge_vol, expiry - t)
+        };
+
+        let initial = greeks(path[0], 0.0);
+        let mut shares = initial.delta;
+        let mut cash = initial.price - shares * path[0] - settings.cost_rate * (shares * path[0]).abs();
+        for i in 1..path.len() {
+            let dt = times[i] - times[i - 1];
+            cash = cash * (rate * dt).exp() + shares * path[i - 1] * self.dividend_yield * dt;
+            if i % settings.rebalance_every.max(1) == 0 && i < path.len() - 1 {
+                let target = greeks(path[i], times[i]).delta;
+                let traded = target - shares;
+                cash -= traded * path[i] + settings.cost_rate * (traded * path[i]).abs();
+                shares = target;
+            }
+        }
+        // Unwinding the hedge at expiry is a trade like any other
+        let st = path[path.len() - 1];
+        cash -= settings.cost_rate * (shares * st).abs();
+        let payoff = match settings.option_type {
+            OptionType::Call => (st - strike).max(0.0),
+            OptionType::Put => (strike - st).max(0.0),
+        };
+        cash + shares * st - payoff
+    }
+
+    /// Hedges the option on simulated paths of the configured model, whose volatility is the realised one.
+    pub fn hedging_distribution(&self, settings: &HedgeSettings) -> Result<PnlDistribution, Box<dyn Error>> {
+        self.check_model()?;
+        let dt = self.years_to_expire / self.num_steps as f64;
+        let times: Vec<f64> = (0..=self.num_steps).map(|i| i as f64 * dt).collect();
+        let pnls: Vec<f64> = (0..self.num_simulations)
+            .into_par_iter()
+            .map(|_| {
+                let path = self.simulate_path(self.current_asset_price, &mut rand::rng());
+                self.hedge_path(settings, self.strike_price, &path, &times)
+            })
+            .collect();
+        Ok(PnlDistribution::from_pnls(pnls))
+    }
+
+    /// Hedges an option written on every bar of the chart and expiring `num_steps` bars later, struck
+    /// at the same moneyness as the configured strike. Returns `None` when the chart is too short.
+    pub fn historical_hedging_distribution(
+        &self,
+        settings: &HedgeSettings,
+        chart: &PriceChart,
+        day_count: DayCount,
+        calendar: &Calendar,
+    ) -> Option<PnlDistribution> {
+        let window = self.num_steps as usize + 1;
+        if chart.data.len() < window || window < 2 {
+            return None;
+        }
+        let moneyness = self.strike_price / self.current_asset_price;
+        let pnls = chart
+            .data
+            .windows(window)
+            .map(|bars| {
+                let path: Vec<f64> = bars.iter().map(|dp| dp.price).collect();
+                let times: Vec<f64> = bars.iter().map(|dp| day_count.year_fraction(bars[0].date, dp.date, calendar)).collect();
+                self.hedge_path(settings, moneyness * path[0], &path, &times)
+            })
+            .collect();
+        Some(PnlDistribution::from_pnls(pnls))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn pricing(num_steps: u16) -> MonteCarloPricing {
+        MonteCarloPricing {
+            current_asset_price: 100.0,
+            strike_price: 100.0,
+            num_simulations: 4000,
+            num_steps,
+            risk_free_rate: 0.05,
+            implied_vol: 0.2,
+            years_to_expire: 0.25,
+            dividend_yield: 0.01,
+            ..MonteCarloPricing::default()
+        }
+    }
+
+    #[test]
+    fn test_percentile() {
+        let sorted = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(percentile(&sorted, 0.0), 1.0);
+        assert_eq!(percentile(&sorted, 0.5), 3.0);
+        assert_abs_diff_eq!(percentile(&sorted, 0.1), 1.4, epsilon = 1e-12);
+        assert_eq!(percentile(&sorted, 1.0), 5.0);
+    }
+
+    #[test]
+    fn test_hedging_error_shrinks_with_rebalancing() {
+        let matched = HedgeSettings { hedge_vol: 0.2, ..HedgeSettings::default() };
+        let daily = pricing(64).hedging_distribution(&matched).unwrap();
+        let weekly = pricing(64).hedging_distribution(&HedgeSettings { rebalance_every: 8, ..matched }).unwrap();
+        assert!(daily.mean.abs() < 0.1, "mean {}", daily.mean);
+        assert!(daily.std_dev < 0.6 * weekly.std_dev, "{} vs {}", daily.std_dev, weekly.std_dev);
+        assert!(daily.percentile_1 < daily.median && daily.median < daily.percentile_99);
+
+        // Selling at a higher vol than realised earns the difference, costs eat into it
+        let rich = pricing(64).hedging_distribution(&HedgeSettings { hedge_vol: 0.3, ..matched }).unwrap();
+        assert!(rich.mean > 1.0, "mean {}", rich.mean);
+        let costly = pricing(64).hedging_distribution(&HedgeSettings { cost_rate: 0.002, ..matched }).unwrap();
+        assert!(costly.mean < daily.mean - 0.1);
+    }
+
+    #[test]
+    fn test_costs_include_the_unwind() {
+        // Without rebalancing the only trades are the initial hedge and its unwind at expiry
+        let mc = pricing(1);
+        let (path, times) = ([100.0, 110.0], [0.0, 0.25]);
+        let free = mc.hedge_path(&HedgeSettings::default(), 100.0, &path, &times);
+        let costly = mc.hedge_path(&HedgeSettings { cost_rate: 0.01, ..HedgeSettings::default() }, 100.0, &path, &times);
+        let delta = black_scholes_greeks(OptionType::Call, 100.0, 100.0, 0.05, 0.04, 0.25, 0.25).delta;
+        let expected = 0.01 * delta * (100.0 * (0.05f64 * 0.25).exp() + 110.0);
+        assert_abs_diff_eq!(free - costly, expected, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_historical_hedging() {
+        let prices: Vec<f64> = (0..30).map(|i| 100.0 + 3.0 * (i as f64 * 0.9).sin()).collect();
+        let chart = PriceChart::from_prices_and_date(prices, chrono::NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(), &Calendar::WeekendsOnly);
+        let mc = pricing(10);
+        let settings = HedgeSettings { option_type: OptionType::Put, ..HedgeSettings::default() };
+        let distribution = mc.historical_hedging_distribution(&settings, &chart, DayCount::Bus252, &Calendar::WeekendsOnly).unwrap();
+        assert_eq!(distribution.pnls.len(), 20);
+        assert!(pricing(40).historical_hedging_distribution(&settings, &chart, DayCount::Bus252, &Calendar::WeekendsOnly).is_none());
+    }
+}
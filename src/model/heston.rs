@@ -0,0 +1,183 @@
+use core::f64;
+use std::fmt;
+use num_complex::Complex64;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::monte_carlo::standard_normal;
+
+const INTEGRATION_LIMIT: f64 = 100.0;
+const INTEGRATION_STEPS: usize = 1000; // must be even for Simpson's rule
+const INTEGRATION_START: f64 = 1e-8; // the integrand is singular at 0
+const QE_PSI_CRITICAL: f64 = 1.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HestonParams {
+    pub kappa: f64, // speed of mean reversion of the variance
+    pub theta: f64, // long run variance
+    pub xi: f64,    // volatility of variance
+    pub rho: f64,   // correlation between spot and variance shocks
+    pub v0: f64,    // initial variance
+}
+
+impl Default for HestonParams {
+    fn default() -> Self {
+        HestonParams { kappa: 2.0, theta: 0.04, xi: 0.3, rho: -0.7, v0: 0.04 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HestonScheme {
+    FullTruncationEuler,
+    #[default]
+    QuadraticExponential,
+}
+
+impl HestonScheme {
+    pub const ALL: [HestonScheme; 2] = [HestonScheme::FullTruncationEuler, HestonScheme::QuadraticExponential];
+}
+
+impl fmt::Display for HestonScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HestonScheme::FullTruncationEuler => write!(f, "Full truncation Euler"),
+            HestonScheme::QuadraticExponential => write!(f, "Quadratic exponential"),
+        }
+    }
+}
+
+impl HestonParams {
+    /// Advances (log spot, variance) by one step of size `dt` under the risk neutral measure.
+    pub fn step<R: Rng + ?Sized>(&self, scheme: HestonScheme, log_spot: f64, variance: f64, drift: f64, dt: f64, rng: &mut R) -> (f64, f64) {
+        match scheme {
+            HestonScheme::FullTruncationEuler => self.euler_step(log_spot, variance, drift, dt, rng),
+            HestonScheme::QuadraticExponential => self.qe_step(log_spot, variance, drift, dt, rng),
+        }
+    }
+
+    fn euler_step<R: Rng + ?Sized>(&self, log_spot: f64, variance: f64, drift: f64, dt: f64, rng: &mut R) -> (f64, f64) {
+        let v_plus = variance.max(0.0);
+        let z1 = standard_normal(rng);
+        let z2 = self.rho * z1 + (1.0 - self.rho.powi(2)).sqrt() * standard_normal(rng);
+
+        let next_log_spot = log_spot + (drift - 0.5 * v_plus) * dt + (v_plus * dt).sqrt() * z1;
+        let next_variance = variance + self.kappa * (self.theta - v_plus) * dt + self.xi * (v_plus * dt).sqrt() * z2;
+        (next_log_spot, next_variance)
+    }
+
+    // Andersen (2008), with the central discretisation gamma1 = gamma2 = 0.5 for the log spot
+    fn qe_step<R: Rng + ?Sized>(&self, log_spot: f64, variance: f64, drift: f64, dt: f64, rng: &mut R) -> (f64, f64) {
+        let decay = (-self.kappa * dt).exp();
+        let m = self.theta + (variance - self.theta) * decay;
+        let s2 = variance * self.xi.powi(2) * decay / self.kappa * (1.0 - decay)
+            + self.theta * self.xi.powi(2) / (2.0 * self.kappa) * (1.0 - decay).powi(2);
+        let psi = s2 / m.powi(2);
+
+        let next_variance = if psi <= QE_PSI_CRITICAL {
+            let inv_psi = 2.0 / psi;
+            let b2 = inv_psi - 1.0 + inv_psi.sqrt() * (inv_psi - 1.0).sqrt();
+            let a = m / (1.0 + b2);
+            a * (b2.sqrt() + standard_normal(rng)).powi(2)
+        } else {
+            let p = (psi - 1.0) / (psi + 1.0);
+            let beta = (1.0 - p) / m;
+            let u: f64 = rng.random();
+            if u <= p { 0.0 } else { ((1.0 - p) / (1.0 - u)).ln() / beta }
+        };
+
+        let k0 = -self.rho * self.kappa * self.theta / self.xi * dt;
+        let k1 = 0.5 * dt * (self.kappa * self.rho / self.xi - 0.5) - self.rho / self.xi;
+        let k2 = 0.5 * dt * (self.kappa * self.rho / self.xi - 0.5) + self.rho / self.xi;
+        let k3 = 0.5 * dt * (1.0 - self.rho.powi(2));
+        let next_log_spot = log_spot + drift * dt + k0 + k1 * variance + k2 * next_variance
+            + (k3 * (variance + next_variance)).sqrt() * standard_normal(rng);
+        (next_log_spot, next_variance)
+    }
+
+    /// Characteristic function of ln(S_T), in the "little trap" form of Albrecher et al.
+    fn characteristic_function(&self, u: Complex64, spot: f64, rate: f64, years: f64) -> Complex64 {
+        let i = Complex64::i();
+        let xi2 = self.xi.powi(2);
+        let beta = self.kappa - self.rho * self.xi * i * u;
+        let d = (beta * beta + xi2 * (i * u + u * u)).sqrt();
+        let g = (beta - d) / (beta + d);
+        let exp_dt = (-d * years).exp();
+
+        let c = rate * i * u * years
+            + self.kappa * self.theta / xi2 * ((beta - d) * years - 2.0 * ((1.0 - g * exp_dt) / (1.0 - g)).ln());
+        let big_d = (beta - d) / xi2 * (1.0 - exp_dt) / (1.0 - g * exp_dt);
+        (c + big_d * self.v0 + i * u * spot.ln()).exp()
+    }
+
+    /// Semi-analytic price of a European call, integrating the characteristic function.
+    pub fn call_price(&self, spot: f64, strike: f64, rate: f64, years: f64) -> f64 {
+        let i = Complex64::i();
+        let log_strike = strike.ln();
+        let forward = Complex64::new(spot * (rate * years).exp(), 0.0);
+
+        let integrand_p1 = |u: f64| {
+            let phi = self.characteristic_function(Complex64::new(u, -1.0), spot, rate, years);
+            ((-i * u * log_strike).exp() * phi / (i * u * forward)).re
+        };
+        let integrand_p2 = |u: f64| {
+            let phi = self.characteristic_function(Complex64::new(u, 0.0), spot, rate, years);
+            ((-i * u * log_strike).exp() * phi / (i * u)).re
+        };
+
+        let p1 = 0.5 + simpson(integrand_p1) / f64::consts::PI;
+        let p2 = 0.5 + simpson(integrand_p2) / f64::consts::PI;
+        (spot * p1 - strike * (-rate * years).exp() * p2).max(0.0)
+    }
+}
+
+fn simpson<F: Fn(f64) -> f64>(f: F) -> f64 {
+    let h = (INTEGRATION_LIMIT - INTEGRATION_START) / INTEGRATION_STEPS as f64;
+    let mut sum = f(INTEGRATION_START) + f(INTEGRATION_LIMIT);
+    for k in 1..INTEGRATION_STEPS {
+        let weight = if k % 2 == 1 { 4.0 } else { 2.0 };
+        sum += weight * f(INTEGRATION_START + k as f64 * h);
+    }
+    sum * h / 3.0
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use crate::model::monte_carlo::MonteCarloPricing;
+
+    #[test]
+    fn test_call_price_matches_black_scholes_for_deterministic_variance() {
+        // With vanishing vol of variance and v0 = theta the model collapses to Black-Scholes
+        let params = HestonParams { kappa: 1.0, theta: 0.04, xi: 1e-4, rho: 0.0, v0: 0.04 };
+        let mc = MonteCarloPricing {
+            current_asset_price: 100.0,
+            strike_price: 105.0,
+            risk_free_rate: 0.03,
+            years_to_expire: 1.0,
+            ..MonteCarloPricing::default()
+        };
+        let heston = params.call_price(100.0, 105.0, 0.03, 1.0);
+        assert_abs_diff_eq!(heston, mc.black_scholes_call_price(0.2), epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_deep_in_the_money_call_is_discounted_intrinsic() {
+        let params = HestonParams { kappa: 1.5768, theta: 0.0398, xi: 0.5751, rho: -0.5711, v0: 0.0175 };
+        let price = params.call_price(100.0, 20.0, 0.025, 1.0);
+        assert_abs_diff_eq!(price, 100.0 - 20.0 * (-0.025f64).exp(), epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_qe_variance_stays_non_negative() {
+        let params = HestonParams { xi: 1.0, ..HestonParams::default() };
+        let mut rng = rand::rng();
+        let (mut x, mut v) = (100f64.ln(), params.v0);
+        for _ in 0..1000 {
+            (x, v) = params.step(HestonScheme::QuadraticExponential, x, v, 0.0, 0.01, &mut rng);
+            assert!(v >= 0.0);
+            assert!(x.is_finite());
+        }
+    }
+}
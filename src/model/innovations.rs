@@ -0,0 +1,321 @@
+use rand::Rng;
+use rand_distr::{Distribution, InverseGaussian, StudentT};
+use statrs::function::gamma::ln_gamma;
+use std::error::Error;
+use std::f64::consts::PI;
+use std::fmt;
+
+use super::monte_carlo::{standard_normal, wiener_increment};
+use super::optimize::nelder_mead;
+
+const MAX_ITERATIONS: usize = 2000;
+const SIMPLEX_STEP: f64 = 0.3;
+const OBJECTIVE_TOLERANCE: f64 = 1e-12;
+const MIN_RETURNS: usize = 10;
+const QUANTILE_GRID_BOUND: f64 = 30.0; // in standard deviations
+const QUANTILE_GRID_STEPS: usize = 12000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InnovationDistribution {
+    #[default]
+    Normal,
+    StudentT,
+    Nig,
+    SkewedT,
+}
+
+impl InnovationDistribution {
+    pub const ALL: [InnovationDistribution; 4] = [
+        InnovationDistribution::Normal,
+        InnovationDistribution::StudentT,
+        InnovationDistribution::Nig,
+        InnovationDistribution::SkewedT,
+    ];
+}
+
+impl fmt::Display for InnovationDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InnovationDistribution::Normal => write!(f, "Normal"),
+            InnovationDistribution::StudentT => write!(f, "Student-t"),
+            InnovationDistribution::Nig => write!(f, "NIG"),
+            InnovationDistribution::SkewedT => write!(f, "Skewed t"),
+        }
+    }
+}
+
+/// Distribution of the shocks driving the GBM paths, always with zero mean and unit variance so the
+/// volatility keeps its meaning and only the shape of the returns changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Innovations {
+    pub distribution: InnovationDistribution,
+    pub nu: f64,        // degrees of freedom of both t distributions, above 2
+    pub skew: f64,      // Hansen's lambda of the skewed t, between -1 and 1
+    pub nig_alpha: f64, // scale free tail parameter of the NIG (alpha * delta)
+    pub nig_beta: f64,  // scale free asymmetry of the NIG (beta * delta), smaller than alpha in size
+}
+
+impl Default for Innovations {
+    fn default() -> Self {
+        Innovations { distribution: InnovationDistribution::Normal, nu: 5.0, skew: 0.0, nig_alpha: 1.5, nig_beta: 0.0 }
+    }
+}
+
+impl Innovations {
+    /// Errs when the shape parameters of the chosen distribution leave it without a unit variance.
+    pub fn check_shape(&self) -> Result<(), Box<dyn Error>> {
+        let uses_nu = matches!(self.distribution, InnovationDistribution::StudentT | InnovationDistribution::SkewedT);
+        if uses_nu && self.nu <= 2.0 {
+            return Err(format!("The degrees of freedom must be above 2, got {}", self.nu).into());
+        }
+        if self.distribution == InnovationDistribution::SkewedT && self.skew.abs() >= 1.0 {
+            return Err(format!("The skew must lie strictly between -1 and 1, got {}", self.skew).into());
+        }
+        if self.distribution == InnovationDistribution::Nig && self.nig_beta.abs() >= self.nig_alpha {
+            return Err(format!("The NIG beta must be smaller than alpha in size, got alpha {} and beta {}", self.nig_alpha, self.nig_beta).into());
+        }
+        Ok(())
+    }
+
+    /// Shock of a step of length `dt`, with variance `dt`.
+    pub fn increment<R: Rng + ?Sized>(&self, dt: f64, rng: &mut R) -> f64 {
+        match self.distribution {
+            InnovationDistribution::Normal => wiener_increment(dt, rng),
+            _ => self.sample(rng) * dt.sqrt(),
+        }
+    }
+
+    /// Draws one standardised innovation.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        match self.distribution {
+            InnovationDistribution::Normal => standard_normal(rng),
+            InnovationDistribution::StudentT => unit_student_t(self.nu, rng),
+            InnovationDistribution::Nig => {
+                let (alpha, beta, mu, delta) = self.nig_parameters();
+                let gamma = (alpha * alpha - beta * beta).sqrt();
+                let mixing = InverseGaussian::new(delta / gamma, delta * delta).unwrap().sample(rng);
+                mu + beta * mixing + mixing.sqrt() * standard_normal(rng)
+            }
+            InnovationDistribution::SkewedT => {
+                // Hansen's density is a unit variance t stretched by 1 - lambda on the left and 1 + lambda on the right
+                let (a, b, _) = skewed_t_constants(self.nu, self.skew);
+                let magnitude = unit_student_t(self.nu, rng).abs();
+                let w = if rng.random::<f64>() < (1.0 - self.skew) / 2.0 { -(1.0 - self.skew) * magnitude } else { (1.0 + self.skew) * magnitude };
+                (w - a) / b
+            }
+        }
+    }
+
+    pub fn log_density(&self, z: f64) -> f64 {
+        match self.distribution {
+            InnovationDistribution::Normal => -0.5 * (z * z + (2.0 * PI).ln()),
+            InnovationDistribution::StudentT => {
+                let nu = self.nu;
+                ln_student_t_constant(nu) - 0.5 * (nu + 1.0) * (1.0 + z * z / (nu - 2.0)).ln()
+            }
+            InnovationDistribution::Nig => {
+                let (alpha, beta, mu, delta) = self.nig_parameters();
+                let gamma = (alpha * alpha - beta * beta).sqrt();
+                let q = (delta * delta + (z - mu).powi(2)).sqrt();
+                (alpha * delta / (PI * q)).ln() + ln_bessel_k1(alpha * q) + delta * gamma + beta * (z - mu)
+            }
+            InnovationDistribution::SkewedT => {
+                let nu = self.nu;
+                let (a, b, ln_c) = skewed_t_constants(nu, self.skew);
+                let side = if z < -a / b { 1.0 - self.skew } else { 1.0 + self.skew };
+                b.ln() + ln_c - 0.5 * (nu + 1.0) * (1.0 + ((b * z + a) / side).powi(2) / (nu - 2.0)).ln()
+            }
+        }
+    }
+
+    /// Quantiles at the given probabilities, read off the density integrated on a fine grid.
+    pub fn quantiles(&self, probabilities: &[f64]) -> Vec<f64> {
+        let h = 2.0 * QUANTILE_GRID_BOUND / QUANTILE_GRID_STEPS as f64;
+        let grid: Vec<f64> = (0..=QUANTILE_GRID_STEPS).map(|i| -QUANTILE_GRID_BOUND + i as f64 * h).collect();
+        let densities: Vec<f64> = grid.iter().map(|z| self.log_density(*z).exp()).collect();
+        let mut cdf = vec![0.0; grid.len()];
+        for i in 1..grid.len() {
+            cdf[i] = cdf[i - 1] + 0.5 * h * (densities[i - 1] + densities[i]);
+        }
+        let total = cdf[QUANTILE_GRID_STEPS];
+        probabilities
+            .iter()
+            .map(|p| {
+                let target = p * total;
+                let i = cdf.partition_point(|c| *c < target).clamp(1, QUANTILE_GRID_STEPS);
+                let weight = if cdf[i] > cdf[i - 1] { (target - cdf[i - 1]) / (cdf[i] - cdf[i - 1]) } else { 0.0 };
+                grid[i - 1] + weight * h
+            })
+            .collect()
+    }
+
+    /// NIG alpha, beta, mu and delta giving zero mean and unit variance for the scale free shape.
+    fn nig_parameters(&self) -> (f64, f64, f64, f64) {
+        let gamma_bar = (self.nig_alpha.powi(2) - self.nig_beta.powi(2)).sqrt();
+        let delta = gamma_bar.powf(1.5) / self.nig_alpha;
+        let mu = -delta * self.nig_beta / gamma_bar;
+        (self.nig_alpha / delta, self.nig_beta / delta, mu, delta)
+    }
+}
+
+/// Returns with their sample mean removed and scaled to unit variance.
+pub fn standardise(returns: &[f64]) -> Vec<f64> {
+    let n = returns.len().max(1) as f64;
+    let mean = returns.iter().sum::<f64>() / n;
+    let std = (returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / n).sqrt();
+    returns.iter().map(|r| if std > 0.0 { (r - mean) / std } else { 0.0 }).collect()
+}
+
+/// Fits the shape of the distribution to the standardised returns by maximum likelihood.
+pub fn fit_innovations(distribution: InnovationDistribution, returns: &[f64]) -> Result<Innovations, Box<dyn Error>> {
+    if returns.len() < MIN_RETURNS {
+        return Err(format!("At least {} returns are needed to fit the innovations", MIN_RETURNS).into());
+    }
+    let sample = standardise(returns);
+    let defaults = Innovations { distribution, ..Innovations::default() };
+    let from_unconstrained = |x: &[f64]| match distribution {
+        InnovationDistribution::Normal => defaults,
+        InnovationDistribution::StudentT => Innovations { nu: 2.0 + x[0].exp(), ..defaults },
+        InnovationDistribution::Nig => Innovations { nig_alpha: x[0].exp(), nig_beta: x[0].exp() * x[1].tanh(), ..defaults },
+        InnovationDistribution::SkewedT => Innovations { nu: 2.0 + x[0].exp(), skew: x[1].tanh(), ..defaults },
+    };
+    let initial = match distribution {
+        InnovationDistribution::Normal => return Ok(defaults),
+        InnovationDistribution::StudentT => vec![(defaults.nu - 2.0).ln()],
+        InnovationDistribution::Nig => vec![defaults.nig_alpha.ln(), 0.0],
+        InnovationDistribution::SkewedT => vec![(defaults.nu - 2.0).ln(), 0.0],
+    };
+    let objective = |x: &[f64]| {
+        let innovations = from_unconstrained(x);
+        let log_likelihood = sample.iter().map(|z| innovations.log_density(*z)).sum::<f64>();
+        if log_likelihood.is_finite() { -log_likelihood / sample.len() as f64 } else { f64::INFINITY }
+    };
+    let (best, _) = nelder_mead(objective, &initial, SIMPLEX_STEP, MAX_ITERATIONS, OBJECTIVE_TOLERANCE);
+    Ok(from_unconstrained(&best))
+}
+
+/// Sorted standardised returns against the quantiles of the fitted distribution at the same plotting positions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QqPlot {
+    pub theoretical: Vec<f64>,
+    pub empirical: Vec<f64>,
+}
+
+impl QqPlot {
+    pub fn new(innovations: &Innovations, returns: &[f64]) -> QqPlot {
+        let mut empirical = standardise(returns);
+        empirical.sort_by(f64::total_cmp);
+        let n = empirical.len() as f64;
+        let probabilities: Vec<f64> = (0..empirical.len()).map(|i| (i as f64 + 0.5) / n).collect();
+        QqPlot { theoretical: innovations.quantiles(&probabilities), empirical }
+    }
+}
+
+fn unit_student_t<R: Rng + ?Sized>(nu: f64, rng: &mut R) -> f64 {
+    StudentT::new(nu).unwrap().sample(rng) * ((nu - 2.0) / nu).sqrt()
+}
+
+/// Log normalising constant of the unit variance t density.
+fn ln_student_t_constant(nu: f64) -> f64 {
+    ln_gamma(0.5 * (nu + 1.0)) - ln_gamma(0.5 * nu) - 0.5 * ((nu - 2.0) * PI).ln()
+}
+
+/// Hansen's a, b and log c of the skewed t.
+fn skewed_t_constants(nu: f64, lambda: f64) -> (f64, f64, f64) {
+    let ln_c = ln_student_t_constant(nu);
+    let a = 4.0 * lambda * ln_c.exp() * (nu - 2.0) / (nu - 1.0);
+    let b = (1.0 + 3.0 * lambda * lambda - a * a).sqrt();
+    (a, b, ln_c)
+}
+
+/// Log of the modified Bessel function of the second kind of order one, from the polynomial
+/// approximations of Abramowitz and Stegun 9.8.3, 9.8.7 and 9.8.8.
+fn ln_bessel_k1(x: f64) -> f64 {
+    if x <= 2.0 {
+        let t = (x / 3.75).powi(2);
+        let i1 = x * (0.5 + t * (0.87890594 + t * (0.51498869 + t * (0.15084934 + t * (0.02658733 + t * (0.00301532 + t * 0.00032411))))));
+        let y = x * x / 4.0;
+        let k1 = (x / 2.0).ln() * i1
+            + (1.0 + y * (0.15443144 + y * (-0.67278579 + y * (-0.18156897 + y * (-0.01919402 + y * (-0.00110404 + y * -0.00004686)))))) / x;
+        k1.ln()
+    } else {
+        let y = 2.0 / x;
+        let series = 1.25331414 + y * (0.23498619 + y * (-0.03655620 + y * (0.01504268 + y * (-0.00780353 + y * (0.00325614 + y * -0.00068245)))));
+        series.ln() - 0.5 * x.ln() - x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn shapes() -> Vec<Innovations> {
+        vec![
+            Innovations::default(),
+            Innovations { distribution: InnovationDistribution::StudentT, nu: 4.0, ..Innovations::default() },
+            Innovations { distribution: InnovationDistribution::Nig, nig_alpha: 1.2, nig_beta: -0.5, ..Innovations::default() },
+            Innovations { distribution: InnovationDistribution::SkewedT, nu: 6.0, skew: -0.3, ..Innovations::default() },
+        ]
+    }
+
+    #[test]
+    fn test_densities_are_standardised() {
+        let h = 0.001;
+        for innovations in shapes() {
+            let moments = (-60000..=60000).fold([0.0; 3], |m, i| {
+                let z = i as f64 * h;
+                let density = innovations.log_density(z).exp() * h;
+                [m[0] + density, m[1] + z * density, m[2] + z * z * density]
+            });
+            let tolerance = if innovations.distribution == InnovationDistribution::StudentT { 2e-2 } else { 2e-3 };
+            assert_abs_diff_eq!(moments[0], 1.0, epsilon = 1e-3);
+            assert_abs_diff_eq!(moments[1], 0.0, epsilon = 1e-3);
+            assert_abs_diff_eq!(moments[2], 1.0, epsilon = tolerance);
+        }
+        assert_abs_diff_eq!(Innovations::default().quantiles(&[0.975])[0], 1.96, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_samples_are_standardised() {
+        let mut rng = rand::rng();
+        for innovations in shapes() {
+            let n = 200000;
+            let draws: Vec<f64> = (0..n).map(|_| innovations.sample(&mut rng)).collect();
+            let mean = draws.iter().sum::<f64>() / n as f64;
+            let median = innovations.quantiles(&[0.5])[0];
+            let below_median = draws.iter().filter(|z| **z < median).count() as f64 / n as f64;
+            assert_abs_diff_eq!(mean, 0.0, epsilon = 0.02);
+            assert_abs_diff_eq!(below_median, 0.5, epsilon = 0.01);
+        }
+    }
+
+    #[test]
+    fn test_check_shape_rejects_invalid_parameters() {
+        assert!(shapes().iter().all(|innovations| innovations.check_shape().is_ok()));
+        let student = Innovations { distribution: InnovationDistribution::StudentT, nu: 2.0, ..Innovations::default() };
+        assert!(student.check_shape().is_err());
+        let skewed = Innovations { distribution: InnovationDistribution::SkewedT, skew: -1.0, ..Innovations::default() };
+        assert!(skewed.check_shape().is_err());
+        let nig = Innovations { distribution: InnovationDistribution::Nig, nig_alpha: 1.0, nig_beta: 1.0, ..Innovations::default() };
+        assert!(nig.check_shape().is_err());
+    }
+
+    #[test]
+    fn test_fit_recovers_shape() {
+        let mut rng = rand::rng();
+        let truth = Innovations { distribution: InnovationDistribution::SkewedT, nu: 5.0, skew: -0.3, ..Innovations::default() };
+        let returns: Vec<f64> = (0..20000).map(|_| 0.0005 + 0.01 * truth.sample(&mut rng)).collect();
+        let fitted = fit_innovations(InnovationDistribution::SkewedT, &returns).unwrap();
+        assert_abs_diff_eq!(fitted.nu, 5.0, epsilon = 0.8);
+        assert_abs_diff_eq!(fitted.skew, -0.3, epsilon = 0.05);
+
+        let nig = fit_innovations(InnovationDistribution::Nig, &returns).unwrap();
+        assert!(nig.nig_beta < 0.0 && nig.nig_beta.abs() < nig.nig_alpha);
+
+        let qq = QqPlot::new(&fitted, &returns);
+        assert_eq!(qq.theoretical.len(), returns.len());
+        assert_abs_diff_eq!(qq.theoretical[10000], qq.empirical[10000], epsilon = 0.05);
+        assert!(fit_innovations(InnovationDistribution::StudentT, &returns[..5]).is_err());
+    }
+}
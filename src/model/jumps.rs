@@ -0,0 +1,234 @@
+use rand::Rng;
+use rand_distr::{Distribution, Exp};
+
+use super::chart::PriceChart;
+use super::monte_carlo::standard_normal;
+use super::utils::black_scholes_call;
+
+const SERIES_TERMS: i32 = 60;
+const JUMP_THRESHOLD: f64 = 3.0; // in standard deviations of the diffusive returns
+const MAX_FILTER_ITERATIONS: usize = 20;
+
+/// A compound Poisson jump component added on top of a log-normal diffusion.
+pub trait JumpProcess {
+    /// Expected number of jumps per year.
+    fn intensity(&self) -> f64;
+    /// E[e^J] - 1, used to keep the discounted spot a martingale.
+    fn compensator(&self) -> f64;
+    /// Draws the size of a single jump in log spot.
+    fn sample_jump<R: Rng + ?Sized>(&self, rng: &mut R) -> f64;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MertonParams {
+    pub lambda: f64,  // jumps per year
+    pub mu_jump: f64, // mean of the log jump size
+    pub sigma_jump: f64, // standard deviation of the log jump size
+}
+
+impl Default for MertonParams {
+    fn default() -> Self {
+        MertonParams { lambda: 1.0, mu_jump: -0.05, sigma_jump: 0.1 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KouParams {
+    pub lambda: f64,   // jumps per year
+    pub p_up: f64,     // probability that a jump is upwards
+    pub eta_up: f64,   // rate of the exponential upward jumps, must exceed 1
+    pub eta_down: f64, // rate of the exponential downward jumps
+}
+
+impl Default for KouParams {
+    fn default() -> Self {
+        KouParams { lambda: 1.0, p_up: 0.4, eta_up: 10.0, eta_down: 5.0 }
+    }
+}
+
+impl JumpProcess for MertonParams {
+    fn intensity(&self) -> f64 {
+        self.lambda
+    }
+
+    fn compensator(&self) -> f64 {
+        (self.mu_jump + 0.5 * self.sigma_jump.powi(2)).exp() - 1.0
+    }
+
+    fn sample_jump<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        self.mu_jump + self.sigma_jump * standard_normal(rng)
+    }
+}
+
+impl JumpProcess for KouParams {
+    fn intensity(&self) -> f64 {
+        self.lambda
+    }
+
+    fn compensator(&self) -> f64 {
+        self.p_up * self.eta_up / (self.eta_up - 1.0) + (1.0 - self.p_up) * self.eta_down / (self.eta_down + 1.0) - 1.0
+    }
+
+    fn sample_jump<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let u: f64 = rng.random();
+        let e: f64 = -(1.0 - rng.random::<f64>()).ln();
+        if u < self.p_up { e / self.eta_up } else { -e / self.eta_down }
+    }
+}
+
+impl MertonParams {
+    /// Merton's closed form: a Poisson weighted series of Black-Scholes prices.
+    pub fn call_price(&self, spot: f64, strike: f64, rate: f64, vol: f64, years: f64) -> f64 {
+        let kappa = self.compensator();
+        let lambda_prime = self.lambda * (1.0 + kappa);
+        let mut weight = (-lambda_prime * years).exp(); // Poisson probability of n jumps
+        let mut price = 0.0;
+        for n in 0..SERIES_TERMS {
+            if n > 0 {
+                weight *= lambda_prime * years / n as f64;
+            }
+            let sigma_n = (vol.powi(2) + n as f64 * self.sigma_jump.powi(2) / years).sqrt();
+            let rate_n = rate - self.lambda * kappa + n as f64 * (1.0 + kappa).ln() / years;
+            price += weight * black_scholes_call(spot, strike, rate_n, years, sigma_n);
+        }
+        price
+    }
+}
+
+/// Simulates a jump diffusion path, drawing exponential inter-arrival times and applying
+/// every jump that falls inside a step at the end of that step. `carries` holds the drift of every step.
+pub fn simulate_jump_diffusion<J: JumpProcess, R: Rng + ?Sized>(
+    jumps: &J,
+    spot: f64,
+    carries: &[f64],
+    vol: f64,
+    dt: f64,
+    rng: &mut R,
+) -> Vec<f64> {
+    let convexity = jumps.intensity() * jumps.compensator() + 0.5 * vol.powi(2);
+    let inter_arrival = Exp::new(jumps.intensity()).ok().filter(|_| jumps.intensity() > 0.0);
+    let next_jump = |rng: &mut R| inter_arrival.map_or(f64::INFINITY, |e| e.sample(rng));
+
+    let mut path = Vec::with_capacity(carries.len() + 1);
+    path.push(spot);
+    let mut log_spot = spot.ln();
+    let mut jump_time = next_jump(rng);
+    for (i, carry) in carries.iter().enumerate() {
+        log_spot += (carry - convexity) * dt + vol * dt.sqrt() * standard_normal(rng);
+        let step_end = (i + 1) as f64 * dt;
+        while jump_time <= step_end {
+            log_spot += jumps.sample_jump(rng);
+            jump_time += next_jump(rng);
+        }
+        path.push(log_spot.exp());
+    }
+    path
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JumpEstimate {
+    pub diffusion_vol: f64,
+    pub merton: MertonParams,
+    pub kou: KouParams,
+}
+
+fn mean_and_std(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0);
+    (mean, variance.sqrt())
+}
+
+/// Separates jumps from diffusive moves in the daily log returns of the chart by
+/// iteratively flagging returns further than a few standard deviations from the mean.
+/// `periods_per_year` annualises the intensity and the diffusive volatility.
+pub fn estimate_jumps(price_chart: &PriceChart, periods_per_year: f64) -> JumpEstimate {
+    let returns = price_chart.log_returns();
+    let mut is_jump = vec![false; returns.len()];
+    for _ in 0..MAX_FILTER_ITERATIONS {
+        let diffusive: Vec<f64> = returns.iter().zip(&is_jump).filter(|(_, j)| !**j).map(|(r, _)| *r).collect();
+        let (mean, std) = mean_and_std(&diffusive);
+        let flagged: Vec<bool> = returns.iter().map(|r| std > 0.0 && (r - mean).abs() > JUMP_THRESHOLD * std).collect();
+        if flagged == is_jump {
+            break;
+        }
+        is_jump = flagged;
+    }
+
+    let jump_returns: Vec<f64> = returns.iter().zip(&is_jump).filter(|(_, j)| **j).map(|(r, _)| *r).collect();
+    let diffusive: Vec<f64> = returns.iter().zip(&is_jump).filter(|(_, j)| !**j).map(|(r, _)| *r).collect();
+
+    let years = returns.len() as f64 / periods_per_year;
+    let lambda = if years > 0.0 { jump_returns.len() as f64 / years } else { 0.0 };
+    let (_, daily_std) = mean_and_std(&diffusive);
+    let (mu_jump, sigma_jump) = mean_and_std(&jump_returns);
+
+    let ups: Vec<f64> = jump_returns.iter().copied().filter(|r| *r > 0.0).collect();
+    let downs: Vec<f64> = jump_returns.iter().copied().filter(|r| *r < 0.0).map(|r| -r).collect();
+    let mean_of = |v: &[f64], fallback: f64| if v.is_empty() { fallback } else { v.iter().sum::<f64>() / v.len() as f64 };
+    let defaults = KouParams::default();
+
+    JumpEstimate {
+        diffusion_vol: daily_std * periods_per_year.sqrt(),
+        merton: MertonParams { lambda, mu_jump, sigma_jump },
+        kou: KouParams {
+            lambda,
+            p_up: if jump_returns.is_empty() { defaults.p_up } else { ups.len() as f64 / jump_returns.len() as f64 },
+            eta_up: 1.0 / mean_of(&ups, 1.0 / defaults.eta_up),
+            eta_down: 1.0 / mean_of(&downs, 1.0 / defaults.eta_down),
+        },
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::calendar::{Calendar, DayCount};
+    use approx::assert_abs_diff_eq;
+    use chrono::NaiveDate;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_merton_without_jumps_is_black_scholes() {
+        let merton = MertonParams { lambda: 0.0, ..MertonParams::default() };
+        let price = merton.call_price(100.0, 105.0, 0.03, 0.2, 0.5);
+        assert_abs_diff_eq!(price, black_scholes_call(100.0, 105.0, 0.03, 0.5, 0.2), epsilon = 1e-10);
+    }
+
+    #[test]
+    fn test_simulated_spot_is_martingale() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let kou = KouParams { lambda: 5.0, ..KouParams::default() };
+        let merton = MertonParams { lambda: 5.0, ..MertonParams::default() };
+        let n = 20000;
+        let kou_mean = (0..n).map(|_| *simulate_jump_diffusion(&kou, 100.0, &[0.05; 10], 0.2, 0.1, &mut rng).last().unwrap()).sum::<f64>() / n as f64;
+        let merton_mean = (0..n).map(|_| *simulate_jump_diffusion(&merton, 100.0, &[0.05; 10], 0.2, 0.1, &mut rng).last().unwrap()).sum::<f64>() / n as f64;
+        assert_abs_diff_eq!(kou_mean, 100.0 * 0.05f64.exp(), epsilon = 1.0);
+        assert_abs_diff_eq!(merton_mean, 100.0 * 0.05f64.exp(), epsilon = 1.0);
+    }
+
+    #[test]
+    fn test_estimate_jumps_finds_planted_jumps() {
+        // Seeded, as a few of the 500 normal returns fall beyond the threshold by chance
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut prices = vec![100.0];
+        for i in 1..504 {
+            let mut r = 0.01 * standard_normal(&mut rng);
+            if i % 100 == 0 {
+                r -= 0.15;
+            }
+            prices.push(prices[i - 1] * f64::exp(r));
+        }
+        let chart = PriceChart::from_prices_and_date(prices, NaiveDate::from_ymd_opt(2023, 1, 2).unwrap(), &Calendar::Nyse);
+        let estimate = estimate_jumps(&chart, chart.periods_per_year(DayCount::Bus252, &Calendar::Nyse));
+        assert_abs_diff_eq!(estimate.merton.lambda, 2.5, epsilon = 1.0);
+        assert!(estimate.merton.mu_jump < -0.1);
+        assert_abs_diff_eq!(estimate.diffusion_vol, 0.01 * 252f64.sqrt(), epsilon = 0.03);
+        assert!(estimate.kou.p_up < 0.5);
+    }
+}
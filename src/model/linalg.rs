@@ -0,0 +1,140 @@
+const JACOBI_MAX_SWEEPS: usize = 100;
+const JACOBI_TOLERANCE: f64 = 1e-14;
+const NEAREST_CORRELATION_MAX_ITERATIONS: usize = 500;
+const NEAREST_CORRELATION_TOLERANCE: f64 = 1e-10;
+const MIN_EIGENVALUE: f64 = 1e-8;
+
+pub type Matrix = Vec<Vec<f64>>;
+
+/// Lower triangular factor L with L L' = m. Returns `None` when the matrix is not positive definite.
+pub fn cholesky(m: &Matrix) -> Option<Matrix> {
+    let n = m.len();
+    let mut l = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let dot: f64 = (0..j).map(|k| l[i][k] * l[j][k]).sum();
+            if i == j {
+                let pivot = m[i][i] - dot;
+                if pivot <= 0.0 {
+                    return None;
+                }
+                l[i][i] = pivot.sqrt();
+            } else {
+                l[i][j] = (m[i][j] - dot) / l[j][j];
+            }
+        }
+    }
+    Some(l)
+}
+
+/// Eigenvalues and eigenvectors (as columns) of a symmetric matrix with the cyclic Jacobi method.
+pub fn symmetric_eigen(m: &Matrix) -> (Vec<f64>, Matrix) {
+    let n = m.len();
+    let mut a = m.clone();
+    let mut v: Matrix = (0..n).map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect()).collect();
+    for _ in 0..JACOBI_MAX_SWEEPS {
+        let off_diagonal: f64 = (0..n).flat_map(|i| (0..n).filter(move |j| *j != i).map(move |j| (i, j))).map(|(i, j)| a[i][j].powi(2)).sum();
+        if off_diagonal < JACOBI_TOLERANCE {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[p][q].abs() < f64::MIN_POSITIVE {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta.powi(2) + 1.0).sqrt());
+                let t = if theta == 0.0 { 1.0 } else { t };
+                let c = 1.0 / (t.powi(2) + 1.0).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                let (row_p, row_q) = (a[p].clone(), a[q].clone());
+                a[p] = row_p.iter().zip(&row_q).map(|(x, y)| c * x - s * y).collect();
+                a[q] = row_p.iter().zip(&row_q).map(|(x, y)| s * x + c * y).collect();
+                for row in v.iter_mut() {
+                    let (vkp, vkq) = (row[p], row[q]);
+                    row[p] = c * vkp - s * vkq;
+                    row[q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    ((0..n).map(|i| a[i][i]).collect(), v)
+}
+
+// Projection onto the positive definite matrices, flooring the eigenvalues
+fn project_positive(m: &Matrix) -> Matrix {
+    let (values, vectors) = symmetric_eigen(m);
+    let n = m.len();
+    (0..n)
+        .map(|i| (0..n).map(|j| (0..n).map(|k| vectors[i][k] * values[k].max(MIN_EIGENVALUE) * vectors[j][k]).sum()).collect())
+        .collect()
+}
+
+/// Nearest correlation matrix in the Frobenius norm, by Higham's alternating projections with
+/// Dykstra's correction. The result is rescaled to a unit diagonal, which keeps it positive definite.
+pub fn nearest_correlation(m: &Matrix) -> Matrix {
+    let n = m.len();
+    let mut y = m.clone();
+    let mut x = m.clone();
+    let mut correction = vec![vec![0.0; n]; n];
+    for _ in 0..NEAREST_CORRELATION_MAX_ITERATIONS {
+        let r: Matrix = (0..n).map(|i| (0..n).map(|j| y[i][j] - correction[i][j]).collect()).collect();
+        x = project_positive(&r);
+        correction = (0..n).map(|i| (0..n).map(|j| x[i][j] - r[i][j]).collect()).collect();
+        let previous = y;
+        y = x.clone();
+        for (i, row) in y.iter_mut().enumerate() {
+            row[i] = 1.0;
+        }
+        let change: f64 = (0..n).flat_map(|i| (0..n).map(move |j| (i, j))).map(|(i, j)| (y[i][j] - previous[i][j]).powi(2)).sum();
+        if change.sqrt() < NEAREST_CORRELATION_TOLERANCE {
+            break;
+        }
+    }
+    (0..n).map(|i| (0..n).map(|j| x[i][j] / (x[i][i] * x[j][j]).sqrt()).collect()).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn test_cholesky() {
+        let m = vec![vec![4.0, 2.0], vec![2.0, 3.0]];
+        let l = cholesky(&m).unwrap();
+        assert_abs_diff_eq!(l[0][0], 2.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(l[1][0], 1.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(l[1][1], 2f64.sqrt(), epsilon = 1e-12);
+        assert!(cholesky(&vec![vec![1.0, 2.0], vec![2.0, 1.0]]).is_none());
+    }
+
+    #[test]
+    fn test_symmetric_eigen() {
+        let m = vec![vec![2.0, 1.0, 0.0], vec![1.0, 2.0, 0.0], vec![0.0, 0.0, 5.0]];
+        let (mut values, _) = symmetric_eigen(&m);
+        values.sort_by(f64::total_cmp);
+        assert_abs_diff_eq!(values[0], 1.0, epsilon = 1e-10);
+        assert_abs_diff_eq!(values[1], 3.0, epsilon = 1e-10);
+        assert_abs_diff_eq!(values[2], 5.0, epsilon = 1e-10);
+    }
+
+    #[test]
+    fn test_nearest_correlation() {
+        // Higham's example of an invalid correlation matrix
+        let m = vec![vec![1.0, 1.0, 0.0], vec![1.0, 1.0, 1.0], vec![0.0, 1.0, 1.0]];
+        let repaired = nearest_correlation(&m);
+        assert!(cholesky(&repaired).is_some());
+        for (i, row) in repaired.iter().enumerate() {
+            assert_abs_diff_eq!(row[i], 1.0, epsilon = 1e-12);
+        }
+        assert_abs_diff_eq!(repaired[0][1], 0.7607, epsilon = 1e-3);
+        assert_abs_diff_eq!(repaired[0][2], 0.1573, epsilon = 1e-3);
+    }
+}
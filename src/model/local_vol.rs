@@ -0,0 +1,317 @@
+use std::error::Error;
+use std::fs;
+use rand::Rng;
+
+use super::monte_carlo::standard_normal;
+
+const FD_LOG_MONEYNESS_STEP: f64 = 1e-3;
+const FD_TIME_STEP: f64 = 1e-3;
+const MIN_LOCAL_VARIANCE: f64 = 1e-4;
+const MAX_LOCAL_VARIANCE: f64 = 4.0;
+const GRID_TIMES: usize = 60;
+const GRID_SPOTS: usize = 120;
+const GRID_MIN_SPOT_RATIO: f64 = 0.2;
+const GRID_MAX_SPOT_RATIO: f64 = 4.0;
+
+/// Raw SVI parameterisation of one expiry slice: w(y) = a + b (rho (y - m) + sqrt((y - m)^2 + sigma^2)),
+/// where w is total implied variance and y the log forward moneyness.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SviParams {
+    pub a: f64,
+    pub b: f64,
+    pub rho: f64,
+    pub m: f64,
+    pub sigma: f64,
+}
+
+impl SviParams {
+    pub fn total_variance(&self, y: f64) -> f64 {
+        self.a + self.b * (self.rho * (y - self.m) + ((y - self.m).powi(2) + self.sigma.powi(2)).sqrt())
+    }
+}
+
+/// An implied volatility surface, either as quoted vols on a strike grid or as fitted SVI slices.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImpliedSurface {
+    Grid {
+        expiries: Vec<f64>,
+        strikes: Vec<f64>,
+        vols: Vec<Vec<f64>>, // vols[expiry][strike]
+    },
+    Svi(Vec<(f64, SviParams)>), // (expiry, slice), sorted by expiry
+}
+
+// Piecewise linear interpolation with flat extrapolation, `xs` sorted ascending
+fn interpolate(xs: &[f64], ys: &[f64], x: f64) -> f64 {
+    if x <= xs[0] {
+        return ys[0];
+    }
+    if x >= xs[xs.len() - 1] {
+        return ys[ys.len() - 1];
+    }
+    let i = xs.partition_point(|v| *v <= x) - 1;
+    let weight = (x - xs[i]) / (xs[i + 1] - xs[i]);
+    ys[i] + weight * (ys[i + 1] - ys[i])
+}
+
+// Natural cubic spline through the points, `xs` sorted ascending, extended linearly beyond them so
+// that the first and second derivatives stay continuous everywhere
+fn natural_spline(xs: &[f64], ys: &[f64], x: f64) -> f64 {
+    let n = xs.len();
+    if n < 3 {
+        return if n == 1 { ys[0] } else { ys[0] + (x - xs[0]) * (ys[1] - ys[0]) / (xs[1] - xs[0]) };
+    }
+    // Second derivatives at the knots, zero at both ends, by the Thomas algorithm
+    let mut second = vec![0.0; n];
+    let mut diagonal = vec![0.0; n];
+    let mut rhs = vec![0.0; n];
+    for i in 1..n - 1 {
+        let (h0, h1) = (xs[i] - xs[i - 1], xs[i + 1] - xs[i]);
+        diagonal[i] = 2.0 * (h0 + h1);
+        rhs[i] = 6.0 * ((ys[i + 1] - ys[i]) / h1 - (ys[i] - ys[i - 1]) / h0);
+        if i > 1 {
+            let factor = h0 / diagonal[i - 1];
+            diagonal[i] -= factor * h0;
+            rhs[i] -= factor * rhs[i - 1];
+        }
+    }
+    for i in (1..n - 1).rev() {
+        second[i] = (rhs[i] - (xs[i + 1] - xs[i]) * second[i + 1]) / diagonal[i];
+    }
+
+    let slope = |i: usize| {
+        let h = xs[i + 1] - xs[i];
+        (ys[i + 1] - ys[i]) / h - h * (2.0 * second[i] + second[i + 1]) / 6.0
+    };
+    if x <= xs[0] {
+        return ys[0] + (x - xs[0]) * slope(0);
+    }
+    if x >= xs[n - 1] {
+        let h = xs[n - 1] - xs[n - 2];
+        let end_slope = (ys[n - 1] - ys[n - 2]) / h + h * (second[n - 2] + 2.0 * second[n - 1]) / 6.0;
+        return ys[n - 1] + (x - xs[n - 1]) * end_slope;
+    }
+    let i = xs.partition_point(|v| *v <= x) - 1;
+    let h = xs[i + 1] - xs[i];
+    let (a, b) = ((xs[i + 1] - x) / h, (x - xs[i]) / h);
+    a * ys[i] + b * ys[i + 1] + ((a.powi(3) - a) * second[i] + (b.powi(3) - b) * second[i + 1]) * h * h / 6.0
+}
+
+impl ImpliedSurface {
+    /// Total implied variance at log forward moneyness `y` and time `t`. Grid slices are splined in
+    /// their own log forward moneyness, keeping the smile twice differentiable for Dupire. Between
+    /// expiries total variance is interpolated linearly in time; before the first expiry the first
+    /// slice's implied vol is kept.
+    pub fn total_variance(&self, y: f64, t: f64, spot: f64, rate: f64) -> f64 {
+        let (expiries, slice_variances): (Vec<f64>, Vec<f64>) = match self {
+            ImpliedSurface::Grid { expiries, strikes, vols } => expiries
+                .iter()
+                .zip(vols)
+                .map(|(e, row)| {
+                    let forward = spot * (rate * e).exp();
+                    let moneyness: Vec<f64> = strikes.iter().map(|k| (k / forward).ln()).collect();
+                    let variances: Vec<f64> = row.iter().map(|vol| vol * vol * e).collect();
+                    (*e, natural_spline(&moneyness, &variances, y).max(MIN_LOCAL_VARIANCE * e))
+                })
+                .unzip(),
+            ImpliedSurface::Svi(slices) => slices.iter().map(|(e, svi)| (*e, svi.total_variance(y))).unzip(),
+        };
+        let first = expiries[0];
+        let last = expiries[expiries.len() - 1];
+        if t <= first {
+            slice_variances[0] * t / first
+        } else if t >= last {
+            slice_variances[slice_variances.len() - 1] * t / last
+        } else {
+            interpolate(&expiries, &slice_variances, t)
+        }
+    }
+
+    pub fn implied_vol(&self, strike: f64, t: f64, spot: f64, rate: f64) -> f64 {
+        let y = (strike / (spot * (rate * t).exp())).ln();
+        (self.total_variance(y, t, spot, rate) / t).sqrt()
+    }
+
+    /// Dupire local variance, written in terms of total implied variance (Gatheral, 2006).
+    pub fn local_variance(&self, y: f64, t: f64, spot: f64, rate: f64) -> f64 {
+        let h = FD_LOG_MONEYNESS_STEP;
+        let w = self.total_variance(y, t, spot, rate);
+        let w_up = self.total_variance(y + h, t, spot, rate);
+        let w_down = self.total_variance(y - h, t, spot, rate);
+        let dt = FD_TIME_STEP.min(0.5 * t);
+        let dw_dt = (self.total_variance(y, t + dt, spot, rate) - self.total_variance(y, t - dt, spot, rate)) / (2.0 * dt);
+        let dw_dy = (w_up - w_down) / (2.0 * h);
+        let d2w_dy2 = (w_up - 2.0 * w + w_down) / h.powi(2);
+
+        let denominator = 1.0 - y / w * dw_dy + 0.25 * (-0.25 - 1.0 / w + y.powi(2) / w.powi(2)) * dw_dy.powi(2) + 0.5 * d2w_dy2;
+        (dw_dt / denominator).clamp(MIN_LOCAL_VARIANCE, MAX_LOCAL_VARIANCE)
+    }
+
+    /// Reads a surface from CSV. Rows with three columns are `days_to_expire,strike,vol` grid quotes,
+    /// rows with six columns are `days_to_expire,a,b,rho,m,sigma` SVI slices. A header line is allowed,
+    /// and the calendar days are converted to years by `to_years`.
+    pub fn load<F: Fn(u64) -> f64>(path: &str, to_years: F) -> Result<ImpliedSurface, Box<dyn Error>> {
+        ImpliedSurface::parse(&fs::read_to_string(path)?, to_years)
+    }
+
+    pub fn parse<F: Fn(u64) -> f64>(contents: &str, to_years: F) -> Result<ImpliedSurface, Box<dyn Error>> {
+        let rows: Vec<Vec<f64>> = contents
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty() && !l.starts_with("days"))
+            .map(|l| l.split(',').map(|f| f.trim().parse::<f64>()).collect::<Result<Vec<f64>, _>>())
+            .collect::<Result<_, _>>()?;
+        if rows.is_empty() {
+            return Err("Volatility surface contains no rows".into());
+        }
+        let columns = rows[0].len();
+        if let Some(row) = rows.iter().find(|r| r.len() != columns) {
+            return Err(format!("Every row of the volatility surface needs {} columns, found {}", columns, row.len()).into());
+        }
+        let to_years = |days: f64| to_years(days.round().max(0.0) as u64);
+        if rows.iter().any(|r| to_years(r[0]) <= 0.0) {
+            return Err("Volatility surface expiries must lie in the future".into());
+        }
+
+        match columns {
+            6 => {
+                let mut slices: Vec<(f64, SviParams)> = rows
+                    .iter()
+                    .map(|r| (to_years(r[0]), SviParams { a: r[1], b: r[2], rho: r[3], m: r[4], sigma: r[5] }))
+                    .collect();
+                slices.sort_by(|a, b| a.0.total_cmp(&b.0));
+                Ok(ImpliedSurface::Svi(slices))
+            }
+            3 => {
+                let mut expiries: Vec<f64> = rows.iter().map(|r| to_years(r[0])).collect();
+                let mut strikes: Vec<f64> = rows.iter().map(|r| r[1]).collect();
+                expiries.sort_by(f64::total_cmp);
+                expiries.dedup();
+                strikes.sort_by(f64::total_cmp);
+                strikes.dedup();
+                let mut vols = vec![vec![f64::NAN; strikes.len()]; expiries.len()];
+                for r in &rows {
+                    let i = expiries.iter().position(|e| *e == to_years(r[0])).unwrap();
+                    let j = strikes.iter().position(|k| *k == r[1]).unwrap();
+                    vols[i][j] = r[2];
+                }
+                if vols.iter().flatten().any(|v| v.is_nan()) {
+                    return Err("Volatility grid must quote every strike for every expiry".into());
+                }
+                Ok(ImpliedSurface::Grid { expiries, strikes, vols })
+            }
+            n => Err(format!("Expected 3 or 6 columns in the volatility surface, found {}", n).into()),
+        }
+    }
+}
+
+/// Local volatility sampled on a (time, log spot) grid, looked up bilinearly during simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalVolSurface {
+    pub times: Vec<f64>,
+    pub log_spots: Vec<f64>,
+    pub vols: Vec<Vec<f64>>, // vols[time][spot]
+}
+
+impl LocalVolSurface {
+    pub fn from_implied(surface: &ImpliedSurface, spot: f64, rate: f64, max_time: f64) -> LocalVolSurface {
+        let times: Vec<f64> = (1..=GRID_TIMES).map(|i| max_time * i as f64 / GRID_TIMES as f64).collect();
+        let (low, high) = ((spot * GRID_MIN_SPOT_RATIO).ln(), (spot * GRID_MAX_SPOT_RATIO).ln());
+        let log_spots: Vec<f64> = (0..GRID_SPOTS).map(|j| low + (high - low) * j as f64 / (GRID_SPOTS - 1) as f64).collect();
+        let vols = times
+            .iter()
+            .map(|t| {
+                let log_forward = spot.ln() + rate * t;
+                log_spots.iter().map(|x| surface.local_variance(x - log_forward, *t, spot, rate).sqrt()).collect()
+            })
+            .collect();
+        LocalVolSurface { times, log_spots, vols }
+    }
+
+    pub fn vol(&self, t: f64, spot: f64) -> f64 {
+        let x = spot.ln();
+        let last = self.times.len() - 1;
+        let i = self.times.partition_point(|v| *v <= t).clamp(1, last);
+        let weight = ((t - self.times[i - 1]) / (self.times[i] - self.times[i - 1])).clamp(0.0, 1.0);
+        let before = interpolate(&self.log_spots, &self.vols[i - 1], x);
+        let after = interpolate(&self.log_spots, &self.vols[i], x);
+        before + weight * (after - before)
+    }
+
+    /// Simulates a path with a log Euler scheme whose diffusion coefficient is the local vol
+    /// at the start of each step. `carries` holds the drift of every step.
+    pub fn simulate_path<R: Rng + ?Sized>(&self, spot: f64, carries: &[f64], dt: f64, rng: &mut R) -> Vec<f64> {
+        let mut path = Vec::with_capacity(carries.len() + 1);
+        path.push(spot);
+        let mut st = spot;
+        for (i, carry) in carries.iter().enumerate() {
+            let sigma = self.vol(i as f64 * dt, st);
+            st *= ((carry - 0.5 * sigma.powi(2)) * dt + sigma * dt.sqrt() * standard_normal(rng)).exp();
+            path.push(st);
+        }
+        path
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn test_flat_surface_has_flat_local_vol() {
+        let surface = ImpliedSurface::Grid { expiries: vec![0.5, 1.0], strikes: vec![80.0, 120.0], vols: vec![vec![0.2, 0.2], vec![0.2, 0.2]] };
+        let local_vol = LocalVolSurface::from_implied(&surface, 100.0, 0.03, 1.0);
+        assert_abs_diff_eq!(local_vol.vol(0.3, 90.0), 0.2, epsilon = 1e-3);
+        assert_abs_diff_eq!(local_vol.vol(0.8, 115.0), 0.2, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_parse_surfaces() {
+        let svi = ImpliedSurface::parse("days,a,b,rho,m,sigma\n365,0.02,0.1,-0.5,0,0.2\n73,0.004,0.05,-0.5,0,0.2\n", |days| days as f64 / 365.0).unwrap();
+        match svi {
+            ImpliedSurface::Svi(slices) => assert!(slices[0].0 < slices[1].0),
+            _ => panic!("expected an SVI surface"),
+        }
+        let grid = ImpliedSurface::parse("365,90,0.25\n365,110,0.2\n", |days| days as f64 / 365.0).unwrap();
+        assert_abs_diff_eq!(grid.implied_vol(90.0, 1.0, 100.0, 0.0), 0.25, epsilon = 1e-9);
+        assert!(ImpliedSurface::parse("365,90,0.25\n730,110,0.2\n", |days| days as f64 / 365.0).is_err());
+        assert!(ImpliedSurface::parse("365,90,0.25\n365,110\n", |days| days as f64 / 365.0).is_err());
+        assert!(ImpliedSurface::parse("0,90,0.25\n0,110,0.2\n", |days| days as f64 / 365.0).is_err());
+    }
+
+    #[test]
+    fn test_grid_smile_matches_svi_local_vol() {
+        // Quotes sampled from SVI slices, each at its own forward
+        let (spot, rate) = (100.0, 0.03);
+        let near = SviParams { a: 0.015, b: 0.05, rho: -0.4, m: 0.0, sigma: 0.3 };
+        let far = SviParams { a: 0.03, b: 0.1, rho: -0.4, m: 0.0, sigma: 0.3 };
+        let svi = ImpliedSurface::Svi(vec![(0.5, near), (1.0, far)]);
+        let strikes: Vec<f64> = (0..21).map(|i| 50.0 + 5.0 * i as f64).collect();
+        let vols = [0.5, 1.0].iter().map(|e| strikes.iter().map(|k| svi.implied_vol(*k, *e, spot, rate)).collect()).collect();
+        let grid = ImpliedSurface::Grid { expiries: vec![0.5, 1.0], strikes: strikes.clone(), vols };
+
+        assert_abs_diff_eq!(grid.implied_vol(strikes[7], 0.5, spot, rate), svi.implied_vol(strikes[7], 0.5, spot, rate), epsilon = 1e-12);
+        for y in [-0.2, -0.05, 0.0, 0.1, 0.25] {
+            let expected = svi.local_variance(y, 0.75, spot, rate).sqrt();
+            assert_abs_diff_eq!(grid.local_variance(y, 0.75, spot, rate).sqrt(), expected, epsilon = 0.005);
+        }
+    }
+
+    #[test]
+    fn test_simulated_vanillas_reprice_svi_surface() {
+        let slice = SviParams { a: 0.03, b: 0.1, rho: -0.4, m: 0.0, sigma: 0.3 };
+        let surface = ImpliedSurface::Svi(vec![(1.0, slice)]);
+        let (spot, rate, years) = (100.0, 0.02, 1.0);
+        let local_vol = LocalVolSurface::from_implied(&surface, spot, rate, years);
+
+        let mut rng = rand::rng();
+        let terminals: Vec<f64> = (0..20000).map(|_| *local_vol.simulate_path(spot, &[rate; 50], years / 50.0, &mut rng).last().unwrap()).collect();
+        for strike in [85.0, 100.0, 115.0] {
+            let simulated = terminals.iter().map(|s| (s - strike).max(0.0)).sum::<f64>() / terminals.len() as f64 * (-rate * years).exp();
+            let expected = crate::model::utils::black_scholes_call(spot, strike, rate, years, surface.implied_vol(strike, years, spot, rate));
+            assert!((simulated - expected).abs() < 0.35, "strike {strike}: {simulated} vs {expected}");
+        }
+    }
+}
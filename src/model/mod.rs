@@ -1,6 +1,35 @@
+pub mod american;
+pub mod analytic;
 pub mod application;
+pub mod asian;
+pub mod backtest;
+pub mod barrier;
+pub mod bootstrap;
+pub mod calendar;
+pub mod calibration;
 pub mod chart;
+pub mod correlation;
+pub mod costs;
+pub mod dividends;
+pub mod exotics;
+pub mod hedging;
+pub mod heston;
+pub mod innovations;
+pub mod jumps;
+pub mod linalg;
+pub mod local_vol;
 pub mod monte_carlo;
-mod request;
-mod utils;
-mod params;
\ No newline at end of file
+pub mod multi_asset;
+pub mod optimize;
+pub mod performance;
+pub mod request;
+pub mod risk;
+pub mod sabr;
+pub mod scenario;
+pub mod statistics;
+pub mod strategy;
+pub mod sweep;
+pub mod systematic;
+pub mod utils;
+pub mod yield_curve;
+pub mod params;
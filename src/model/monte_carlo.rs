@@ -1,11 +1,120 @@
 use crate::model::chart::PriceChart;
 use std::error::Error;
+use std::fmt;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use rand_distr::{Normal, Distribution};
 use rayon::prelude::*;
 
-use super::{params::MonteCarloParams, utils::days_to_years};
+use super::asian::AsianOption;
+use super::barrier::BarrierOption;
+use super::bootstrap::HistoricalBootstrap;
+use super::dividends::DividendSchedule;
+use super::exotics::{DigitalOption, GapOption, LookbackStrike};
+use super::heston::{HestonParams, HestonScheme};
+use super::innovations::Innovations;
+use super::jumps::{simulate_jump_diffusion, KouParams, MertonParams};
+use super::local_vol::LocalVolSurface;
+use super::sabr::SabrParams;
+use super::yield_curve::YieldCurve;
+use super::params::MonteCarloParams;
 
-#[derive(Default)]
+const SEEDED_PATHS_SEED: u64 = 2024; // of the first path, the others following it
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SimulationModel {
+    #[default]
+    Gbm,
+    Heston,
+    Merton,
+    Kou,
+    LocalVol,
+    Sabr,
+    Bootstrap,
+}
+
+impl SimulationModel {
+    pub const ALL: [SimulationModel; 7] = [
+        SimulationModel::Gbm,
+        SimulationModel::Heston,
+        SimulationModel::Merton,
+        SimulationModel::Kou,
+        SimulationModel::LocalVol,
+        SimulationModel::Sabr,
+        SimulationModel::Bootstrap,
+    ];
+}
+
+impl fmt::Display for SimulationModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationModel::Gbm => write!(f, "GBM"),
+            SimulationModel::Heston => write!(f, "Heston"),
+            SimulationModel::Merton => write!(f, "Merton jump diffusion"),
+            SimulationModel::Kou => write!(f, "Kou jump diffusion"),
+            SimulationModel::LocalVol => write!(f, "Dupire local vol"),
+            SimulationModel::Sabr => write!(f, "SABR"),
+            SimulationModel::Bootstrap => write!(f, "Historical bootstrap"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExerciseStyle {
+    #[default]
+    European,
+    American,
+}
+
+impl ExerciseStyle {
+    pub const ALL: [ExerciseStyle; 2] = [ExerciseStyle::European, ExerciseStyle::American];
+}
+
+impl fmt::Display for ExerciseStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExerciseStyle::European => write!(f, "European"),
+            ExerciseStyle::American => write!(f, "American"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PayoffKind {
+    #[default]
+    Vanilla,
+    Barrier,
+    Asian,
+    Lookback,
+    Digital,
+    Gap,
+}
+
+impl PayoffKind {
+    pub const ALL: [PayoffKind; 6] = [
+        PayoffKind::Vanilla,
+        PayoffKind::Barrier,
+        PayoffKind::Asian,
+        PayoffKind::Lookback,
+        PayoffKind::Digital,
+        PayoffKind::Gap,
+    ];
+}
+
+impl fmt::Display for PayoffKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PayoffKind::Vanilla => write!(f, "Vanilla call"),
+            PayoffKind::Barrier => write!(f, "Barrier call"),
+            PayoffKind::Asian => write!(f, "Asian call"),
+            PayoffKind::Lookback => write!(f, "Lookback call"),
+            PayoffKind::Digital => write!(f, "Digital call"),
+            PayoffKind::Gap => write!(f, "Gap call"),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
 pub struct MonteCarloPricing {
     pub current_asset_price: f64,
     pub market_option_price: f64,
@@ -15,6 +124,25 @@ pub struct MonteCarloPricing {
     pub risk_free_rate: f64,
     pub implied_vol: f64,
     pub years_to_expire: f64,
+    pub model: SimulationModel,
+    pub innovations: Innovations,
+    pub heston: HestonParams,
+    pub heston_scheme: HestonScheme,
+    pub merton: MertonParams,
+    pub kou: KouParams,
+    pub local_vol: Option<LocalVolSurface>,
+    pub sabr: SabrParams,
+    pub bootstrap: HistoricalBootstrap,
+    pub dividend_yield: f64,
+    pub dividends: DividendSchedule,
+    pub exercise: ExerciseStyle,
+    pub yield_curve: Option<YieldCurve>,
+    pub payoff: PayoffKind,
+    pub barrier: BarrierOption,
+    pub asian: AsianOption,
+    pub lookback: LookbackStrike,
+    pub digital: DigitalOption,
+    pub gap: GapOption,
 }
 
 impl MonteCarloPricing {
@@ -27,48 +155,238 @@ impl MonteCarloPricing {
             num_steps: params.num_steps.parse::<u16>().unwrap_or(5),
             risk_free_rate: params.risk_free_rate.parse::<f64>().unwrap_or(0.0),
             implied_vol: params.implied_vol.parse::<f64>().unwrap_or(0.03),
-            years_to_expire: days_to_years(params.days_to_expire.parse::<u16>().unwrap_or(30)),
+            years_to_expire: params.years_to_expire(),
+            model: params.model,
+            innovations: Innovations {
+                distribution: params.innovations,
+                nu: params.innovation_nu.parse::<f64>().unwrap_or(5.0),
+                skew: params.innovation_skew.parse::<f64>().unwrap_or(0.0),
+                nig_alpha: params.nig_alpha.parse::<f64>().unwrap_or(1.5),
+                nig_beta: params.nig_beta.parse::<f64>().unwrap_or(0.0),
+            },
+            heston: HestonParams {
+                kappa: params.heston_kappa.parse::<f64>().unwrap_or(2.0),
+                theta: params.heston_theta.parse::<f64>().unwrap_or(0.04),
+                xi: params.heston_xi.parse::<f64>().unwrap_or(0.3),
+                rho: params.heston_rho.parse::<f64>().unwrap_or(-0.7),
+                v0: params.heston_v0.parse::<f64>().unwrap_or(0.04),
+            },
+            heston_scheme: params.heston_scheme,
+            merton: MertonParams {
+                lambda: params.merton_lambda.parse::<f64>().unwrap_or(1.0),
+                mu_jump: params.merton_mu_jump.parse::<f64>().unwrap_or(-0.05),
+                sigma_jump: params.merton_sigma_jump.parse::<f64>().unwrap_or(0.1),
+            },
+            kou: KouParams {
+                lambda: params.kou_lambda.parse::<f64>().unwrap_or(1.0),
+                p_up: params.kou_p_up.parse::<f64>().unwrap_or(0.4),
+                eta_up: params.kou_eta_up.parse::<f64>().unwrap_or(10.0),
+                eta_down: params.kou_eta_down.parse::<f64>().unwrap_or(5.0),
+            },
+            local_vol: None,
+            sabr: SabrParams {
+                alpha: params.sabr_alpha.parse::<f64>().unwrap_or(0.2),
+                beta: params.sabr_beta.parse::<f64>().unwrap_or(1.0),
+                rho: params.sabr_rho.parse::<f64>().unwrap_or(-0.3),
+                nu: params.sabr_nu.parse::<f64>().unwrap_or(0.4),
+            },
+            bootstrap: HistoricalBootstrap::default(),
+            dividend_yield: params.dividend_yield.parse::<f64>().unwrap_or(0.0),
+            dividends: DividendSchedule::default(),
+            exercise: params.exercise,
+            yield_curve: None,
+            payoff: params.payoff,
+            barrier: BarrierOption {
+                direction: params.barrier_direction,
+                knock: params.barrier_knock,
+                level: params.barrier_level.parse::<f64>().unwrap_or(90.0),
+                rebate: params.barrier_rebate.parse::<f64>().unwrap_or(0.0),
+                brownian_bridge: params.brownian_bridge,
+            },
+            asian: AsianOption {
+                average: params.asian_average,
+                strike: params.asian_strike,
+                averaging_times: params.averaging_times(),
+            },
+            lookback: params.lookback_strike,
+            digital: DigitalOption {
+                kind: params.digital_kind,
+                cash: params.digital_cash.parse::<f64>().unwrap_or(1.0),
+                smoothing: params.digital_smoothing.parse::<f64>().unwrap_or(0.0),
+            },
+            gap: GapOption { trigger: params.gap_trigger.parse::<f64>().unwrap_or(100.0) },
         }
     }
 
+    /// Fails when the selected model lacks the market data it simulates from.
+    pub fn check_model(&self) -> Result<(), Box<dyn Error>> {
+        if self.model == SimulationModel::LocalVol && self.local_vol.is_none() {
+            return Err("No volatility surface was loaded for the local volatility model".into());
+        }
+        if self.model == SimulationModel::Bootstrap && self.bootstrap.returns.len() < 2 {
+            return Err("Not enough price history to bootstrap returns from".into());
+        }
+        if self.model == SimulationModel::Kou && self.kou.eta_up <= 1.0 {
+            // E[exp(J)] of the upward jumps is only finite for a rate above 1
+            return Err(format!("The rate of the upward Kou jumps must exceed 1, got {}", self.kou.eta_up).into());
+        }
+        self.innovations.check_shape()
+    }
+
     pub fn price(&self, price_chart: &PriceChart) -> Result<f64, Box<dyn Error>> {
-        let dt = self.years_to_expire / self.num_steps as f64;
+        self.check_model()?;
+        let spot = price_chart.underlying_price();
+        if self.exercise == ExerciseStyle::American {
+            if self.payoff != PayoffKind::Vanilla {
+                return Err("American exercise is only supported for vanilla payoffs".into());
+            }
+            let paths: Vec<Vec<f64>> = (0..self.num_simulations)
+                .into_par_iter()
+                .map(|_| self.simulate_path(spot, &mut rand::rng()))
+                .collect();
+            return Ok(self.longstaff_schwartz(&paths));
+        }
+        if self.payoff == PayoffKind::Asian {
+            return Ok(self.asian_estimate(spot).price);
+        }
 
         let sum_payoff: f64 = (0..self.num_simulations)
             .into_par_iter() // Run simulations in parallel
-            .map(|_| {
-                let mut st = price_chart.underlying_price();
-                for _ in 0..self.num_steps {
-                    let w = wiener_increment(dt);
-                    st *= 1.0 + self.risk_free_rate * dt + self.implied_vol * w;
-                }
-                (st - self.strike_price).max(0.0) 
-            })
+            .map(|_| self.discounted_payoff(&self.simulate_path(spot, &mut rand::rng())))
             .sum(); 
 
-        let average_payoff = (sum_payoff / self.num_simulations as f64)
-            * (-self.risk_free_rate * self.years_to_expire).exp();
-        Ok(average_payoff)
+        Ok(sum_payoff / self.num_simulations as f64)
+    }
+
+    /// Present value of the selected payoff along one simulated path.
+    /// Price at `spot` on paths seeded by their index, so repeated valuations see the same random numbers
+    /// and the differences between them are free of simulation noise.
+    pub fn seeded_price(&self, spot: f64) -> Result<f64, Box<dyn Error>> {
+        self.check_model()?;
+        let paths: Vec<Vec<f64>> = (0..self.num_simulations as u64)
+            .into_par_iter()
+            .map(|i| self.simulate_path(spot, &mut StdRng::seed_from_u64(SEEDED_PATHS_SEED + i)))
+            .collect();
+        if self.exercise == ExerciseStyle::American {
+            return Ok(self.longstaff_schwartz(&paths));
+        }
+        Ok(paths.iter().map(|path| self.discounted_payoff(path)).sum::<f64>() / paths.len().max(1) as f64)
+    }
+
+    pub fn discounted_payoff(&self, path: &[f64]) -> f64 {
+        match self.payoff {
+            PayoffKind::Vanilla => {
+                let st = path[path.len() - 1];
+                (st - self.strike_price).max(0.0) * self.discount_factor(self.years_to_expire)
+            }
+            PayoffKind::Barrier => self.barrier_payoff(path),
+            PayoffKind::Asian => self.asian_payoff(path),
+            PayoffKind::Lookback => self.lookback_payoff(path),
+            PayoffKind::Digital => self.digital_payoff(path),
+            PayoffKind::Gap => self.gap_payoff(path),
+        }
+    }
+
+    /// The loaded yield curve, or a flat curve at the risk free rate.
+    pub fn curve(&self) -> YieldCurve {
+        self.yield_curve.clone().unwrap_or_else(|| YieldCurve::flat(self.risk_free_rate))
+    }
+
+    pub fn zero_rate(&self, years: f64) -> f64 {
+        self.yield_curve.as_ref().map_or(self.risk_free_rate, |c| c.zero_rate(years))
+    }
+
+    pub fn discount_factor(&self, years: f64) -> f64 {
+        (-self.zero_rate(years) * years).exp()
+    }
+
+    /// Average risk neutral drift of the spot until expiry: the zero rate net of the continuous dividend yield.
+    pub fn carry(&self) -> f64 {
+        self.zero_rate(self.years_to_expire) - self.dividend_yield
+    }
+
+    /// Drift of every simulation step: the forward rate over the step net of the continuous dividend yield.
+    pub fn step_carries(&self) -> Vec<f64> {
+        let dt = self.years_to_expire / self.num_steps as f64;
+        match &self.yield_curve {
+            Some(curve) => (0..self.num_steps)
+                .map(|i| curve.forward_rate(i as f64 * dt, (i + 1) as f64 * dt) - self.dividend_yield)
+                .collect(),
+            None => vec![self.risk_free_rate - self.dividend_yield; self.num_steps as usize],
+        }
+    }
+
+    /// Simulates one path of the underlying under the selected model, including the starting spot.
+    /// Discrete dividends are taken off the spot on their ex-dates. The model must pass `check_model`.
+    pub fn simulate_path<R: Rng + ?Sized>(&self, spot: f64, rng: &mut R) -> Vec<f64> {
+        let dt = self.years_to_expire / self.num_steps as f64;
+        let carries = self.step_carries();
+        let mut path = match self.model {
+            SimulationModel::Gbm => self.gbm_path(spot, &carries, dt, rng),
+            SimulationModel::Heston => self.heston_path(spot, &carries, dt, rng),
+            SimulationModel::Merton => simulate_jump_diffusion(&self.merton, spot, &carries, self.implied_vol, dt, rng),
+            SimulationModel::Kou => simulate_jump_diffusion(&self.kou, spot, &carries, self.implied_vol, dt, rng),
+            SimulationModel::LocalVol => self
+                .local_vol
+                .as_ref()
+                .expect("check_model rejects the local volatility model without a surface")
+                .simulate_path(spot, &carries, dt, rng),
+            SimulationModel::Sabr => self.sabr.simulate_path(spot, &carries, dt, rng),
+            SimulationModel::Bootstrap => self.bootstrap.simulate_path(spot, &carries, dt, rng),
+        };
+        self.dividends.apply_to_path(&mut path, dt);
+        path
+    }
+
+    fn gbm_path<R: Rng + ?Sized>(&self, spot: f64, carries: &[f64], dt: f64, rng: &mut R) -> Vec<f64> {
+        let mut path = Vec::with_capacity(carries.len() + 1);
+        path.push(spot);
 
+        // Stepping the log of the spot keeps it positive under fat tailed shocks, and is exact for normal ones
+        let mut st = spot;
+        for carry in carries {
+            let w = self.innovations.increment(dt, rng);
+            st *= ((carry - 0.5 * self.implied_vol.powi(2)) * dt + self.implied_vol * w).exp();
+            path.push(st);
+        }
+        path
+    }
+
+    fn heston_path<R: Rng + ?Sized>(&self, spot: f64, carries: &[f64], dt: f64, rng: &mut R) -> Vec<f64> {
+        let mut path = Vec::with_capacity(carries.len() + 1);
+        path.push(spot);
+
+        let mut log_spot = spot.ln();
+        let mut variance = self.heston.v0;
+        for carry in carries {
+            (log_spot, variance) = self.heston.step(self.heston_scheme, log_spot, variance, *carry, dt, rng);
+            path.push(log_spot.exp());
+        }
+        path
     }
 }
 
 
 
-pub(crate)fn wiener_increment(dt: f64) -> f64 {
+pub(crate) fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
     let normal = Normal::new(0.0, 1.0).unwrap();
-    let mut rng = rand::rng();
-
-    let sample = normal.sample(&mut rng);
+    normal.sample(rng)
+}
 
-    sample * dt.sqrt()
+pub(crate) fn wiener_increment<R: Rng + ?Sized>(dt: f64, rng: &mut R) -> f64 {
+    standard_normal(rng) * dt.sqrt()
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::calendar::Calendar;
     use crate::model::chart::PriceChart;
+    use crate::model::dividends::{Dividend, DividendKind};
+    use crate::model::innovations::InnovationDistribution;
+    use crate::model::yield_curve::{CurveInterpolation, InterpolatedQuantity};
+    use approx::assert_abs_diff_eq;
 
     #[test]
     fn test_from_params() {
@@ -81,6 +399,7 @@ mod tests {
             risk_free_rate: "0.05".to_string(),
             implied_vol: "0.2".to_string(),
             num_steps: "10".to_string(),
+            ..MonteCarloParams::default()
         };
 
         let mc = MonteCarloPricing::from_params(&params);
@@ -102,6 +421,7 @@ mod tests {
             risk_free_rate: 0.05,
             implied_vol: 0.2,
             years_to_expire: 1.0,
+            ..MonteCarloPricing::default()
         };
 
         let price_chart = PriceChart::default();
@@ -111,10 +431,137 @@ mod tests {
         assert!(price_result.unwrap() > 0.0);
     }
 
+    #[test]
+    fn test_fat_tailed_gbm_stays_positive() {
+        let mc = MonteCarloPricing {
+            num_steps: 2,
+            implied_vol: 1.5,
+            years_to_expire: 1.0,
+            innovations: Innovations { distribution: InnovationDistribution::StudentT, nu: 2.5, ..Innovations::default() },
+            ..MonteCarloPricing::default()
+        };
+        let mut rng = rand::rng();
+        assert!((0..20000).all(|_| mc.simulate_path(100.0, &mut rng).iter().all(|s| *s > 0.0)));
+    }
+
     #[test]
     fn test_wiener_increment() {
         let dt = 0.01;
-        let w = wiener_increment(dt);
+        let w = wiener_increment(dt, &mut rand::rng());
         assert!(w.is_finite());
     }
+
+    #[test]
+    fn test_heston_price_matches_semi_analytic() {
+        for scheme in HestonScheme::ALL {
+            let mc = MonteCarloPricing {
+                strike_price: 100.0,
+                num_simulations: 20000,
+                num_steps: 50,
+                risk_free_rate: 0.025,
+                years_to_expire: 1.0,
+                model: SimulationModel::Heston,
+                heston: HestonParams { kappa: 1.5768, theta: 0.0398, xi: 0.5751, rho: -0.5711, v0: 0.0175 },
+                heston_scheme: scheme,
+                ..MonteCarloPricing::default()
+            };
+            let price_chart = PriceChart::from_prices_and_date(vec![100.0], chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), &Calendar::Nyse);
+            let simulated = mc.price(&price_chart).unwrap();
+            let analytic = mc.heston.call_price(100.0, 100.0, 0.025, 1.0);
+            assert!((simulated - analytic).abs() < 0.25, "{scheme}: {simulated} vs {analytic}");
+        }
+    }
+
+    #[test]
+    fn test_merton_price_matches_series() {
+        let mc = MonteCarloPricing {
+            strike_price: 100.0,
+            num_simulations: 20000,
+            num_steps: 20,
+            risk_free_rate: 0.03,
+            implied_vol: 0.2,
+            years_to_expire: 1.0,
+            model: SimulationModel::Merton,
+            merton: MertonParams { lambda: 2.0, mu_jump: -0.1, sigma_jump: 0.15 },
+            ..MonteCarloPricing::default()
+        };
+        let price_chart = PriceChart::from_prices_and_date(vec![100.0], chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), &Calendar::Nyse);
+        let simulated = mc.price(&price_chart).unwrap();
+        let analytic = mc.merton.call_price(100.0, 100.0, 0.03, 0.2, 1.0);
+        assert!((simulated - analytic).abs() < 0.4, "{simulated} vs {analytic}");
+    }
+
+    #[test]
+    fn test_kou_rejects_upward_jumps_without_a_mean() {
+        let mut mc = MonteCarloPricing { model: SimulationModel::Kou, ..MonteCarloPricing::default() };
+        assert!(mc.check_model().is_ok());
+        mc.kou.eta_up = 1.0;
+        assert!(mc.check_model().is_err());
+    }
+
+    #[test]
+    fn test_sabr_price_matches_hagan_vol() {
+        let mc = MonteCarloPricing {
+            current_asset_price: 100.0,
+            strike_price: 105.0,
+            num_simulations: 20000,
+            num_steps: 50,
+            risk_free_rate: 0.02,
+            years_to_expire: 0.5,
+            model: SimulationModel::Sabr,
+            sabr: SabrParams { alpha: 0.2, beta: 1.0, rho: -0.3, nu: 0.4 },
+            ..MonteCarloPricing::default()
+        };
+        let price_chart = PriceChart::from_prices_and_date(vec![100.0], chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), &Calendar::Nyse);
+        let simulated = mc.price(&price_chart).unwrap();
+        let analytic = mc.black_scholes_call_price(mc.sabr_implied_vol());
+        assert!((simulated - analytic).abs() < 0.2, "{simulated} vs {analytic}");
+    }
+
+    #[test]
+    fn test_dividends_match_closed_form() {
+        let price_chart = PriceChart::from_prices_and_date(vec![100.0], chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), &Calendar::Nyse);
+        let mut mc = MonteCarloPricing {
+            current_asset_price: 100.0,
+            strike_price: 100.0,
+            num_simulations: 40000,
+            num_steps: 20,
+            risk_free_rate: 0.05,
+            implied_vol: 0.2,
+            years_to_expire: 1.0,
+            dividend_yield: 0.03,
+            ..MonteCarloPricing::default()
+        };
+        let simulated = mc.price(&price_chart).unwrap();
+        let analytic = mc.black_scholes_call_price(0.2);
+        assert!((simulated - analytic).abs() < 0.2, "yield: {simulated} vs {analytic}");
+
+        mc.dividend_yield = 0.0;
+        mc.dividends = DividendSchedule::new(vec![Dividend { years_to_ex_date: 0.4, amount: 0.05, kind: DividendKind::Proportional }]);
+        let simulated = mc.price(&price_chart).unwrap();
+        let analytic = mc.black_scholes_call_price(0.2);
+        assert!((simulated - analytic).abs() < 0.2, "proportional: {simulated} vs {analytic}");
+    }
+
+    #[test]
+    fn test_yield_curve_drift_and_discounting() {
+        let price_chart = PriceChart::from_prices_and_date(vec![100.0], chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), &Calendar::Nyse);
+        let curve = YieldCurve::new(vec![0.25, 1.0, 2.0], vec![0.01, 0.04, 0.06], CurveInterpolation::MonotoneCubic, InterpolatedQuantity::ZeroRate).unwrap();
+        let mc = MonteCarloPricing {
+            current_asset_price: 100.0,
+            strike_price: 100.0,
+            num_simulations: 40000,
+            num_steps: 20,
+            implied_vol: 0.2,
+            years_to_expire: 1.5,
+            yield_curve: Some(curve.clone()),
+            ..MonteCarloPricing::default()
+        };
+        let integrated: f64 = mc.step_carries().iter().sum::<f64>() * mc.years_to_expire / mc.num_steps as f64;
+        assert_abs_diff_eq!(integrated, curve.zero_rate(1.5) * 1.5, epsilon = 1e-12);
+
+        let simulated = mc.price(&price_chart).unwrap();
+        let analytic = mc.black_scholes_call_price(0.2);
+        assert!((simulated - analytic).abs() < 0.25, "{simulated} vs {analytic}");
+    }
 }
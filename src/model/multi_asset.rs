@@ -0,0 +1,290 @@
+use std::error::Error;
+use std::fmt;
+use rand::Rng;
+use rayon::prelude::*;
+
+use super::analytic::kirk_spread_call;
+use super::linalg::{cholesky, nearest_correlation, Matrix};
+use super::monte_carlo::standard_normal;
+use super::params::MonteCarloParams;
+
+const CORRELATION_TOLERANCE: f64 = 1e-8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MultiAssetPayoff {
+    #[default]
+    Basket,  // pays the weighted sum of the assets over the strike
+    BestOf,  // pays the best performing asset over the strike
+    WorstOf, // pays the worst performing asset over the strike
+    Spread,  // pays the first asset less the second over the strike
+}
+
+impl MultiAssetPayoff {
+    pub const ALL: [MultiAssetPayoff; 4] = [
+        MultiAssetPayoff::Basket,
+        MultiAssetPayoff::BestOf,
+        MultiAssetPayoff::WorstOf,
+        MultiAssetPayoff::Spread,
+    ];
+}
+
+impl fmt::Display for MultiAssetPayoff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MultiAssetPayoff::Basket => write!(f, "Basket call"),
+            MultiAssetPayoff::BestOf => write!(f, "Best-of call"),
+            MultiAssetPayoff::WorstOf => write!(f, "Worst-of call"),
+            MultiAssetPayoff::Spread => write!(f, "Spread call"),
+        }
+    }
+}
+
+/// One underlying of a multi-asset option, following a GBM with a continuous dividend yield.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Asset {
+    pub spot: f64,
+    pub vol: f64,
+    pub dividend_yield: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiAssetEstimate {
+    pub price: f64,
+    pub std_error: f64,
+    pub closed_form: Option<f64>,
+    pub repaired_correlation: Option<Matrix>, // set when the entered matrix was not positive definite
+}
+
+/// European option on several correlated underlyings, on a flat rate.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MultiAssetPricing {
+    pub assets: Vec<Asset>,
+    pub weights: Vec<f64>,
+    pub correlation: Matrix,
+    pub payoff: MultiAssetPayoff,
+    pub strike_price: f64,
+    pub risk_free_rate: f64,
+    pub years_to_expire: f64,
+    pub num_simulations: u16,
+    pub num_steps: u16,
+}
+
+pub(crate) fn parse_list(text: &str) -> Result<Vec<f64>, Box<dyn Error>> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| v.parse::<f64>().map_err(|e| -> Box<dyn Error> { format!("Invalid number '{}'. {}", v, e).into() }))
+        .collect()
+}
+
+/// Parses a matrix written row by row, rows separated by semicolons or new lines and entries by commas.
+pub fn parse_correlation(text: &str) -> Result<Matrix, Box<dyn Error>> {
+    let rows: Matrix = text
+        .split([';', '\n'])
+        .filter(|row| !row.trim().is_empty())
+        .map(parse_list)
+        .collect::<Result<_, _>>()?;
+    if rows.iter().any(|row| row.len() != rows.len()) {
+        return Err("Correlation matrix is not square".into());
+    }
+    Ok(rows)
+}
+
+/// Writes a matrix in the format read by [`parse_correlation`].
+pub fn format_correlation(matrix: &Matrix) -> String {
+    matrix
+        .iter()
+        .map(|row| row.iter().map(|c| format!("{:.4}", c)).collect::<Vec<_>>().join(", "))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl MultiAssetPricing {
+    pub fn from_params(params: &MonteCarloParams) -> Result<MultiAssetPricing, Box<dyn Error>> {
+        let spots = parse_list(&params.asset_spots)?;
+        let vols = parse_list(&params.asset_vols)?;
+        let yields = parse_list(&params.asset_dividend_yields)?;
+        if vols.len() != spots.len() || yields.len() != spots.len() {
+            return Err("Expected a spot, a volatility and a dividend yield for every asset".into());
+        }
+        let pricing = MultiAssetPricing {
+            assets: spots
+                .iter()
+                .zip(&vols)
+                .zip(&yields)
+                .map(|((&spot, &vol), &dividend_yield)| Asset { spot, vol, dividend_yield })
+                .collect(),
+            weights: parse_list(&params.basket_weights)?,
+            correlation: parse_correlation(&params.correlation)?,
+            payoff: params.multi_asset_payoff,
+            strike_price: params.strike_price.parse::<f64>().unwrap_or(0.0),
+            risk_free_rate: params.risk_free_rate.parse::<f64>().unwrap_or(0.0),
+            years_to_expire: params.years_to_expire(),
+            num_simulations: params.num_simulations.parse::<u16>().unwrap_or(1000),
+            num_steps: params.num_steps.parse::<u16>().unwrap_or(5),
+        };
+        pricing.validate()?;
+        Ok(pricing)
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        let n = self.assets.len();
+        if n == 0 {
+            return Err("No assets were specified".into());
+        }
+        if self.payoff == MultiAssetPayoff::Spread && n != 2 {
+            return Err("A spread option needs exactly two assets".into());
+        }
+        if !self.weights.is_empty() && self.weights.len() != n {
+            return Err(format!("Expected {} basket weights, got {}", n, self.weights.len()).into());
+        }
+        if self.correlation.len() != n || self.correlation.iter().any(|row| row.len() != n) {
+            return Err(format!("Expected a {n}x{n} correlation matrix").into());
+        }
+        for i in 0..n {
+            if (self.correlation[i][i] - 1.0).abs() > CORRELATION_TOLERANCE {
+                return Err("Correlation matrix must have a unit diagonal".into());
+            }
+            for j in 0..i {
+                let rho = self.correlation[i][j];
+                if (rho - self.correlation[j][i]).abs() > CORRELATION_TOLERANCE {
+                    return Err("Correlation matrix is not symmetric".into());
+                }
+                if rho.abs() > 1.0 {
+                    return Err(format!("Correlation {} is outside [-1, 1]", rho).into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Cholesky factor of the correlation matrix. An entered matrix that is not positive definite is
+    /// replaced by its nearest correlation matrix, which is returned alongside.
+    pub fn correlation_factor(&self) -> (Matrix, Option<Matrix>) {
+        if let Some(factor) = cholesky(&self.correlation) {
+            return (factor, None);
+        }
+        let repaired = nearest_correlation(&self.correlation);
+        let factor = cholesky(&repaired).expect("nearest correlation matrix is positive definite");
+        (factor, Some(repaired))
+    }
+
+    fn weight(&self, i: usize) -> f64 {
+        self.weights.get(i).copied().unwrap_or(1.0 / self.assets.len() as f64)
+    }
+
+    /// One path per asset, driven by normals correlated through the Cholesky `factor`.
+    pub fn simulate_paths<R: Rng + ?Sized>(&self, factor: &Matrix, rng: &mut R) -> Vec<Vec<f64>> {
+        let dt = self.years_to_expire / self.num_steps as f64;
+        let mut paths: Vec<Vec<f64>> = self.assets.iter().map(|a| vec![a.spot]).collect();
+        let mut log_spots: Vec<f64> = self.assets.iter().map(|a| a.spot.ln()).collect();
+        for _ in 0..self.num_steps {
+            let z: Vec<f64> = (0..self.assets.len()).map(|_| standard_normal(rng)).collect();
+            for (i, asset) in self.assets.iter().enumerate() {
+                let correlated: f64 = (0..=i).map(|k| factor[i][k] * z[k]).sum();
+                log_spots[i] += (self.risk_free_rate - asset.dividend_yield - 0.5 * asset.vol.powi(2)) * dt + asset.vol * dt.sqrt() * correlated;
+                paths[i].push(log_spots[i].exp());
+            }
+        }
+        paths
+    }
+
+    /// Undiscounted payoff on the spots at expiry.
+    pub fn payoff(&self, terminal: &[f64]) -> f64 {
+        let underlying = match self.payoff {
+            MultiAssetPayoff::Basket => terminal.iter().enumerate().map(|(i, s)| self.weight(i) * s).sum(),
+            MultiAssetPayoff::BestOf => terminal.iter().fold(f64::MIN, |a, &b| a.max(b)),
+            MultiAssetPayoff::WorstOf => terminal.iter().fold(f64::MAX, |a, &b| a.min(b)),
+            MultiAssetPayoff::Spread => terminal[0] - terminal[1],
+        };
+        (underlying - self.strike_price).max(0.0)
+    }
+
+    /// Kirk's approximation for spreads, which is Margrabe's exact price at a zero strike.
+    pub fn closed_form_price(&self) -> Option<f64> {
+        if self.payoff != MultiAssetPayoff::Spread || self.assets.len() != 2 {
+            return None;
+        }
+        Some(kirk_spread_call(&self.assets[0], &self.assets[1], self.strike_price, self.risk_free_rate, self.correlation[0][1], self.years_to_expire))
+    }
+
+    pub fn price(&self) -> Result<MultiAssetEstimate, Box<dyn Error>> {
+        self.validate()?;
+        let (factor, repaired_correlation) = self.correlation_factor();
+        let discount = (-self.risk_free_rate * self.years_to_expire).exp();
+        let payoffs: Vec<f64> = (0..self.num_simulations)
+            .into_par_iter()
+            .map(|_| {
+                let paths = self.simulate_paths(&factor, &mut rand::rng());
+                let terminal: Vec<f64> = paths.iter().map(|p| p[p.len() - 1]).collect();
+                self.payoff(&terminal) * discount
+            })
+            .collect();
+
+        let n = payoffs.len() as f64;
+        let price = payoffs.iter().sum::<f64>() / n;
+        let variance = payoffs.iter().map(|p| (p - price).powi(2)).sum::<f64>() / (n - 1.0).max(1.0);
+        Ok(MultiAssetEstimate { price, std_error: (variance / n).sqrt(), closed_form: self.closed_form_price(), repaired_correlation })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pricing(payoff: MultiAssetPayoff, strike: f64, rho: f64) -> MultiAssetPricing {
+        MultiAssetPricing {
+            assets: vec![
+                Asset { spot: 100.0, vol: 0.25, dividend_yield: 0.02 },
+                Asset { spot: 95.0, vol: 0.3, dividend_yield: 0.0 },
+            ],
+            weights: Vec::new(),
+            correlation: vec![vec![1.0, rho], vec![rho, 1.0]],
+            payoff,
+            strike_price: strike,
+            risk_free_rate: 0.05,
+            years_to_expire: 0.5,
+            num_simulations: 40000,
+            num_steps: 1,
+        }
+    }
+
+    #[test]
+    fn test_spread_matches_margrabe_and_kirk() {
+        for (strike, rho) in [(0.0, 0.5), (0.0, -0.3), (5.0, 0.7)] {
+            let mc = pricing(MultiAssetPayoff::Spread, strike, rho);
+            let estimate = mc.price().unwrap();
+            let analytic = estimate.closed_form.unwrap();
+            assert!((estimate.price - analytic).abs() < 4.0 * estimate.std_error + 0.02, "K={strike} rho={rho}: {} vs {analytic}", estimate.price);
+        }
+    }
+
+    #[test]
+    fn test_rainbow_ordering() {
+        let basket = pricing(MultiAssetPayoff::Basket, 100.0, 0.5);
+        let best = MultiAssetPricing { payoff: MultiAssetPayoff::BestOf, ..basket.clone() };
+        let worst = MultiAssetPricing { payoff: MultiAssetPayoff::WorstOf, ..basket.clone() };
+        assert_eq!(basket.payoff(&[120.0, 90.0]), 5.0);
+        assert_eq!(best.payoff(&[120.0, 90.0]), 20.0);
+        assert_eq!(worst.payoff(&[120.0, 90.0]), 0.0);
+        let (best, basket, worst) = (best.price().unwrap().price, basket.price().unwrap().price, worst.price().unwrap().price);
+        assert!(best > basket && basket > worst, "{best} {basket} {worst}");
+    }
+
+    #[test]
+    fn test_invalid_correlation() {
+        let mut mc = pricing(MultiAssetPayoff::Basket, 100.0, 0.5);
+        mc.assets.push(Asset { spot: 110.0, vol: 0.2, dividend_yield: 0.0 });
+        mc.correlation = vec![vec![1.0, 0.9, -0.9], vec![0.9, 1.0, 0.9], vec![-0.9, 0.9, 1.0]];
+        let estimate = mc.price().unwrap();
+        let repaired = estimate.repaired_correlation.unwrap();
+        assert!(cholesky(&repaired).is_some());
+
+        mc.correlation[0][1] = 0.2;
+        assert!(mc.price().is_err());
+        assert!(parse_correlation("1, 0.5; 0.5, 1").is_ok());
+        assert!(parse_correlation("1, 0.5; 0.5").is_err());
+        assert_eq!(parse_correlation(&format_correlation(&repaired)).unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,133 @@
+const REFLECTION: f64 = 1.0;
+const EXPANSION: f64 = 2.0;
+const CONTRACTION: f64 = 0.5;
+const SHRINK: f64 = 0.5;
+
+/// Minimises `f` with the Nelder-Mead simplex method, starting from `x0`.
+/// `step` is the size of the initial simplex along each axis.
+/// Returns the best point found together with its objective value.
+pub fn nelder_mead<F: Fn(&[f64]) -> f64>(f: F, x0: &[f64], step: f64, max_iter: usize, tolerance: f64) -> (Vec<f64>, f64) {
+    let n = x0.len();
+    let mut simplex: Vec<(Vec<f64>, f64)> = Vec::with_capacity(n + 1);
+    simplex.push((x0.to_vec(), f(x0)));
+    for i in 0..n {
+        let mut x = x0.to_vec();
+        x[i] += step;
+        let value = f(&x);
+        simplex.push((x, value));
+    }
+
+    for _ in 0..max_iter {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let best = simplex[0].1;
+        let worst = simplex[n].1;
+        if (worst - best).abs() <= tolerance * (best.abs() + tolerance) {
+            break;
+        }
+
+        let centroid: Vec<f64> = (0..n)
+            .map(|j| simplex[..n].iter().map(|(x, _)| x[j]).sum::<f64>() / n as f64)
+            .collect();
+        let towards = |coef: f64| -> Vec<f64> {
+            centroid.iter().zip(&simplex[n].0).map(|(c, w)| c + coef * (c - w)).collect()
+        };
+
+        let reflected = towards(REFLECTION);
+        let reflected_value = f(&reflected);
+        if reflected_value < best {
+            let expanded = towards(EXPANSION);
+            let expanded_value = f(&expanded);
+            simplex[n] = if expanded_value < reflected_value { (expanded, expanded_value) } else { (reflected, reflected_value) };
+        } else if reflected_value < simplex[n - 1].1 {
+            simplex[n] = (reflected, reflected_value);
+        } else {
+            let contracted = towards(-CONTRACTION);
+            let contracted_value = f(&contracted);
+            if contracted_value < worst {
+                simplex[n] = (contracted, contracted_value);
+            } else {
+                let best_point = simplex[0].0.clone();
+                for (x, value) in simplex.iter_mut().skip(1) {
+                    for (xj, bj) in x.iter_mut().zip(&best_point) {
+                        *xj = bj + SHRINK * (*xj - bj);
+                    }
+                    *value = f(x);
+                }
+            }
+        }
+    }
+
+    simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+    simplex.swap_remove(0)
+}
+
+/// Solves the linear least squares problem min |X b - y| through the normal equations.
+/// Returns `None` when the design matrix is rank deficient.
+pub fn least_squares(design: &[Vec<f64>], y: &[f64]) -> Option<Vec<f64>> {
+    let n = design.first()?.len();
+    let mut a = vec![vec![0.0; n + 1]; n]; // augmented matrix [X'X | X'y]
+    for (row, yi) in design.iter().zip(y) {
+        for i in 0..n {
+            for j in 0..n {
+                a[i][j] += row[i] * row[j];
+            }
+            a[i][n] += row[i] * yi;
+        }
+    }
+
+    // Gaussian elimination with partial pivoting
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        let pivot_row = a[col].clone();
+        for row in a.iter_mut().skip(col + 1) {
+            let factor = row[col] / pivot_row[col];
+            for (v, p) in row.iter_mut().zip(&pivot_row).skip(col) {
+                *v -= factor * p;
+            }
+        }
+    }
+    let mut solution = vec![0.0; n];
+    for i in (0..n).rev() {
+        let tail: f64 = (i + 1..n).map(|j| a[i][j] * solution[j]).sum();
+        solution[i] = (a[i][n] - tail) / a[i][i];
+    }
+    Some(solution)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn test_nelder_mead_quadratic() {
+        let (x, value) = nelder_mead(|x| (x[0] - 1.0).powi(2) + (x[1] + 2.0).powi(2), &[0.0, 0.0], 0.5, 1000, 1e-12);
+        assert_abs_diff_eq!(x[0], 1.0, epsilon = 1e-4);
+        assert_abs_diff_eq!(x[1], -2.0, epsilon = 1e-4);
+        assert_abs_diff_eq!(value, 0.0, epsilon = 1e-8);
+    }
+
+    #[test]
+    fn test_nelder_mead_rosenbrock() {
+        let rosenbrock = |x: &[f64]| (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0].powi(2)).powi(2);
+        let (x, _) = nelder_mead(rosenbrock, &[-1.2, 1.0], 0.1, 5000, 1e-14);
+        assert_abs_diff_eq!(x[0], 1.0, epsilon = 1e-3);
+        assert_abs_diff_eq!(x[1], 1.0, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_least_squares() {
+        let design: Vec<Vec<f64>> = (0..10).map(|i| vec![1.0, i as f64, (i * i) as f64]).collect();
+        let y: Vec<f64> = (0..10).map(|i| 2.0 - 0.5 * i as f64 + 0.25 * (i * i) as f64).collect();
+        let b = least_squares(&design, &y).unwrap();
+        assert_abs_diff_eq!(b[0], 2.0, epsilon = 1e-8);
+        assert_abs_diff_eq!(b[1], -0.5, epsilon = 1e-8);
+        assert_abs_diff_eq!(b[2], 0.25, epsilon = 1e-8);
+        assert!(least_squares(&[vec![1.0, 1.0], vec![2.0, 2.0]], &[1.0, 2.0]).is_none());
+    }
+}
@@ -1,3 +1,36 @@
+use chrono::{Days, NaiveDate};
+
+use crate::model::analytic::OptionType;
+use crate::model::asian::{AsianAverage, AsianStrike};
+use crate::model::barrier::{BarrierDirection, BarrierKnock};
+use crate::model::bootstrap::BootstrapScheme;
+use crate::model::calendar::{Calendar, DayCount};
+use crate::model::correlation::CovarianceEstimator;
+use crate::model::costs::SpreadQuote;
+use crate::model::exotics::{DigitalKind, LookbackStrike};
+use crate::model::heston::{HestonParams, HestonScheme};
+use crate::model::innovations::{InnovationDistribution, Innovations};
+use crate::model::jumps::JumpEstimate;
+use crate::model::risk::VarMethod;
+use crate::model::sabr::SabrParams;
+use crate::model::scenario::{ScenarioPricer, ScenarioTarget};
+use crate::model::statistics::ReturnKind;
+use crate::model::sweep::SweepMetric;
+use crate::model::systematic::SystematicStrategy;
+use crate::model::strategy::{LegKind, StrategyTemplate};
+use crate::model::monte_carlo::{ExerciseStyle, PayoffKind, SimulationModel};
+use crate::model::multi_asset::MultiAssetPayoff;
+use crate::model::yield_curve::{CurveInterpolation, InterpolatedQuantity};
+
+/// One strategy leg as entered, with the expiry in calendar days from the valuation date.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegParams {
+    pub kind: LegKind,
+    pub quantity: String,
+    pub strike: String,
+    pub days_to_expire: String,
+}
+
 pub struct MonteCarloParams {
     pub current_asset_price: String,
     pub market_option_price: String,
@@ -7,6 +40,103 @@ pub struct MonteCarloParams {
     pub num_steps: String,
     pub risk_free_rate: String,
     pub implied_vol: String,
+    pub model: SimulationModel,
+    pub heston_kappa: String,
+    pub heston_theta: String,
+    pub heston_xi: String,
+    pub heston_rho: String,
+    pub heston_v0: String,
+    pub heston_scheme: HestonScheme,
+    pub merton_lambda: String,
+    pub merton_mu_jump: String,
+    pub merton_sigma_jump: String,
+    pub kou_lambda: String,
+    pub kou_p_up: String,
+    pub kou_eta_up: String,
+    pub kou_eta_down: String,
+    pub sabr_alpha: String,
+    pub sabr_beta: String,
+    pub sabr_rho: String,
+    pub sabr_nu: String,
+    pub dividend_yield: String,
+    pub exercise: ExerciseStyle,
+    pub curve_interpolation: CurveInterpolation,
+    pub curve_quantity: InterpolatedQuantity,
+    pub valuation_date: NaiveDate,
+    pub day_count: DayCount,
+    pub calendar: Calendar,
+    pub payoff: PayoffKind,
+    pub barrier_direction: BarrierDirection,
+    pub barrier_knock: BarrierKnock,
+    pub barrier_level: String,
+    pub barrier_rebate: String,
+    pub brownian_bridge: bool,
+    pub asian_average: AsianAverage,
+    pub asian_strike: AsianStrike,
+    pub averaging_days: String,
+    pub lookback_strike: LookbackStrike,
+    pub digital_kind: DigitalKind,
+    pub digital_cash: String,
+    pub digital_smoothing: String,
+    pub gap_trigger: String,
+    pub multi_asset_payoff: MultiAssetPayoff,
+    pub asset_spots: String,
+    pub asset_vols: String,
+    pub asset_dividend_yields: String,
+    pub basket_weights: String,
+    pub correlation: String,
+    pub covariance_estimator: CovarianceEstimator,
+    pub ewma_decay: String,
+    pub strategy_template: Option<StrategyTemplate>,
+    pub strategy_width: String,
+    pub strategy_legs: Vec<LegParams>,
+    pub hedge_option_type: OptionType,
+    pub hedge_rebalance_steps: String,
+    pub hedge_cost: String,
+    pub hedge_vol: String,
+    pub backtest_initial_cash: String,
+    pub cost_commission_order: String,
+    pub cost_commission: String,
+    pub cost_contract_size: String,
+    pub cost_notional_rate: String,
+    pub cost_spread_quote: SpreadQuote,
+    pub cost_spread: String,
+    pub cost_slippage_impact: String,
+    pub sweep_option_kind: LegKind,
+    pub sweep_quantity: String,
+    pub sweep_strike_offsets: String,
+    pub sweep_days_to_expiry: String,
+    pub sweep_roll_days: String,
+    pub sweep_metric: SweepMetric,
+    pub in_sample_bars: String,
+    pub out_of_sample_bars: String,
+    pub systematic_strategy: SystematicStrategy,
+    pub systematic_quantity: String,
+    pub systematic_days: String,
+    pub systematic_roll_days: String,
+    pub systematic_delta: String,
+    pub systematic_wing_delta: String,
+    pub profit_take: String,
+    pub stop_loss: String,
+    pub bootstrap_scheme: BootstrapScheme,
+    pub bootstrap_block_length: String,
+    pub bootstrap_target_vol: String,
+    pub innovations: InnovationDistribution,
+    pub innovation_nu: String,
+    pub innovation_skew: String,
+    pub nig_alpha: String,
+    pub nig_beta: String,
+    pub return_kind: ReturnKind,
+    pub autocorrelation_lags: String,
+    pub var_confidences: String,
+    pub var_method: VarMethod,
+    pub var_window: String,
+    pub scenario_spot_shocks: String,
+    pub scenario_vol_shocks: String,
+    pub scenario_decay_days: String,
+    pub scenario_rate_shock: String,
+    pub scenario_pricer: ScenarioPricer,
+    pub scenario_target: ScenarioTarget,
 }
 
 impl Default for MonteCarloParams {
@@ -19,7 +149,196 @@ impl Default for MonteCarloParams {
             num_simulations:        String::from("1000"), 
             num_steps:              String::from("10"), 
             risk_free_rate:         String::from("0.05"), 
-            implied_vol:            String::from("0.25") 
+            implied_vol:            String::from("0.25"),
+            model:                  SimulationModel::Gbm,
+            heston_kappa:           String::from("2.0"),
+            heston_theta:           String::from("0.04"),
+            heston_xi:              String::from("0.3"),
+            heston_rho:             String::from("-0.7"),
+            heston_v0:              String::from("0.04"),
+            heston_scheme:          HestonScheme::QuadraticExponential,
+            merton_lambda:          String::from("1.0"),
+            merton_mu_jump:         String::from("-0.05"),
+            merton_sigma_jump:      String::from("0.1"),
+            kou_lambda:             String::from("1.0"),
+            kou_p_up:               String::from("0.4"),
+            kou_eta_up:             String::from("10.0"),
+            kou_eta_down:           String::from("5.0"),
+            sabr_alpha:             String::from("0.2"),
+            sabr_beta:              String::from("1.0"),
+            sabr_rho:               String::from("-0.3"),
+            sabr_nu:                String::from("0.4"),
+            dividend_yield:         String::from("0.0"),
+            exercise:               ExerciseStyle::European,
+            curve_interpolation:    CurveInterpolation::Linear,
+            curve_quantity:         InterpolatedQuantity::ZeroRate,
+            valuation_date:         NaiveDate::default(), // set by the caller, see OptiRust::new
+            day_count:              DayCount::Act365Fixed,
+            calendar:               Calendar::Nyse,
+            payoff:                 PayoffKind::Vanilla,
+            barrier_direction:      BarrierDirection::Down,
+            barrier_knock:          BarrierKnock::Out,
+            barrier_level:          String::from("90.0"),
+            barrier_rebate:         String::from("0.0"),
+            brownian_bridge:        true,
+            asian_average:          AsianAverage::Arithmetic,
+            asian_strike:           AsianStrike::Fixed,
+            averaging_days:         String::new(),
+            lookback_strike:        LookbackStrike::Fixed,
+            digital_kind:           DigitalKind::CashOrNothing,
+            digital_cash:           String::from("1.0"),
+            digital_smoothing:      String::from("0.0"),
+            gap_trigger:            String::from("110.0"),
+            multi_asset_payoff:     MultiAssetPayoff::Basket,
+            asset_spots:            String::from("100.0, 95.0"),
+            asset_vols:             String::from("0.25, 0.3"),
+            asset_dividend_yields:  String::from("0.0, 0.0"),
+            basket_weights:         String::from("0.5, 0.5"),
+            correlation:            String::from("1.0, 0.5; 0.5, 1.0"),
+            covariance_estimator:   CovarianceEstimator::Sample,
+            ewma_decay:             String::from("0.94"),
+            strategy_template:      None,
+            strategy_width:         String::from("5.0"),
+            strategy_legs:          Vec::new(),
+            hedge_option_type:      OptionType::Call,
+            hedge_rebalance_steps:  String::from("1"),
+            hedge_cost:             String::from("0.0"),
+            hedge_vol:              String::from("0.25"),
+            backtest_initial_cash:  String::from("10000.0"),
+            cost_commission_order:  String::from("0.0"),
+            cost_commission:        String::from("0.0"),
+            cost_contract_size:     String::from("100"),
+            cost_notional_rate:     String::from("0.0"),
+            cost_spread_quote:      SpreadQuote::Price,
+            cost_spread:            String::from("0.0"),
+            cost_slippage_impact:   String::from("0.0"),
+            sweep_option_kind:      LegKind::Call,
+            sweep_quantity:         String::from("-1"),
+            sweep_strike_offsets:   String::from("0.0, 0.025, 0.05"),
+            sweep_days_to_expiry:   String::from("14, 30, 45"),
+            sweep_roll_days:        String::from("0, 5"),
+            sweep_metric:           SweepMetric::Sharpe,
+            in_sample_bars:         String::from("60"),
+            out_of_sample_bars:     String::from("20"),
+            systematic_strategy:    SystematicStrategy::CoveredCall,
+            systematic_quantity:    String::from("100"),
+            systematic_days:        String::from("30"),
+            systematic_roll_days:   String::from("0"),
+            systematic_delta:       String::from("0.3"),
+            systematic_wing_delta:  String::from("0.1"),
+            profit_take:            String::from("0.5"),
+            stop_loss:              String::from("2.0"),
+            bootstrap_scheme:       BootstrapScheme::Iid,
+            bootstrap_block_length: String::from("5"),
+            bootstrap_target_vol:   String::new(),
+            innovations:            InnovationDistribution::Normal,
+            innovation_nu:          String::from("5.0"),
+            innovation_skew:        String::from("0.0"),
+            nig_alpha:              String::from("1.5"),
+            nig_beta:               String::from("0.0"),
+            return_kind:            ReturnKind::Log,
+            autocorrelation_lags:   String::from("10"),
+            var_confidences:        String::from("0.95, 0.99"),
+            var_method:             VarMethod::DeltaNormal,
+            var_window:             String::from("20"),
+            scenario_spot_shocks:   String::from("-0.2, -0.1, -0.05, 0, 0.05, 0.1, 0.2"),
+            scenario_vol_shocks:    String::from("-0.05, -0.02, 0, 0.02, 0.05, 0.1"),
+            scenario_decay_days:    String::from("0, 7, 30"),
+            scenario_rate_shock:    String::from("0.0"),
+            scenario_pricer:        ScenarioPricer::ClosedForm,
+            scenario_target:        ScenarioTarget::Option,
+        }
+    }
+}
+
+impl MonteCarloParams {
+    /// Averaging dates entered as comma separated calendar days from the valuation date, converted with
+    /// the day count. Entries that do not parse are skipped.
+    pub fn averaging_times(&self) -> Vec<f64> {
+        self.averaging_days
+            .split(',')
+            .filter_map(|d| d.trim().parse::<u64>().ok())
+            .filter_map(|d| self.valuation_date.checked_add_days(Days::new(d)))
+            .map(|date| self.day_count.year_fraction(self.valuation_date, date, &self.calendar))
+            .collect()
+    }
+
+    /// Barrier levels to draw on the price chart.
+    pub fn barrier_levels(&self) -> Vec<f64> {
+        match (self.payoff, self.barrier_level.parse::<f64>()) {
+            (PayoffKind::Barrier, Ok(level)) => vec![level],
+            _ => Vec::new(),
         }
     }
+
+    /// Time from the valuation date to expiry under the selected day count, where the days to
+    /// expire are calendar days.
+    pub fn years_to_expire(&self) -> f64 {
+        self.year_fraction_for_days(self.days_to_expire.parse::<u16>().unwrap_or(30) as u64)
+    }
+
+    /// Year fraction under the selected day count from the valuation date to `days` calendar days later.
+    pub fn year_fraction_for_days(&self, days: u64) -> f64 {
+        let end = self.valuation_date.checked_add_days(Days::new(days)).unwrap_or(self.valuation_date);
+        self.day_count.year_fraction(self.valuation_date, end, &self.calendar)
+    }
+
+    pub fn add_strategy_leg(&mut self) {
+        self.strategy_legs.push(LegParams {
+            kind: LegKind::Call,
+            quantity: String::from("1"),
+            strike: self.strike_price.clone(),
+            days_to_expire: self.days_to_expire.clone(),
+        });
+    }
+
+    /// Replaces the strategy legs with the template centred on the asset price, expiring with the option.
+    pub fn set_strategy_template(&mut self, template: StrategyTemplate) {
+        let spot = self.current_asset_price.parse::<f64>().unwrap_or(100.0);
+        let width = self.strategy_width.parse::<f64>().unwrap_or(5.0);
+        self.strategy_template = Some(template);
+        self.strategy_legs = template
+            .legs(spot, width, 0.0)
+            .iter()
+            .map(|leg| LegParams {
+                kind: leg.kind,
+                quantity: leg.quantity.to_string(),
+                strike: leg.strike.to_string(),
+                days_to_expire: self.days_to_expire.clone(),
+            })
+            .collect();
+    }
+
+    pub fn set_heston_params(&mut self, params: &HestonParams) {
+        self.heston_kappa = params.kappa.to_string();
+        self.heston_theta = params.theta.to_string();
+        self.heston_xi = params.xi.to_string();
+        self.heston_rho = params.rho.to_string();
+        self.heston_v0 = params.v0.to_string();
+    }
+
+    pub fn set_sabr_params(&mut self, params: &SabrParams) {
+        self.sabr_alpha = params.alpha.to_string();
+        self.sabr_beta = params.beta.to_string();
+        self.sabr_rho = params.rho.to_string();
+        self.sabr_nu = params.nu.to_string();
+    }
+
+    pub fn set_jump_estimate(&mut self, estimate: &JumpEstimate) {
+        self.implied_vol = estimate.diffusion_vol.to_string();
+        self.merton_lambda = estimate.merton.lambda.to_string();
+        self.merton_mu_jump = estimate.merton.mu_jump.to_string();
+        self.merton_sigma_jump = estimate.merton.sigma_jump.to_string();
+        self.kou_lambda = estimate.kou.lambda.to_string();
+        self.kou_p_up = estimate.kou.p_up.to_string();
+        self.kou_eta_up = estimate.kou.eta_up.to_string();
+        self.kou_eta_down = estimate.kou.eta_down.to_string();
+    }
+
+    pub fn set_innovations(&mut self, innovations: &Innovations) {
+        self.innovation_nu = innovations.nu.to_string();
+        self.innovation_skew = innovations.skew.to_string();
+        self.nig_alpha = innovations.nig_alpha.to_string();
+        self.nig_beta = innovations.nig_beta.to_string();
+    }
 }
\ No newline at end of file
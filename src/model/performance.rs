@@ -0,0 +1,226 @@
+use super::backtest::{BacktestResult, Contract, Trade, TradeReason};
+
+const MIN_RATIO_VOLATILITY: f64 = 1e-8; // annualised, below which Sharpe and Sortino only measure rounding noise
+
+/// Summary statistics of a backtest. Returns and volatility are annualised with the observation
+/// frequency of the bars. Sharpe and Sortino are undefined when the volatility they divide by is
+/// negligible, and the other ratios that would divide by zero are reported as zero.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PerformanceMetrics {
+    pub total_return: f64,
+    pub annualised_return: f64,
+    pub volatility: f64,
+    pub sharpe_ratio: Option<f64>,
+    pub sortino_ratio: Option<f64>,
+    pub calmar_ratio: f64,
+    pub max_drawdown: f64,             // as a fraction of the running peak
+    pub max_drawdown_duration: usize,  // longest number of bars spent below a previous peak
+    pub win_rate: f64,                 // fraction of closed trades with a profit
+    pub profit_factor: f64,            // gross profit over gross loss of closed trades
+    pub exposure: f64,                 // fraction of bars with money in positions
+    pub turnover: f64,                 // traded notional over average equity, per year
+}
+
+/// A backtest together with its metrics and drawdown curve, as shown on the results screen.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BacktestReport {
+    pub result: BacktestResult,
+    pub metrics: PerformanceMetrics,
+    pub drawdowns: Vec<f64>,
+}
+
+impl BacktestReport {
+    pub fn new(result: BacktestResult, periods_per_year: f64, risk_free_rate: f64) -> BacktestReport {
+        let equity: Vec<f64> = result.equity_curve.iter().map(|point| point.equity).collect();
+        BacktestReport {
+            metrics: PerformanceMetrics::from_backtest(&result, periods_per_year, risk_free_rate),
+            drawdowns: drawdowns(&equity),
+            result,
+        }
+    }
+}
+
+/// Fall from the running peak at every point of the equity curve, as a fraction of the peak.
+pub fn drawdowns(equity: &[f64]) -> Vec<f64> {
+    let mut peak = f64::NEG_INFINITY;
+    equity
+        .iter()
+        .map(|&value| {
+            peak = peak.max(value);
+            if peak > 0.0 { (peak - value) / peak } else { 0.0 }
+        })
+        .collect()
+}
+
+fn longest_drawdown(drawdowns: &[f64]) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    for &drawdown in drawdowns {
+        current = if drawdown > 0.0 { current + 1 } else { 0 };
+        longest = longest.max(current);
+    }
+    longest
+}
+
+/// Realised P&L of every trade that reduces an open position, against the average cost of that
+/// position. Costs are folded into the prices, so buys cost more and sales raise less than the mid.
+/// Settlements at expiry count as closing trades.
+pub fn closed_trade_pnls(trades: &[Trade]) -> Vec<f64> {
+    let mut book: Vec<(Contract, f64, f64)> = Vec::new(); // contract, quantity, average price
+    let mut pnls = Vec::new();
+    for trade in trades.iter().filter(|trade| trade.quantity != 0.0) {
+        let price = trade.price + trade.costs.total() / trade.quantity;
+        let index = match book.iter().position(|(contract, _, _)| *contract == trade.contract) {
+            Some(index) => index,
+            None => {
+                book.push((trade.contract, 0.0, 0.0));
+                book.len() - 1
+            }
+        };
+        let (_, held, average) = &mut book[index];
+        if *held * trade.quantity < 0.0 {
+            let closed = trade.quantity.abs().min(held.abs()) * held.signum();
+            pnls.push(closed * (price - *average));
+            *held -= closed;
+            let opened = trade.quantity + closed;
+            if opened != 0.0 {
+                *held = opened;
+                *average = price;
+            }
+        } else {
+            let quantity = *held + trade.quantity;
+            if quantity != 0.0 {
+                *average = (*held * *average + trade.quantity * price) / quantity;
+            }
+            *held = quantity;
+        }
+    }
+    pnls
+}
+
+impl PerformanceMetrics {
+    pub fn from_backtest(result: &BacktestResult, periods_per_year: f64, risk_free_rate: f64) -> PerformanceMetrics {
+        let curve = &result.equity_curve;
+        if curve.len() < 2 || periods_per_year <= 0.0 {
+            return PerformanceMetrics::default();
+        }
+        let equity: Vec<f64> = curve.iter().map(|point| point.equity).collect();
+        let returns: Vec<f64> = equity.windows(2).map(|w| w[1] / w[0] - 1.0).collect();
+        let n = returns.len() as f64;
+        let years = n / periods_per_year;
+
+        let total_return = equity[equity.len() - 1] / equity[0] - 1.0;
+        let annualised_return = (1.0 + total_return).max(0.0).powf(1.0 / years) - 1.0;
+        let excess: Vec<f64> = returns.iter().map(|r| r - risk_free_rate / periods_per_year).collect();
+        let mean_excess = excess.iter().sum::<f64>() / n * periods_per_year;
+        let mean = returns.iter().sum::<f64>() / n;
+        let volatility = (returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0) * periods_per_year).sqrt();
+        let downside = (excess.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / n * periods_per_year).sqrt();
+        let ratio = |numerator: f64, denominator: f64| if denominator > 0.0 { numerator / denominator } else { 0.0 };
+        let risk_adjusted = |volatility: f64| (volatility > MIN_RATIO_VOLATILITY).then(|| mean_excess / volatility);
+
+        let drawdowns = drawdowns(&equity);
+        let max_drawdown = drawdowns.iter().fold(0.0, |a: f64, &b| a.max(b));
+
+        let pnls = closed_trade_pnls(&result.trades);
+        let gross_profit: f64 = pnls.iter().filter(|&&p| p > 0.0).sum();
+        let gross_loss: f64 = -pnls.iter().filter(|&&p| p < 0.0).sum::<f64>();
+        let traded: f64 = result.trades.iter().filter(|t| t.reason == TradeReason::Order).map(|t| (t.quantity * t.price).abs()).sum();
+        let average_equity = equity.iter().sum::<f64>() / equity.len() as f64;
+
+        PerformanceMetrics {
+            total_return,
+            annualised_return,
+            volatility,
+            sharpe_ratio: risk_adjusted(volatility),
+            sortino_ratio: risk_adjusted(downside),
+            calmar_ratio: ratio(annualised_return, max_drawdown),
+            max_drawdown,
+            max_drawdown_duration: longest_drawdown(&drawdowns),
+            win_rate: ratio(pnls.iter().filter(|&&p| p > 0.0).count() as f64, pnls.len() as f64),
+            profit_factor: ratio(gross_profit, gross_loss),
+            exposure: curve.iter().filter(|point| point.positions_value != 0.0).count() as f64 / curve.len() as f64,
+            turnover: ratio(traded, average_equity) / years,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::backtest::EquityPoint;
+    use crate::model::costs::FillCosts;
+    use approx::assert_abs_diff_eq;
+    use chrono::NaiveDate;
+
+    fn curve(equity: &[f64]) -> Vec<EquityPoint> {
+        let start = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+        equity
+            .iter()
+            .enumerate()
+            .map(|(i, &e)| EquityPoint { date: start + chrono::Days::new(i as u64), cash: e, positions_value: 0.0, equity: e })
+            .collect()
+    }
+
+    fn trade(contract: Contract, quantity: f64, price: f64) -> Trade {
+        Trade { date: NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(), contract, quantity, price, costs: FillCosts::default(), reason: TradeReason::Order }
+    }
+
+    #[test]
+    fn test_drawdowns() {
+        let equity = [100.0, 110.0, 99.0, 104.5, 110.0, 121.0, 108.9];
+        let drawdowns = drawdowns(&equity);
+        assert_abs_diff_eq!(drawdowns[2], 0.1, epsilon = 1e-12);
+        assert_eq!(drawdowns[4], 0.0);
+        assert_abs_diff_eq!(drawdowns[6], 0.1, epsilon = 1e-12);
+        assert_eq!(longest_drawdown(&drawdowns), 2);
+    }
+
+    #[test]
+    fn test_closed_trades() {
+        let stock = Contract::underlying();
+        let trades = [
+            trade(stock, 2.0, 100.0),
+            trade(stock, 2.0, 110.0),  // average cost 105
+            trade(stock, -1.0, 115.0), // +10
+            trade(stock, -5.0, 100.0), // -15 on the remaining 3, then short 2 at 100
+            trade(stock, 2.0, 90.0),   // +20
+        ];
+        let pnls = closed_trade_pnls(&trades);
+        assert_eq!(pnls.len(), 3);
+        assert_abs_diff_eq!(pnls[0], 10.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(pnls[1], -15.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(pnls[2], 20.0, epsilon = 1e-12);
+
+        let result = BacktestResult { trades: trades.to_vec(), equity_curve: curve(&[100.0, 101.0, 102.0]) };
+        let metrics = PerformanceMetrics::from_backtest(&result, 252.0, 0.0);
+        assert_abs_diff_eq!(metrics.win_rate, 2.0 / 3.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(metrics.profit_factor, 2.0, epsilon = 1e-12);
+
+        // Costs of both the opening and the closing fill come off the trade's P&L
+        let costs = FillCosts { commission: 1.0, ..FillCosts::default() };
+        let costly = [Trade { costs, ..trade(stock, 2.0, 100.0) }, Trade { costs, ..trade(stock, -2.0, 103.0) }];
+        assert_abs_diff_eq!(closed_trade_pnls(&costly)[0], 4.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_metrics() {
+        // Steady growth of 0.1% a bar for a year: no volatility and no drawdown
+        let steady: Vec<f64> = (0..=252).map(|i| 1000.0 * 1.001f64.powi(i)).collect();
+        let metrics = PerformanceMetrics::from_backtest(&BacktestResult { trades: Vec::new(), equity_curve: curve(&steady) }, 252.0, 0.0);
+        assert_abs_diff_eq!(metrics.total_return, 1.001f64.powi(252) - 1.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(metrics.annualised_return, metrics.total_return, epsilon = 1e-12);
+        assert_abs_diff_eq!(metrics.volatility, 0.0, epsilon = 1e-12);
+        assert_eq!((metrics.sharpe_ratio, metrics.sortino_ratio), (None, None));
+        assert_eq!((metrics.max_drawdown, metrics.max_drawdown_duration, metrics.exposure), (0.0, 0, 0.0));
+
+        let choppy = [100.0, 102.0, 99.0, 101.0, 97.0, 103.0];
+        let metrics = PerformanceMetrics::from_backtest(&BacktestResult { trades: Vec::new(), equity_curve: curve(&choppy) }, 252.0, 0.02);
+        assert!(metrics.volatility > 0.0);
+        let (sharpe, sortino) = (metrics.sharpe_ratio.unwrap(), metrics.sortino_ratio.unwrap());
+        assert!(sortino > sharpe && sharpe > 0.0);
+        assert_abs_diff_eq!(metrics.max_drawdown, 5.0 / 102.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(metrics.calmar_ratio, metrics.annualised_return / metrics.max_drawdown, epsilon = 1e-12);
+    }
+}
@@ -0,0 +1,264 @@
+use rayon::prelude::*;
+use statrs::distribution::{ChiSquared, Continuous, ContinuousCDF, Normal};
+use std::error::Error;
+use std::fmt;
+
+use super::chart::PriceChart;
+use super::hedging::percentile;
+use super::monte_carlo::MonteCarloPricing;
+use super::strategy::Strategy;
+
+pub const HORIZONS: [usize; 2] = [1, 10]; // in bars of the imported history
+const TAIL_POINTS: usize = 1000; // quantiles averaged for the Cornish-Fisher expected shortfall
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VarMethod {
+    #[default]
+    DeltaNormal,
+    DeltaGamma,
+    MonteCarlo,
+}
+
+impl VarMethod {
+    pub const ALL: [VarMethod; 3] = [VarMethod::DeltaNormal, VarMethod::DeltaGamma, VarMethod::MonteCarlo];
+}
+
+impl fmt::Display for VarMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VarMethod::DeltaNormal => write!(f, "Delta-normal"),
+            VarMethod::DeltaGamma => write!(f, "Delta-gamma (Cornish-Fisher)"),
+            VarMethod::MonteCarlo => write!(f, "Full revaluation MC"),
+        }
+    }
+}
+
+/// Loss of the book not exceeded with the given confidence over the horizon, and the mean loss beyond it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskEstimate {
+    pub method: VarMethod,
+    pub horizon: usize,
+    pub confidence: f64,
+    pub value_at_risk: f64,
+    pub expected_shortfall: f64,
+}
+
+/// The strategy legs held as a book on the imported underlying, valued with the pricer's market data.
+/// The local methods ignore time decay, which full revaluation includes.
+#[derive(Clone, Default)]
+pub struct PortfolioRisk {
+    pub strategy: Strategy,
+    pub pricing: MonteCarloPricing,
+    pub periods_per_year: f64,
+}
+
+impl PortfolioRisk {
+    pub fn estimate(&self, method: VarMethod, horizon: usize, confidence: f64, spot: f64) -> Result<RiskEstimate, Box<dyn Error>> {
+        let years = horizon as f64 / self.periods_per_year;
+        let greeks = self.strategy.greeks(&self.pricing, spot, 0.0);
+        let z = Normal::standard().inverse_cdf(confidence);
+        let (value_at_risk, expected_shortfall) = match method {
+            VarMethod::DeltaNormal => {
+                let sd = (greeks.delta * spot * self.pricing.implied_vol).abs() * years.sqrt();
+                (z * sd, sd * Normal::standard().pdf(z) / (1.0 - confidence))
+            }
+            VarMethod::DeltaGamma => {
+                // P&L of a Z + b Z^2 for a standard normal return Z, through its first four moments
+                let a = greeks.delta * spot * self.pricing.implied_vol * years.sqrt();
+                let b = 0.5 * greeks.gamma * (spot * self.pricing.implied_vol).powi(2) * years;
+                let variance = a * a + 2.0 * b * b;
+                if variance <= 0.0 {
+                    (-b, -b)
+                } else {
+                    let skewness = (6.0 * a * a * b + 8.0 * b.powi(3)) / variance.powf(1.5);
+                    let excess_kurtosis = (3.0 * a.powi(4) + 60.0 * a * a * b * b + 60.0 * b.powi(4)) / variance.powi(2) - 3.0;
+                    let loss_quantile = |p: f64| -(b + variance.sqrt() * cornish_fisher(Normal::standard().inverse_cdf(p), skewness, excess_kurtosis));
+                    let tail = 1.0 - confidence;
+                    let shortfall = (0..TAIL_POINTS).map(|i| loss_quantile((i as f64 + 0.5) / TAIL_POINTS as f64 * tail)).sum::<f64>() / TAIL_POINTS as f64;
+                    (loss_quantile(tail), shortfall)
+                }
+            }
+            VarMethod::MonteCarlo => {
+                self.pricing.check_model()?;
+                let horizon_pricing = MonteCarloPricing { years_to_expire: years, num_steps: horizon.max(1) as u16, ..self.pricing.clone() };
+                let mut pnls: Vec<f64> = (0..self.pricing.num_simulations)
+                    .into_par_iter()
+                    .map(|_| {
+                        let path = horizon_pricing.simulate_path(spot, &mut rand::rng());
+                        self.strategy.greeks(&self.pricing, path[path.len() - 1], years).price - greeks.price
+                    })
+                    .collect();
+                pnls.sort_by(f64::total_cmp);
+                let value_at_risk = -percentile(&pnls, 1.0 - confidence);
+                let tail: Vec<f64> = pnls.iter().copied().filter(|p| -p >= value_at_risk).collect();
+                (value_at_risk, -tail.iter().sum::<f64>() / tail.len().max(1) as f64)
+            }
+        };
+        Ok(RiskEstimate { method, horizon, confidence, value_at_risk, expected_shortfall })
+    }
+
+    /// Every method at both horizons and each confidence level.
+    pub fn estimates(&self, confidences: &[f64], spot: f64) -> Result<Vec<RiskEstimate>, Box<dyn Error>> {
+        let mut estimates = Vec::new();
+        for method in VarMethod::ALL {
+            for horizon in HORIZONS {
+                for confidence in confidences {
+                    estimates.push(self.estimate(method, horizon, *confidence, spot)?);
+                }
+            }
+        }
+        Ok(estimates)
+    }
+
+    /// Forecasts the one bar VaR on every bar of the chart with the volatility of the preceding `window`
+    /// returns, and compares it with the P&L the book, kept at constant maturity, made on the next bar.
+    pub fn backtest(&self, chart: &PriceChart, method: VarMethod, confidence: f64, window: usize) -> Result<VarBacktest, Box<dyn Error>> {
+        let returns = chart.log_returns();
+        let window = window.max(2);
+        if returns.len() <= window {
+            return Err(format!("At least {} returns are needed to backtest with a {} bar window", window + 1, window).into());
+        }
+        let years = 1.0 / self.periods_per_year;
+        let mut exceeded = Vec::with_capacity(returns.len() - window);
+        for t in window..returns.len() {
+            let past = &returns[t - window..t];
+            let mean = past.iter().sum::<f64>() / window as f64;
+            let vol = (past.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (window - 1) as f64 * self.periods_per_year).sqrt();
+            let day = PortfolioRisk { pricing: MonteCarloPricing { implied_vol: vol, ..self.pricing.clone() }, ..self.clone() };
+            let spot = chart.data[t].price;
+            let estimate = day.estimate(method, 1, confidence, spot)?;
+            let pnl = day.strategy.greeks(&day.pricing, chart.data[t + 1].price, years).price - day.strategy.greeks(&day.pricing, spot, 0.0).price;
+            exceeded.push(-pnl > estimate.value_at_risk);
+        }
+        Ok(VarBacktest::from_exceedances(exceeded, 1.0 - confidence))
+    }
+}
+
+/// Cornish-Fisher expansion of the standard normal quantile `z` for the given skewness and excess kurtosis.
+fn cornish_fisher(z: f64, skewness: f64, excess_kurtosis: f64) -> f64 {
+    z + (z * z - 1.0) * skewness / 6.0 + (z.powi(3) - 3.0 * z) * excess_kurtosis / 24.0 - (2.0 * z.powi(3) - 5.0 * z) * skewness.powi(2) / 36.0
+}
+
+/// Exceedances of a VaR forecast with Kupiec's test of their frequency and Christoffersen's test of
+/// their independence from one bar to the next.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct VarBacktest {
+    pub exceeded: Vec<bool>,
+    pub expected_rate: f64,
+    pub exceedances: usize,
+    pub kupiec: f64,
+    pub kupiec_p_value: f64,
+    pub christoffersen: f64,
+    pub christoffersen_p_value: f64,
+}
+
+impl VarBacktest {
+    pub fn from_exceedances(exceeded: Vec<bool>, expected_rate: f64) -> VarBacktest {
+        let observations = exceeded.len();
+        let exceedances = exceeded.iter().filter(|e| **e).count();
+        let observed_rate = exceedances as f64 / observations.max(1) as f64;
+        let misses = observations - exceedances;
+        let kupiec = 2.0 * (log_likelihood(observed_rate, exceedances, misses) - log_likelihood(expected_rate, exceedances, misses));
+
+        // Transitions between consecutive bars, indexed by the previous and the current state
+        let mut transitions = [[0usize; 2]; 2];
+        for pair in exceeded.windows(2) {
+            transitions[pair[0] as usize][pair[1] as usize] += 1;
+        }
+        let rate = |from: [usize; 2]| from[1] as f64 / (from[0] + from[1]).max(1) as f64;
+        let pooled = [transitions[0][0] + transitions[1][0], transitions[0][1] + transitions[1][1]];
+        let christoffersen = 2.0
+            * (log_likelihood(rate(transitions[0]), transitions[0][1], transitions[0][0])
+                + log_likelihood(rate(transitions[1]), transitions[1][1], transitions[1][0])
+                - log_likelihood(rate(pooled), pooled[1], pooled[0]));
+
+        let chi_squared = ChiSquared::new(1.0).unwrap();
+        VarBacktest {
+            exceeded,
+            expected_rate,
+            exceedances,
+            kupiec,
+            kupiec_p_value: 1.0 - chi_squared.cdf(kupiec),
+            christoffersen,
+            christoffersen_p_value: 1.0 - chi_squared.cdf(christoffersen),
+        }
+    }
+}
+
+/// Bernoulli log likelihood of `hits` successes and `misses` failures, taking 0 ln 0 as 0.
+fn log_likelihood(p: f64, hits: usize, misses: usize) -> f64 {
+    let term = |count: usize, probability: f64| if count == 0 { 0.0 } else { count as f64 * probability.ln() };
+    term(hits, p) + term(misses, 1.0 - p)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::calendar::Calendar;
+    use crate::model::strategy::{Leg, LegKind, StrategyTemplate};
+    use approx::assert_abs_diff_eq;
+    use chrono::NaiveDate;
+
+    fn risk(legs: Vec<Leg>) -> PortfolioRisk {
+        PortfolioRisk {
+            strategy: Strategy { legs },
+            pricing: MonteCarloPricing { implied_vol: 0.2, num_simulations: 20000, ..MonteCarloPricing::default() },
+            periods_per_year: 252.0,
+        }
+    }
+
+    #[test]
+    fn test_linear_book() {
+        let shares = risk(vec![Leg { kind: LegKind::Underlying, quantity: 10.0, ..Leg::default() }]);
+        let sd = 10.0 * 100.0 * 0.2 * (10.0f64 / 252.0).sqrt();
+        let normal = shares.estimate(VarMethod::DeltaNormal, 10, 0.99, 100.0).unwrap();
+        assert_abs_diff_eq!(normal.value_at_risk, 2.326348 * sd, epsilon = 1e-3);
+        assert_abs_diff_eq!(normal.expected_shortfall, 2.665214 * sd, epsilon = 1e-3);
+        let delta_gamma = shares.estimate(VarMethod::DeltaGamma, 10, 0.99, 100.0).unwrap();
+        assert_abs_diff_eq!(delta_gamma.value_at_risk, normal.value_at_risk, epsilon = 1e-9);
+        assert_abs_diff_eq!(delta_gamma.expected_shortfall, normal.expected_shortfall, epsilon = 0.5);
+        let simulated = shares.estimate(VarMethod::MonteCarlo, 10, 0.99, 100.0).unwrap();
+        assert_abs_diff_eq!(simulated.value_at_risk / normal.value_at_risk, 1.0, epsilon = 0.06);
+    }
+
+    #[test]
+    fn test_short_gamma_has_fatter_loss_tail() {
+        let short_straddle: Vec<Leg> = StrategyTemplate::Straddle.legs(100.0, 5.0, 0.1).into_iter().map(|leg| Leg { quantity: -leg.quantity, ..leg }).collect();
+        let book = risk(short_straddle);
+        let estimates = book.estimates(&[0.99], 100.0).unwrap();
+        assert_eq!(estimates.len(), 6);
+        let normal = estimates[1];
+        let delta_gamma = estimates[3];
+        let simulated = estimates[5];
+        assert_eq!((normal.horizon, delta_gamma.method), (10, VarMethod::DeltaGamma));
+        assert!(delta_gamma.value_at_risk > normal.value_at_risk);
+        assert!(simulated.value_at_risk > normal.value_at_risk);
+        assert!(delta_gamma.expected_shortfall > delta_gamma.value_at_risk);
+    }
+
+    #[test]
+    fn test_coverage_tests() {
+        // One exceedance in a hundred bars is exactly the expected rate
+        let mut exceeded = vec![false; 100];
+        exceeded[50] = true;
+        let calibrated = VarBacktest::from_exceedances(exceeded, 0.01);
+        assert_abs_diff_eq!(calibrated.kupiec, 0.0, epsilon = 1e-12);
+        assert!(calibrated.christoffersen_p_value > 0.5);
+
+        // Ten clustered exceedances fail both tests
+        let clustered = VarBacktest::from_exceedances((0..100).map(|i| i >= 90).collect(), 0.01);
+        let expected_kupiec = 2.0 * (10.0 * 0.1f64.ln() + 90.0 * 0.9f64.ln() - 10.0 * 0.01f64.ln() - 90.0 * 0.99f64.ln());
+        assert_abs_diff_eq!(clustered.kupiec, expected_kupiec, epsilon = 1e-9);
+        let expected_independence = 2.0 * (89.0 * (89.0f64 / 90.0).ln() + (1.0f64 / 90.0).ln() + 9.0 * 1.0f64.ln()
+            - (89.0 * (89.0f64 / 99.0).ln() + 10.0 * (10.0f64 / 99.0).ln()));
+        assert_abs_diff_eq!(clustered.christoffersen, expected_independence, epsilon = 1e-9);
+        assert!(clustered.kupiec_p_value < 1e-4 && clustered.christoffersen_p_value < 1e-4);
+
+        let prices: Vec<f64> = (0..80).map(|i| 100.0 * (0.02 * (i as f64 * 0.7).sin()).exp()).collect();
+        let chart = PriceChart::from_prices_and_date(prices, NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(), &Calendar::WeekendsOnly);
+        let book = risk(StrategyTemplate::Collar.legs(100.0, 5.0, 0.25));
+        let backtest = book.backtest(&chart, VarMethod::DeltaGamma, 0.95, 20).unwrap();
+        assert_eq!(backtest.exceeded.len(), 59);
+        assert!(book.backtest(&chart, VarMethod::DeltaNormal, 0.95, 79).is_err());
+    }
+}
@@ -0,0 +1,169 @@
+use rand::Rng;
+
+use super::monte_carlo::{standard_normal, MonteCarloPricing};
+use super::optimize::nelder_mead;
+
+const ATM_TOLERANCE: f64 = 1e-7;
+const MAX_ITERATIONS: usize = 2000;
+const SIMPLEX_STEP: f64 = 0.3;
+const OBJECTIVE_TOLERANCE: f64 = 1e-14;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SabrParams {
+    pub alpha: f64, // initial volatility
+    pub beta: f64,  // CEV exponent, usually fixed rather than calibrated
+    pub rho: f64,   // correlation between forward and volatility shocks
+    pub nu: f64,    // volatility of volatility
+}
+
+impl Default for SabrParams {
+    fn default() -> Self {
+        SabrParams { alpha: 0.2, beta: 1.0, rho: -0.3, nu: 0.4 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SabrCalibration {
+    pub years_to_expire: f64,
+    pub params: SabrParams,
+    pub rmse: f64, // in implied vol
+}
+
+impl SabrParams {
+    // z / x(z), which tends to 1 at the money
+    fn z_over_x(&self, z: f64) -> f64 {
+        if z.abs() < ATM_TOLERANCE {
+            return 1.0;
+        }
+        let x = (((1.0 - 2.0 * self.rho * z + z * z).sqrt() + z - self.rho) / (1.0 - self.rho)).ln();
+        z / x
+    }
+
+    /// Hagan's lognormal (Black) implied volatility approximation.
+    pub fn lognormal_vol(&self, forward: f64, strike: f64, years: f64) -> f64 {
+        let one_minus_beta = 1.0 - self.beta;
+        let log_moneyness = (forward / strike).ln();
+        let fk_pow = (forward * strike).powf(0.5 * one_minus_beta);
+        let z = self.nu / self.alpha * fk_pow * log_moneyness;
+
+        let denominator = fk_pow
+            * (1.0 + one_minus_beta.powi(2) / 24.0 * log_moneyness.powi(2) + one_minus_beta.powi(4) / 1920.0 * log_moneyness.powi(4));
+        let correction = 1.0
+            + (one_minus_beta.powi(2) / 24.0 * self.alpha.powi(2) / fk_pow.powi(2)
+                + 0.25 * self.rho * self.beta * self.nu * self.alpha / fk_pow
+                + (2.0 - 3.0 * self.rho.powi(2)) / 24.0 * self.nu.powi(2))
+                * years;
+        self.alpha / denominator * self.z_over_x(z) * correction
+    }
+
+    /// Hagan's normal (Bachelier) implied volatility approximation.
+    pub fn normal_vol(&self, forward: f64, strike: f64, years: f64) -> f64 {
+        let one_minus_beta = 1.0 - self.beta;
+        let f_mid = (forward * strike).sqrt();
+        let z = self.nu / self.alpha * (forward - strike) / f_mid.powf(self.beta);
+
+        let scale = if (forward - strike).abs() < ATM_TOLERANCE {
+            f_mid.powf(self.beta)
+        } else if one_minus_beta.abs() < ATM_TOLERANCE {
+            (forward - strike) / (forward / strike).ln()
+        } else {
+            one_minus_beta * (forward - strike) / (forward.powf(one_minus_beta) - strike.powf(one_minus_beta))
+        };
+        let correction = 1.0
+            + (-self.beta * (2.0 - self.beta) * self.alpha.powi(2) / (24.0 * f_mid.powf(2.0 * one_minus_beta))
+                + 0.25 * self.rho * self.alpha * self.nu * self.beta / f_mid.powf(one_minus_beta)
+                + (2.0 - 3.0 * self.rho.powi(2)) / 24.0 * self.nu.powi(2))
+                * years;
+        self.alpha * scale * self.z_over_x(z) * correction
+    }
+
+    /// Simulates the spot implied by a SABR forward maturing at the end of the path. The volatility is
+    /// stepped exactly in logs and the forward with an Euler step absorbed at zero. `carries` holds the
+    /// drift of every step, used to convert between spot and forward.
+    pub fn simulate_path<R: Rng + ?Sized>(&self, spot: f64, carries: &[f64], dt: f64, rng: &mut R) -> Vec<f64> {
+        let mut path = Vec::with_capacity(carries.len() + 1);
+        path.push(spot);
+
+        let mut remaining_carry: f64 = carries.iter().sum::<f64>() * dt;
+        let mut forward = spot * remaining_carry.exp();
+        let mut vol = self.alpha;
+        for carry in carries {
+            let z1 = standard_normal(rng);
+            let z2 = self.rho * z1 + (1.0 - self.rho.powi(2)).sqrt() * standard_normal(rng);
+            if forward > 0.0 {
+                forward = (forward + vol * forward.powf(self.beta) * dt.sqrt() * z1).max(0.0);
+            }
+            vol *= (self.nu * dt.sqrt() * z2 - 0.5 * self.nu.powi(2) * dt).exp();
+            remaining_carry -= carry * dt;
+            path.push(forward * (-remaining_carry).exp());
+        }
+        path
+    }
+}
+
+impl MonteCarloPricing {
+    /// SABR lognormal vol at the option's strike, usable as the `implied_vol` input.
+    pub fn sabr_implied_vol(&self) -> f64 {
+        let forward = self.dividend_adjusted_spot() / self.discount_factor(self.years_to_expire);
+        self.sabr.lognormal_vol(forward, self.strike_price, self.years_to_expire)
+    }
+}
+
+/// Fits alpha, rho and nu of one expiry slice to lognormal implied vols, keeping beta fixed.
+pub fn calibrate_sabr(forward: f64, years: f64, strikes: &[f64], vols: &[f64], beta: f64) -> SabrCalibration {
+    let params_from = |x: &[f64]| SabrParams { alpha: x[0].exp(), beta, rho: x[1].tanh(), nu: x[2].exp() };
+    let objective = |x: &[f64]| {
+        let params = params_from(x);
+        strikes
+            .iter()
+            .zip(vols)
+            .map(|(k, v)| (params.lognormal_vol(forward, *k, years) - v).powi(2))
+            .sum::<f64>() / strikes.len() as f64
+    };
+
+    // Start alpha from the at-the-money vol, scaled to the CEV backbone
+    let atm_vol = strikes
+        .iter()
+        .zip(vols)
+        .min_by(|a, b| (a.0 - forward).abs().total_cmp(&(b.0 - forward).abs()))
+        .map_or(0.2, |(_, v)| *v);
+    let initial = [(atm_vol * forward.powf(1.0 - beta)).ln(), 0.0, 0.5f64.ln()];
+
+    let (best, mse) = nelder_mead(objective, &initial, SIMPLEX_STEP, MAX_ITERATIONS, OBJECTIVE_TOLERANCE);
+    SabrCalibration { years_to_expire: years, params: params_from(&best), rmse: mse.sqrt() }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn test_degenerate_cases() {
+        // beta = 1 without vol of vol is Black-Scholes, beta = 0 without vol of vol is Bachelier
+        let lognormal = SabrParams { alpha: 0.25, beta: 1.0, rho: 0.0, nu: 0.0 };
+        assert_abs_diff_eq!(lognormal.lognormal_vol(100.0, 120.0, 1.0), 0.25, epsilon = 1e-12);
+        let normal = SabrParams { alpha: 20.0, beta: 0.0, rho: 0.0, nu: 0.0 };
+        assert_abs_diff_eq!(normal.normal_vol(100.0, 80.0, 1.0), 20.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(normal.normal_vol(100.0, 100.0, 1.0), 20.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_negative_rho_produces_skew() {
+        let params = SabrParams { alpha: 0.2, beta: 1.0, rho: -0.5, nu: 0.5 };
+        assert!(params.lognormal_vol(100.0, 80.0, 1.0) > params.lognormal_vol(100.0, 120.0, 1.0));
+    }
+
+    #[test]
+    fn test_calibration_recovers_parameters() {
+        let actual = SabrParams { alpha: 0.3, beta: 0.5, rho: -0.4, nu: 0.6 };
+        let strikes = [70.0, 80.0, 90.0, 100.0, 110.0, 120.0, 130.0];
+        let vols: Vec<f64> = strikes.iter().map(|k| actual.lognormal_vol(100.0, *k, 0.5)).collect();
+        let result = calibrate_sabr(100.0, 0.5, &strikes, &vols, 0.5);
+        assert!(result.rmse < 1e-5);
+        assert_abs_diff_eq!(result.params.alpha, actual.alpha, epsilon = 1e-3);
+        assert_abs_diff_eq!(result.params.rho, actual.rho, epsilon = 1e-2);
+        assert_abs_diff_eq!(result.params.nu, actual.nu, epsilon = 1e-2);
+    }
+}
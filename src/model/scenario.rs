@@ -0,0 +1,336 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+
+use super::asian::AsianOption;
+use super::monte_carlo::{ExerciseStyle, MonteCarloPricing, PayoffKind};
+use super::multi_asset::parse_list;
+use super::params::MonteCarloParams;
+use super::strategy::{Leg, LegKind, Strategy};
+use super::sweep::parse_days;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScenarioPricer {
+    #[default]
+    ClosedForm,
+    MonteCarlo,
+}
+
+impl ScenarioPricer {
+    pub const ALL: [ScenarioPricer; 2] = [ScenarioPricer::ClosedForm, ScenarioPricer::MonteCarlo];
+}
+
+impl fmt::Display for ScenarioPricer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioPricer::ClosedForm => write!(f, "Closed form"),
+            ScenarioPricer::MonteCarlo => write!(f, "Monte Carlo"),
+        }
+    }
+}
+
+/// What the scenarios revalue: the configured option, or the strategy legs as a book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScenarioTarget {
+    #[default]
+    Option,
+    Strategy,
+}
+
+impl ScenarioTarget {
+    pub const ALL: [ScenarioTarget; 2] = [ScenarioTarget::Option, ScenarioTarget::Strategy];
+}
+
+impl fmt::Display for ScenarioTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioTarget::Option => write!(f, "Configured option"),
+            ScenarioTarget::Strategy => write!(f, "Strategy legs"),
+        }
+    }
+}
+
+/// Shocks applied to the option: relative spot moves, absolute vol moves, days elapsed and a parallel
+/// shift of the rates applied to every scenario.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ScenarioSettings {
+    pub spot_shocks: Vec<f64>,
+    pub vol_shocks: Vec<f64>,
+    pub decay_days: Vec<u64>,
+    pub decay_years: Vec<f64>,
+    pub rate_shock: f64,
+    pub pricer: ScenarioPricer,
+    pub target: ScenarioTarget,
+}
+
+impl ScenarioSettings {
+    pub fn from_params(params: &MonteCarloParams) -> Result<ScenarioSettings, Box<dyn Error>> {
+        let decay_days = parse_days(&params.scenario_decay_days)?;
+        let settings = ScenarioSettings {
+            spot_shocks: parse_list(&params.scenario_spot_shocks)?,
+            vol_shocks: parse_list(&params.scenario_vol_shocks)?,
+            decay_years: decay_days.iter().map(|d| params.year_fraction_for_days(*d)).collect(),
+            decay_days,
+            rate_shock: params
+                .scenario_rate_shock
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("Invalid rate shock {}", params.scenario_rate_shock))?,
+            pricer: params.scenario_pricer,
+            target: params.scenario_target,
+        };
+        if settings.spot_shocks.is_empty() || settings.vol_shocks.is_empty() || settings.decay_days.is_empty() {
+            return Err("Every scenario axis needs at least one shock".into());
+        }
+        Ok(settings)
+    }
+}
+
+/// Values over the scenarios, indexed by decay, spot shock and vol shock, with the spot and vol they
+/// were shocked from.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ScenarioGrid {
+    pub settings: ScenarioSettings,
+    pub base_spot: f64,
+    pub base_vol: f64,
+    pub base_value: f64,
+    pub values: Vec<Vec<Vec<f64>>>,
+}
+
+impl ScenarioGrid {
+    /// P&L against today's value for one decay slice, with spot shocks as rows and vol shocks as columns.
+    pub fn pnl_slice(&self, decay: usize) -> Vec<Vec<f64>> {
+        self.values[decay].iter().map(|row| row.iter().map(|v| v - self.base_value).collect()).collect()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let settings = &self.settings;
+        let mut csv = String::from("decay_days,spot_shock,vol_shock,rate_shock,spot,vol,value,pnl\n");
+        for (d, days) in settings.decay_days.iter().enumerate() {
+            for (s, spot_shock) in settings.spot_shocks.iter().enumerate() {
+                for (v, vol_shock) in settings.vol_shocks.iter().enumerate() {
+                    let value = self.values[d][s][v];
+                    csv.push_str(&format!(
+                        "{},{},{},{},{},{},{},{}\n",
+                        days, spot_shock, vol_shock, settings.rate_shock,
+                        self.base_spot * (1.0 + spot_shock), self.base_vol + vol_shock, value, value - self.base_value
+                    ));
+                }
+            }
+        }
+        csv
+    }
+
+    pub fn save_csv(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        fs::write(path, self.to_csv())?;
+        Ok(())
+    }
+}
+
+impl MonteCarloPricing {
+    /// The pricer after `decay_years` have passed, with the vol and rates moved. Dividends and
+    /// averaging dates are brought closer, those already past being dropped. The vol shock only
+    /// reaches the models driven by the implied vol.
+    pub fn shocked(&self, vol_shock: f64, decay_years: f64, rate_shock: f64) -> MonteCarloPricing {
+        let averaging_times = self.asian.averaging_times.iter().filter(|t| **t > decay_years).map(|t| t - decay_years).collect();
+        MonteCarloPricing {
+            implied_vol: self.implied_vol + vol_shock,
+            risk_free_rate: self.risk_free_rate + rate_shock,
+            yield_curve: self.yield_curve.as_ref().map(|curve| curve.shifted(rate_shock)),
+            years_to_expire: self.years_to_expire - decay_years,
+            dividends: self.dividends.after(decay_years),
+            asian: AsianOption { averaging_times, ..self.asian.clone() },
+            ..self.clone()
+        }
+    }
+
+    /// Value at `spot` with the chosen pricer. Monte Carlo runs on seeded paths, so all scenarios see
+    /// the same random numbers and the grid stays smooth.
+    pub fn scenario_value(&self, pricer: ScenarioPricer, spot: f64) -> Result<f64, Box<dyn Error>> {
+        match pricer {
+            ScenarioPricer::ClosedForm => self.closed_form_price(spot).ok_or_else(|| "No closed form exists for the selected model and payoff".into()),
+            ScenarioPricer::MonteCarlo => self.seeded_price(spot),
+        }
+    }
+}
+
+/// Value of one strategy leg at `spot` once `decay_years` have passed on the shocked pricer. The closed
+/// form is the Black-Scholes valuation of the strategy panel; Monte Carlo prices calls on the seeded
+/// paths and puts by parity. Expired legs are worth their intrinsic value.
+fn leg_value(leg: &Leg, shocked: &MonteCarloPricing, pricer: ScenarioPricer, spot: f64, decay_years: f64) -> Result<f64, Box<dyn Error>> {
+    let years = leg.years_to_expire - decay_years;
+    let value = match (leg.kind, pricer) {
+        (LegKind::Underlying, _) => spot,
+        (LegKind::Call, _) if years <= 0.0 => (spot - leg.strike).max(0.0),
+        (LegKind::Put, _) if years <= 0.0 => (leg.strike - spot).max(0.0),
+        (_, ScenarioPricer::ClosedForm) => Strategy::leg_greeks(&Leg { quantity: 1.0, ..*leg }, shocked, spot, decay_years).price,
+        (kind, ScenarioPricer::MonteCarlo) => {
+            let vanilla = MonteCarloPricing {
+                current_asset_price: spot,
+                strike_price: leg.strike,
+                years_to_expire: years,
+                payoff: PayoffKind::Vanilla,
+                exercise: ExerciseStyle::European,
+                ..shocked.clone()
+            };
+            let call = vanilla.scenario_value(pricer, spot)?;
+            match kind {
+                LegKind::Put => call - vanilla.dividend_adjusted_spot() + leg.strike * vanilla.discount_factor(years),
+                _ => call,
+            }
+        }
+    };
+    Ok(leg.quantity * value)
+}
+
+/// Value of the scenario target at `spot`, `decay_years` from now on the shocked pricer.
+fn target_value(
+    strategy: &Strategy,
+    shocked: &MonteCarloPricing,
+    settings: &ScenarioSettings,
+    spot: f64,
+    decay_years: f64,
+) -> Result<f64, Box<dyn Error>> {
+    match settings.target {
+        ScenarioTarget::Option => shocked.scenario_value(settings.pricer, spot),
+        ScenarioTarget::Strategy => strategy.legs.iter().map(|leg| leg_value(leg, shocked, settings.pricer, spot, decay_years)).sum(),
+    }
+}
+
+/// Revalues the target on every scenario. `strategy` is only used when the target is the strategy book,
+/// whose legs may expire within the decay.
+pub fn scenario_grid(pricing: &MonteCarloPricing, strategy: &Strategy, settings: &ScenarioSettings) -> Result<ScenarioGrid, Box<dyn Error>> {
+    match settings.target {
+        ScenarioTarget::Option if settings.decay_years.iter().any(|years| *years >= pricing.years_to_expire) => {
+            return Err("Time decay must stay short of the option's expiry".into());
+        }
+        ScenarioTarget::Strategy if strategy.legs.is_empty() => return Err("No strategy legs were specified".into()),
+        _ => {}
+    }
+    if settings.vol_shocks.iter().any(|shock| pricing.implied_vol + shock <= 0.0) {
+        return Err("Volatility shocks must leave a positive volatility".into());
+    }
+    let spot = pricing.current_asset_price;
+    let base_value = target_value(strategy, pricing, settings, spot, 0.0)?;
+    let mut values = Vec::with_capacity(settings.decay_years.len());
+    for decay_years in &settings.decay_years {
+        let mut slice = Vec::with_capacity(settings.spot_shocks.len());
+        for spot_shock in &settings.spot_shocks {
+            let row = settings
+                .vol_shocks
+                .iter()
+                .map(|vol_shock| {
+                    let shocked = pricing.shocked(*vol_shock, *decay_years, settings.rate_shock);
+                    target_value(strategy, &shocked, settings, spot * (1.0 + spot_shock), *decay_years)
+                })
+                .collect::<Result<Vec<f64>, _>>()?;
+            slice.push(row);
+        }
+        values.push(slice);
+    }
+    Ok(ScenarioGrid { settings: settings.clone(), base_spot: spot, base_vol: pricing.implied_vol, base_value, values })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::dividends::{Dividend, DividendKind, DividendSchedule};
+    use crate::model::utils::black_scholes_call;
+    use approx::assert_abs_diff_eq;
+
+    fn pricing() -> MonteCarloPricing {
+        MonteCarloPricing {
+            current_asset_price: 100.0,
+            strike_price: 100.0,
+            num_simulations: 4000,
+            num_steps: 20,
+            risk_free_rate: 0.03,
+            implied_vol: 0.2,
+            years_to_expire: 0.5,
+            ..MonteCarloPricing::default()
+        }
+    }
+
+    fn settings(pricer: ScenarioPricer) -> ScenarioSettings {
+        ScenarioSettings {
+            spot_shocks: vec![-0.1, 0.0, 0.1],
+            vol_shocks: vec![-0.05, 0.0, 0.05],
+            decay_days: vec![0, 73],
+            decay_years: vec![0.0, 0.2],
+            rate_shock: 0.01,
+            pricer,
+            target: ScenarioTarget::Option,
+        }
+    }
+
+    #[test]
+    fn test_closed_form_grid() {
+        let grid = scenario_grid(&pricing(), &Strategy::default(), &settings(ScenarioPricer::ClosedForm)).unwrap();
+        assert_abs_diff_eq!(grid.base_value, black_scholes_call(100.0, 100.0, 0.03, 0.5, 0.2), epsilon = 1e-9);
+        assert_abs_diff_eq!(grid.values[1][2][0], black_scholes_call(110.0, 100.0, 0.04, 0.3, 0.15), epsilon = 1e-9);
+        let pnl = grid.pnl_slice(0);
+        assert!(pnl[0][1] < 0.0 && pnl[2][1] > 0.0 && pnl[1][2] > pnl[1][0]);
+
+        let csv = grid.to_csv();
+        assert_eq!(csv.lines().count(), 1 + 2 * 3 * 3);
+        assert!(csv.lines().nth(1).unwrap().starts_with("0,-0.1,-0.05,0.01,90,"));
+    }
+
+    #[test]
+    fn test_monte_carlo_grid_uses_common_random_numbers() {
+        let grid = scenario_grid(&pricing(), &Strategy::default(), &settings(ScenarioPricer::MonteCarlo)).unwrap();
+        // The same draws make the value monotone in spot and vol, and repeatable
+        for slice in &grid.values {
+            for s in 1..3 {
+                assert!(slice[s][1] > slice[s - 1][1]);
+                assert!(slice[1][s] > slice[1][s - 1]);
+            }
+        }
+        let again = pricing().shocked(0.05, 0.2, 0.01).scenario_value(ScenarioPricer::MonteCarlo, 110.0).unwrap();
+        assert_abs_diff_eq!(again, grid.values[1][2][2], epsilon = 1e-9);
+        assert_abs_diff_eq!(grid.values[1][2][2], black_scholes_call(110.0, 100.0, 0.04, 0.3, 0.25), epsilon = 0.5);
+    }
+
+    #[test]
+    fn test_invalid_shocks() {
+        let mut long_decay = settings(ScenarioPricer::ClosedForm);
+        long_decay.decay_years = vec![0.5];
+        assert!(scenario_grid(&pricing(), &Strategy::default(), &long_decay).is_err());
+        let mut vol_crash = settings(ScenarioPricer::ClosedForm);
+        vol_crash.vol_shocks = vec![-0.2];
+        assert!(scenario_grid(&pricing(), &Strategy::default(), &vol_crash).is_err());
+        let params = MonteCarloParams { scenario_rate_shock: String::from("1%"), ..MonteCarloParams::default() };
+        assert!(ScenarioSettings::from_params(&params).is_err());
+    }
+
+    #[test]
+    fn test_strategy_book_and_decayed_schedules() {
+        // A call spread whose short leg expires within the decay is worth its intrinsic value then
+        let legs = vec![
+            Leg { kind: LegKind::Call, quantity: 1.0, strike: 100.0, years_to_expire: 0.5 },
+            Leg { kind: LegKind::Call, quantity: -1.0, strike: 110.0, years_to_expire: 0.1 },
+        ];
+        let book = Strategy { legs };
+        let settings = ScenarioSettings { target: ScenarioTarget::Strategy, ..settings(ScenarioPricer::ClosedForm) };
+        let grid = scenario_grid(&pricing(), &book, &settings).unwrap();
+        assert_abs_diff_eq!(grid.base_value, book.greeks(&pricing(), 100.0, 0.0).price, epsilon = 1e-12);
+        let expected = black_scholes_call(110.0, 100.0, 0.04, 0.3, 0.15);
+        assert_abs_diff_eq!(grid.values[1][2][0], expected, epsilon = 1e-9);
+        let monte_carlo = ScenarioSettings { pricer: ScenarioPricer::MonteCarlo, ..settings };
+        let simulated = scenario_grid(&pricing(), &book, &monte_carlo).unwrap();
+        assert_abs_diff_eq!(simulated.values[1][2][0], expected, epsilon = 0.5);
+        assert_eq!((grid.base_spot, grid.base_vol), (100.0, 0.2));
+
+        let dividends = DividendSchedule::new(vec![
+            Dividend { years_to_ex_date: 0.1, amount: 1.0, kind: DividendKind::Cash },
+            Dividend { years_to_ex_date: 0.3, amount: 1.0, kind: DividendKind::Cash },
+        ]);
+        let asian = AsianOption { averaging_times: vec![0.1, 0.25, 0.5], ..AsianOption::default() };
+        let decayed = MonteCarloPricing { dividends, asian, ..pricing() }.shocked(0.0, 0.2, 0.0);
+        assert_eq!(decayed.dividends.dividends.len(), 1);
+        assert_abs_diff_eq!(decayed.dividends.dividends[0].years_to_ex_date, 0.1, epsilon = 1e-12);
+        assert_eq!(decayed.asian.averaging_times.len(), 2);
+        assert_abs_diff_eq!(decayed.asian.averaging_times[0], 0.05, epsilon = 1e-12);
+    }
+}
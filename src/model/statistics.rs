@@ -0,0 +1,193 @@
+use statrs::distribution::{ChiSquared, ContinuousCDF, Normal};
+use std::fmt;
+
+use super::chart::PriceChart;
+
+const MIN_BINS: usize = 5;
+const MAX_BINS: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReturnKind {
+    #[default]
+    Log,
+    Simple,
+}
+
+impl ReturnKind {
+    pub const ALL: [ReturnKind; 2] = [ReturnKind::Log, ReturnKind::Simple];
+}
+
+impl fmt::Display for ReturnKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReturnKind::Log => write!(f, "Log returns"),
+            ReturnKind::Simple => write!(f, "Simple returns"),
+        }
+    }
+}
+
+/// Counts of the returns in equal width bins, next to the normal distribution with the same mean and variance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    pub lower: f64,
+    pub bin_width: f64,
+    pub counts: Vec<usize>,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl Histogram {
+    /// Square root rule for the number of bins, kept within a readable range.
+    pub fn new(values: &[f64], mean: f64, std_dev: f64) -> Histogram {
+        let bins = ((values.len() as f64).sqrt().round() as usize).clamp(MIN_BINS, MAX_BINS);
+        let lower = values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
+        let upper = values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+        let bin_width = if upper > lower { (upper - lower) / bins as f64 } else { 1.0 };
+        let mut counts = vec![0; bins];
+        for value in values {
+            counts[(((value - lower) / bin_width) as usize).min(bins - 1)] += 1;
+        }
+        Histogram { lower, bin_width, counts, mean, std_dev }
+    }
+
+    /// Expected count of each bin under the normal overlay.
+    pub fn normal_counts(&self) -> Vec<f64> {
+        let total = self.counts.iter().sum::<usize>() as f64;
+        let normal = Normal::new(self.mean, self.std_dev.max(f64::MIN_POSITIVE)).unwrap();
+        (0..self.counts.len())
+            .map(|i| {
+                let left = self.lower + i as f64 * self.bin_width;
+                total * (normal.cdf(left + self.bin_width) - normal.cdf(left))
+            })
+            .collect()
+    }
+}
+
+/// Summary of the return distribution of a chart, with tests for normality and serial correlation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReturnStatistics {
+    pub kind: ReturnKind,
+    pub count: usize,
+    pub mean: f64,
+    pub annualised_vol: f64,
+    pub skewness: f64,
+    pub excess_kurtosis: f64,
+    pub jarque_bera: f64,
+    pub jarque_bera_p_value: f64,
+    pub autocorrelations: Vec<f64>, // at lags 1, 2, ...
+    pub ljung_box: f64,
+    pub ljung_box_p_value: f64,
+    pub histogram: Histogram,
+}
+
+impl PriceChart {
+    pub fn returns(&self, kind: ReturnKind) -> Vec<f64> {
+        match kind {
+            ReturnKind::Log => self.log_returns(),
+            ReturnKind::Simple => self.simple_returns(),
+        }
+    }
+
+    /// Statistics of the chosen returns, with autocorrelations up to `lags`. None when there are too few
+    /// returns or they do not vary.
+    pub fn return_statistics(&self, kind: ReturnKind, periods_per_year: f64, lags: usize) -> Option<ReturnStatistics> {
+        let returns = self.returns(kind);
+        let count = returns.len();
+        if count < 3 {
+            return None;
+        }
+        let n = count as f64;
+        let mean = returns.iter().sum::<f64>() / n;
+        let moment = |power: i32| returns.iter().map(|r| (r - mean).powi(power)).sum::<f64>() / n;
+        let variance = moment(2);
+        if variance <= 0.0 {
+            return None;
+        }
+        let skewness = moment(3) / variance.powf(1.5);
+        let excess_kurtosis = moment(4) / variance.powi(2) - 3.0;
+        let jarque_bera = n / 6.0 * (skewness.powi(2) + excess_kurtosis.powi(2) / 4.0);
+
+        let lags = lags.clamp(1, count - 1);
+        let autocorrelations: Vec<f64> = (1..=lags)
+            .map(|lag| returns.windows(lag + 1).map(|w| (w[0] - mean) * (w[lag] - mean)).sum::<f64>() / (n * variance))
+            .collect();
+        let ljung_box = n * (n + 2.0) * autocorrelations.iter().enumerate().map(|(i, rho)| rho * rho / (n - (i + 1) as f64)).sum::<f64>();
+
+        let sample_std = (variance * n / (n - 1.0)).sqrt();
+        Some(ReturnStatistics {
+            kind,
+            count,
+            mean,
+            annualised_vol: sample_std * periods_per_year.sqrt(),
+            skewness,
+            excess_kurtosis,
+            jarque_bera,
+            jarque_bera_p_value: chi_squared_p_value(jarque_bera, 2),
+            autocorrelations,
+            ljung_box,
+            ljung_box_p_value: chi_squared_p_value(ljung_box, lags),
+            histogram: Histogram::new(&returns, mean, sample_std),
+        })
+    }
+}
+
+fn chi_squared_p_value(statistic: f64, degrees_of_freedom: usize) -> f64 {
+    1.0 - ChiSquared::new(degrees_of_freedom as f64).unwrap().cdf(statistic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::calendar::Calendar;
+    use approx::assert_abs_diff_eq;
+    use chrono::NaiveDate;
+
+    fn chart(returns: &[f64]) -> PriceChart {
+        let prices: Vec<f64> = std::iter::once(100.0).chain(returns.iter().scan(100.0, |p, r| { *p *= r.exp(); Some(*p) })).collect();
+        PriceChart::from_prices_and_date(prices, NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(), &Calendar::WeekendsOnly)
+    }
+
+    #[test]
+    fn test_alternating_returns() {
+        let returns: Vec<f64> = (0..100).map(|i| if i % 2 == 0 { 0.01 } else { -0.01 }).collect();
+        let stats = chart(&returns).return_statistics(ReturnKind::Log, 252.0, 3).unwrap();
+        assert_eq!(stats.count, 100);
+        assert_abs_diff_eq!(stats.mean, 0.0, epsilon = 1e-15);
+        assert_abs_diff_eq!(stats.skewness, 0.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(stats.excess_kurtosis, -2.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(stats.jarque_bera, 100.0 / 6.0, epsilon = 1e-6);
+        assert_abs_diff_eq!(stats.annualised_vol, 0.01 * (100.0f64 / 99.0 * 252.0).sqrt(), epsilon = 1e-12);
+        assert_abs_diff_eq!(stats.autocorrelations[0], -0.99, epsilon = 1e-9);
+        assert_abs_diff_eq!(stats.autocorrelations[1], 0.98, epsilon = 1e-9);
+        let expected: f64 = 100.0 * 102.0 * (0.99f64.powi(2) / 99.0 + 0.98f64.powi(2) / 98.0 + 0.97f64.powi(2) / 97.0);
+        assert_abs_diff_eq!(stats.ljung_box, expected, epsilon = 1e-6);
+        assert!(stats.ljung_box_p_value < 1e-6);
+    }
+
+    #[test]
+    fn test_normality_is_not_rejected_for_normal_returns() {
+        // Normal quantiles at evenly spaced probabilities, shuffled by a fixed stride
+        let normal = Normal::new(0.0, 0.01).unwrap();
+        let n = 499;
+        let returns: Vec<f64> = (0..n).map(|i| normal.inverse_cdf(((i * 211) % n) as f64 / n as f64 + 0.5 / n as f64)).collect();
+        let stats = chart(&returns).return_statistics(ReturnKind::Log, 252.0, 5).unwrap();
+        assert!(stats.jarque_bera_p_value > 0.9, "{}", stats.jarque_bera_p_value);
+        assert_abs_diff_eq!(stats.skewness, 0.0, epsilon = 1e-9);
+
+        let histogram = &stats.histogram;
+        assert_eq!(histogram.counts.len(), 22);
+        assert_eq!(histogram.counts.iter().sum::<usize>(), n);
+        let expected = histogram.normal_counts();
+        let worst = histogram.counts.iter().zip(&expected).map(|(c, e)| (*c as f64 - e).abs()).fold(0.0, f64::max);
+        assert!(worst < 4.0, "{}", worst);
+    }
+
+    #[test]
+    fn test_simple_returns() {
+        let chart = chart(&[0.1, -0.2]);
+        let simple = chart.returns(ReturnKind::Simple);
+        assert_abs_diff_eq!(simple[0], 0.1f64.exp() - 1.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(simple[1], (-0.2f64).exp() - 1.0, epsilon = 1e-12);
+        assert!(chart.return_statistics(ReturnKind::Simple, 252.0, 1).is_none());
+    }
+}
@@ -0,0 +1,217 @@
+use std::fmt;
+
+use super::analytic::{black_scholes_greeks, Greeks, OptionType};
+use super::monte_carlo::MonteCarloPricing;
+use super::params::MonteCarloParams;
+
+const DIAGRAM_POINTS: usize = 121;
+const DIAGRAM_MARGIN: f64 = 0.2; // spot range beyond the outermost strikes, as a fraction
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LegKind {
+    #[default]
+    Call,
+    Put,
+    Underlying,
+}
+
+impl LegKind {
+    pub const ALL: [LegKind; 3] = [LegKind::Call, LegKind::Put, LegKind::Underlying];
+}
+
+impl fmt::Display for LegKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LegKind::Call => write!(f, "Call"),
+            LegKind::Put => write!(f, "Put"),
+            LegKind::Underlying => write!(f, "Underlying"),
+        }
+    }
+}
+
+/// A signed position in one instrument, negative quantities being short. The strike and expiry are
+/// ignored for the underlying.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Leg {
+    pub kind: LegKind,
+    pub quantity: f64,
+    pub strike: f64,
+    pub years_to_expire: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrategyTemplate {
+    LongCall,
+    BullCallSpread,
+    Straddle,
+    Strangle,
+    Butterfly,
+    IronCondor,
+    Collar,
+}
+
+impl StrategyTemplate {
+    pub const ALL: [StrategyTemplate; 7] = [
+        StrategyTemplate::LongCall,
+        StrategyTemplate::BullCallSpread,
+        StrategyTemplate::Straddle,
+        StrategyTemplate::Strangle,
+        StrategyTemplate::Butterfly,
+        StrategyTemplate::IronCondor,
+        StrategyTemplate::Collar,
+    ];
+
+    /// Legs of the strategy centred on `strike`, with `width` between adjacent strikes.
+    pub fn legs(&self, strike: f64, width: f64, years: f64) -> Vec<Leg> {
+        let leg = |kind, quantity, strike| Leg { kind, quantity, strike, years_to_expire: years };
+        match self {
+            StrategyTemplate::LongCall => vec![leg(LegKind::Call, 1.0, strike)],
+            StrategyTemplate::BullCallSpread => vec![leg(LegKind::Call, 1.0, strike), leg(LegKind::Call, -1.0, strike + width)],
+            StrategyTemplate::Straddle => vec![leg(LegKind::Call, 1.0, strike), leg(LegKind::Put, 1.0, strike)],
+            StrategyTemplate::Strangle => vec![leg(LegKind::Put, 1.0, strike - width), leg(LegKind::Call, 1.0, strike + width)],
+            StrategyTemplate::Butterfly => vec![
+                leg(LegKind::Call, 1.0, strike - width),
+                leg(LegKind::Call, -2.0, strike),
+                leg(LegKind::Call, 1.0, strike + width),
+            ],
+            StrategyTemplate::IronCondor => vec![
+                leg(LegKind::Put, 1.0, strike - 2.0 * width),
+                leg(LegKind::Put, -1.0, strike - width),
+                leg(LegKind::Call, -1.0, strike + width),
+                leg(LegKind::Call, 1.0, strike + 2.0 * width),
+            ],
+            StrategyTemplate::Collar => vec![
+                leg(LegKind::Underlying, 1.0, strike),
+                leg(LegKind::Put, 1.0, strike - width),
+                leg(LegKind::Call, -1.0, strike + width),
+            ],
+        }
+    }
+}
+
+impl fmt::Display for StrategyTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StrategyTemplate::LongCall => write!(f, "Long call"),
+            StrategyTemplate::BullCallSpread => write!(f, "Bull call spread"),
+            StrategyTemplate::Straddle => write!(f, "Straddle"),
+            StrategyTemplate::Strangle => write!(f, "Strangle"),
+            StrategyTemplate::Butterfly => write!(f, "Butterfly"),
+            StrategyTemplate::IronCondor => write!(f, "Iron condor"),
+            StrategyTemplate::Collar => write!(f, "Collar"),
+        }
+    }
+}
+
+/// Profit and loss against the cost of the strategy today, over a range of spots.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PayoffDiagram {
+    pub spot: f64,
+    pub spots: Vec<f64>,
+    pub expiry_pnl: Vec<f64>,  // at the first expiry, later legs keeping their time value
+    pub current_pnl: Vec<f64>, // on the valuation date
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Strategy {
+    pub legs: Vec<Leg>,
+}
+
+impl Strategy {
+    pub fn from_params(params: &MonteCarloParams) -> Strategy {
+        Strategy {
+            legs: params
+                .strategy_legs
+                .iter()
+                .map(|leg| Leg {
+                    kind: leg.kind,
+                    quantity: leg.quantity.parse::<f64>().unwrap_or(0.0),
+                    strike: leg.strike.parse::<f64>().unwrap_or(0.0),
+                    years_to_expire: params.year_fraction_for_days(leg.days_to_expire.parse::<u64>().unwrap_or(0)),
+                })
+                .collect(),
+        }
+    }
+
+    /// Black-Scholes value and Greeks of one leg, `elapsed` years after the valuation date, using the
+    /// pricer's volatility, dividend yield and curve.
+    pub fn leg_greeks(leg: &Leg, pricing: &MonteCarloPricing, spot: f64, elapsed: f64) -> Greeks {
+        let years = (leg.years_to_expire - elapsed).max(0.0);
+        let rate = pricing.zero_rate(years);
+        let carry = rate - pricing.dividend_yield;
+        let greeks = match leg.kind {
+            LegKind::Underlying => Greeks { price: spot, delta: 1.0, ..Greeks::default() },
+            LegKind::Call => black_scholes_greeks(OptionType::Call, spot, leg.strike, rate, carry, pricing.implied_vol, years),
+            LegKind::Put => black_scholes_greeks(OptionType::Put, spot, leg.strike, rate, carry, pricing.implied_vol, years),
+        };
+        greeks * leg.quantity
+    }
+
+    pub fn greeks(&self, pricing: &MonteCarloPricing, spot: f64, elapsed: f64) -> Greeks {
+        self.legs.iter().map(|leg| Strategy::leg_greeks(leg, pricing, spot, elapsed)).fold(Greeks::default(), |a, b| a + b)
+    }
+
+    pub fn first_expiry(&self) -> f64 {
+        self.legs
+            .iter()
+            .filter(|leg| leg.kind != LegKind::Underlying)
+            .map(|leg| leg.years_to_expire)
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    pub fn payoff_diagram(&self, pricing: &MonteCarloPricing, spot: f64) -> PayoffDiagram {
+        let strikes = self.legs.iter().filter(|leg| leg.kind != LegKind::Underlying).map(|leg| leg.strike);
+        let low = strikes.clone().fold(spot, f64::min) * (1.0 - DIAGRAM_MARGIN);
+        let high = strikes.fold(spot, f64::max) * (1.0 + DIAGRAM_MARGIN);
+        let cost = self.greeks(pricing, spot, 0.0).price;
+        let expiry = if self.first_expiry().is_finite() { self.first_expiry() } else { 0.0 };
+
+        let spots: Vec<f64> = (0..DIAGRAM_POINTS).map(|i| low + (high - low) * i as f64 / (DIAGRAM_POINTS - 1) as f64).collect();
+        PayoffDiagram {
+            spot,
+            expiry_pnl: spots.iter().map(|&s| self.greeks(pricing, s, expiry).price - cost).collect(),
+            current_pnl: spots.iter().map(|&s| self.greeks(pricing, s, 0.0).price - cost).collect(),
+            spots,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn pricing() -> MonteCarloPricing {
+        MonteCarloPricing { risk_free_rate: 0.05, implied_vol: 0.2, dividend_yield: 0.01, ..MonteCarloPricing::default() }
+    }
+
+    #[test]
+    fn test_legs_aggregate() {
+        let straddle = Strategy { legs: StrategyTemplate::Straddle.legs(100.0, 5.0, 0.5) };
+        let total = straddle.greeks(&pricing(), 100.0, 0.0);
+        let call = Strategy::leg_greeks(&straddle.legs[0], &pricing(), 100.0, 0.0);
+        let put = Strategy::leg_greeks(&straddle.legs[1], &pricing(), 100.0, 0.0);
+        assert_abs_diff_eq!(total.price, call.price + put.price, epsilon = 1e-12);
+        assert_abs_diff_eq!(total.gamma, 2.0 * call.gamma, epsilon = 1e-12);
+        assert!(total.delta.abs() < 0.2 && total.theta < 0.0);
+
+        // Long the underlying and a put, short a call: delta between the put and call hedges
+        let collar = Strategy { legs: StrategyTemplate::Collar.legs(100.0, 10.0, 0.5) }.greeks(&pricing(), 100.0, 0.0);
+        assert!(collar.delta > 0.0 && collar.delta < 1.0);
+    }
+
+    #[test]
+    fn test_butterfly_payoff_diagram() {
+        let butterfly = Strategy { legs: StrategyTemplate::Butterfly.legs(100.0, 10.0, 0.25) };
+        let diagram = butterfly.payoff_diagram(&pricing(), 100.0);
+        let cost = butterfly.greeks(&pricing(), 100.0, 0.0).price;
+        let body = diagram.spots.iter().position(|s| (s - 100.0).abs() < 1e-9).unwrap();
+        assert!(cost > 0.0);
+        assert_abs_diff_eq!(diagram.expiry_pnl[0], -cost, epsilon = 1e-12);
+        assert_abs_diff_eq!(diagram.expiry_pnl[body], 10.0 - cost, epsilon = 1e-9);
+        // Before expiry the body is worth less than its payoff and the wings more
+        assert!(diagram.current_pnl[body] < diagram.expiry_pnl[body]);
+        assert!(diagram.current_pnl[0] > diagram.expiry_pnl[0]);
+    }
+}
@@ -0,0 +1,322 @@
+use chrono::NaiveDate;
+use rayon::prelude::*;
+use std::error::Error;
+use std::fmt;
+
+use super::backtest::{Backtester, TradingStrategy};
+use super::chart::PriceChart;
+use super::multi_asset::parse_list;
+use super::params::MonteCarloParams;
+use super::performance::PerformanceMetrics;
+
+/// Parameters of a rolling option strategy explored by a sweep.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SweepPoint {
+    pub strike_offset: f64,
+    pub days_to_expiry: u64,
+    pub roll_days: u64,
+}
+
+/// Values to try for each parameter; the sweep runs every combination.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ParameterGrid {
+    pub strike_offsets: Vec<f64>,
+    pub days_to_expiry: Vec<u64>,
+    pub roll_days: Vec<u64>,
+}
+
+pub(crate) fn parse_days(text: &str) -> Result<Vec<u64>, Box<dyn Error>> {
+    parse_list(text)?
+        .into_iter()
+        .map(|days| if days >= 0.0 { Ok(days.round() as u64) } else { Err(format!("Invalid number of days {}", days).into()) })
+        .collect()
+}
+
+impl ParameterGrid {
+    pub fn from_params(params: &MonteCarloParams) -> Result<ParameterGrid, Box<dyn Error>> {
+        let grid = ParameterGrid {
+            strike_offsets: parse_list(&params.sweep_strike_offsets)?,
+            days_to_expiry: parse_days(&params.sweep_days_to_expiry)?,
+            roll_days: parse_days(&params.sweep_roll_days)?,
+        };
+        if grid.strike_offsets.is_empty() || grid.days_to_expiry.is_empty() || grid.roll_days.is_empty() {
+            return Err("Every swept parameter needs at least one value".into());
+        }
+        if grid.roll_days.iter().any(|roll| grid.days_to_expiry.iter().any(|days| roll >= days)) {
+            return Err("Options must be rolled fewer days before expiry than they are opened for".into());
+        }
+        Ok(grid)
+    }
+
+    pub fn points(&self) -> Vec<SweepPoint> {
+        self.strike_offsets
+            .iter()
+            .flat_map(|&strike_offset| {
+                self.days_to_expiry.iter().flat_map(move |&days_to_expiry| {
+                    self.roll_days.iter().map(move |&roll_days| SweepPoint { strike_offset, days_to_expiry, roll_days })
+                })
+            })
+            .collect()
+    }
+}
+
+/// Metric a sweep is ranked by, oriented so that higher is better.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SweepMetric {
+    #[default]
+    Sharpe,
+    Sortino,
+    Calmar,
+    TotalReturn,
+    MaxDrawdown,
+    ProfitFactor,
+}
+
+impl SweepMetric {
+    pub const ALL: [SweepMetric; 6] = [
+        SweepMetric::Sharpe,
+        SweepMetric::Sortino,
+        SweepMetric::Calmar,
+        SweepMetric::TotalReturn,
+        SweepMetric::MaxDrawdown,
+        SweepMetric::ProfitFactor,
+    ];
+
+    /// The metric's value, with drawdowns negated so that shallower ones rank higher. `None` when the
+    /// metric is undefined, as Sharpe and Sortino are without volatility.
+    pub fn value(&self, metrics: &PerformanceMetrics) -> Option<f64> {
+        match self {
+            SweepMetric::Sharpe => metrics.sharpe_ratio,
+            SweepMetric::Sortino => metrics.sortino_ratio,
+            SweepMetric::Calmar => Some(metrics.calmar_ratio),
+            SweepMetric::TotalReturn => Some(metrics.total_return),
+            SweepMetric::MaxDrawdown => Some(-metrics.max_drawdown),
+            SweepMetric::ProfitFactor => Some(metrics.profit_factor),
+        }
+    }
+}
+
+impl fmt::Display for SweepMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SweepMetric::Sharpe => write!(f, "Sharpe"),
+            SweepMetric::Sortino => write!(f, "Sortino"),
+            SweepMetric::Calmar => write!(f, "Calmar"),
+            SweepMetric::TotalReturn => write!(f, "Return"),
+            SweepMetric::MaxDrawdown => write!(f, "-Drawdown"),
+            SweepMetric::ProfitFactor => write!(f, "Profit factor"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepResult {
+    pub point: SweepPoint,
+    pub metrics: PerformanceMetrics,
+}
+
+/// One step of a walk-forward analysis: the parameters that scored best in sample, and how they
+/// fared on the bars that followed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WalkForwardWindow {
+    pub in_sample_start: NaiveDate,
+    pub out_of_sample_start: NaiveDate,
+    pub out_of_sample_end: NaiveDate,
+    pub chosen: SweepPoint,
+    pub in_sample_score: Option<f64>,
+    pub out_of_sample_score: Option<f64>,
+}
+
+/// Number of bars to fit on, then to test on, in each walk-forward step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WalkForwardSplit {
+    pub in_sample_bars: usize,
+    pub out_of_sample_bars: usize,
+}
+
+fn metrics_for(backtester: &Backtester, chart: &PriceChart, start: usize, strategy: &mut dyn TradingStrategy) -> PerformanceMetrics {
+    let result = backtester.run_from(chart, start, strategy);
+    let periods_per_year = PriceChart::new(&chart.data[start..]).periods_per_year(backtester.day_count, &backtester.calendar);
+    PerformanceMetrics::from_backtest(&result, periods_per_year, backtester.pricing.zero_rate(1.0))
+}
+
+/// Backtests the strategy built for every point in parallel.
+pub fn sweep<F>(backtester: &Backtester, chart: &PriceChart, points: &[SweepPoint], make_strategy: &F) -> Vec<SweepResult>
+where
+    F: Fn(&SweepPoint) -> Box<dyn TradingStrategy> + Sync,
+{
+    sweep_from(backtester, chart, 0, points, make_strategy)
+}
+
+fn sweep_from<F>(backtester: &Backtester, chart: &PriceChart, start: usize, points: &[SweepPoint], make_strategy: &F) -> Vec<SweepResult>
+where
+    F: Fn(&SweepPoint) -> Box<dyn TradingStrategy> + Sync,
+{
+    points
+        .par_iter()
+        .map(|point| SweepResult { point: *point, metrics: metrics_for(backtester, chart, start, make_strategy(point).as_mut()) })
+        .collect()
+}
+
+/// Sorts the results best first by the metric, those where it is undefined last.
+pub fn sort_results(results: &mut [SweepResult], metric: SweepMetric) {
+    let rank = |r: &SweepResult| metric.value(&r.metrics).unwrap_or(f64::NEG_INFINITY);
+    results.sort_by(|a, b| rank(b).total_cmp(&rank(a)));
+}
+
+/// Mean of the metric over the roll days for every strike offset (rows) and days to expiry (columns),
+/// skipping the runs where it is undefined.
+pub fn metric_grid(results: &[SweepResult], grid: &ParameterGrid, metric: SweepMetric) -> Vec<Vec<f64>> {
+    grid.strike_offsets
+        .iter()
+        .map(|&offset| {
+            grid.days_to_expiry
+                .iter()
+                .map(|&days| {
+                    let values: Vec<f64> = results
+                        .iter()
+                        .filter(|r| r.point.strike_offset == offset && r.point.days_to_expiry == days)
+                        .filter_map(|r| metric.value(&r.metrics))
+                        .collect();
+                    if values.is_empty() { 0.0 } else { values.iter().sum::<f64>() / values.len() as f64 }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Rolls through the chart picking the best point on each in-sample window by sweeping, then scoring
+/// it on the out-of-sample window that follows. Windows advance by the out-of-sample length, and each
+/// one keeps the bars before it as history so the volatility estimate does not start cold.
+pub fn walk_forward<F>(
+    backtester: &Backtester,
+    chart: &PriceChart,
+    points: &[SweepPoint],
+    make_strategy: &F,
+    metric: SweepMetric,
+    split: WalkForwardSplit,
+) -> Vec<WalkForwardWindow>
+where
+    F: Fn(&SweepPoint) -> Box<dyn TradingStrategy> + Sync,
+{
+    let WalkForwardSplit { in_sample_bars, out_of_sample_bars } = split;
+    let mut windows = Vec::new();
+    if in_sample_bars < 2 || out_of_sample_bars < 2 || points.is_empty() {
+        return windows;
+    }
+    let mut start = 0;
+    while start + in_sample_bars + out_of_sample_bars <= chart.data.len() {
+        let split_at = start + in_sample_bars;
+        let in_sample = PriceChart::new(&chart.data[..split_at]);
+        let out_of_sample = PriceChart::new(&chart.data[..split_at + out_of_sample_bars]);
+
+        let mut results = sweep_from(backtester, &in_sample, start, points, make_strategy);
+        sort_results(&mut results, metric);
+        let best = results[0];
+        let tested = metrics_for(backtester, &out_of_sample, split_at, make_strategy(&best.point).as_mut());
+        windows.push(WalkForwardWindow {
+            in_sample_start: chart.data[start].date,
+            out_of_sample_start: chart.data[split_at].date,
+            out_of_sample_end: chart.data[split_at + out_of_sample_bars - 1].date,
+            chosen: best.point,
+            in_sample_score: metric.value(&best.metrics),
+            out_of_sample_score: metric.value(&tested),
+        });
+        start += out_of_sample_bars;
+    }
+    windows
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::backtest::RollingOption;
+    use crate::model::calendar::{Calendar, DayCount};
+    use crate::model::monte_carlo::MonteCarloPricing;
+    use crate::model::strategy::LegKind;
+    use approx::assert_abs_diff_eq;
+
+    fn chart(bars: usize) -> PriceChart {
+        let prices: Vec<f64> = (0..bars).map(|i| 100.0 * (1.0 + 0.03 * (i as f64 * 0.4).sin()) + 0.1 * i as f64).collect();
+        PriceChart::from_prices_and_date(prices, NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(), &Calendar::WeekendsOnly)
+    }
+
+    fn backtester() -> Backtester {
+        let pricing = MonteCarloPricing { risk_free_rate: 0.03, implied_vol: 0.2, ..MonteCarloPricing::default() };
+        Backtester::new(pricing, 1000.0, DayCount::Bus252, Calendar::WeekendsOnly)
+    }
+
+    fn short_call(point: &SweepPoint) -> Box<dyn TradingStrategy> {
+        Box::new(RollingOption {
+            kind: LegKind::Call,
+            quantity: -1.0,
+            strike_offset: point.strike_offset,
+            days_to_expiry: point.days_to_expiry,
+            roll_days: point.roll_days,
+        })
+    }
+
+    fn grid() -> ParameterGrid {
+        ParameterGrid { strike_offsets: vec![0.0, 0.05], days_to_expiry: vec![14, 28], roll_days: vec![0, 3] }
+    }
+
+    #[test]
+    fn test_grid_points() {
+        let points = grid().points();
+        assert_eq!(points.len(), 8);
+        assert_eq!(points[0], SweepPoint { strike_offset: 0.0, days_to_expiry: 14, roll_days: 0 });
+        assert_eq!(points[7], SweepPoint { strike_offset: 0.05, days_to_expiry: 28, roll_days: 3 });
+        assert_eq!(parse_days("5, 10.0").unwrap(), vec![5, 10]);
+        assert!(parse_days("-1").is_err());
+
+        let params = MonteCarloParams { sweep_days_to_expiry: String::from("14, 28"), sweep_roll_days: String::from("0, 14"), ..MonteCarloParams::default() };
+        assert!(ParameterGrid::from_params(&params).is_err());
+    }
+
+    #[test]
+    fn test_sweep_matches_single_backtests() {
+        let chart = chart(60);
+        let backtester = backtester();
+        let grid = grid();
+        let mut results = sweep(&backtester, &chart, &grid.points(), &short_call);
+        for result in &results {
+            let single = metrics_for(&backtester, &chart, 0, short_call(&result.point).as_mut());
+            assert_abs_diff_eq!(result.metrics.total_return, single.total_return, epsilon = 1e-12);
+        }
+        sort_results(&mut results, SweepMetric::TotalReturn);
+        assert!(results.windows(2).all(|w| w[0].metrics.total_return >= w[1].metrics.total_return));
+
+        let heatmap = metric_grid(&results, &grid, SweepMetric::TotalReturn);
+        let cell: Vec<f64> = results.iter().filter(|r| r.point.strike_offset == 0.05 && r.point.days_to_expiry == 14).map(|r| r.metrics.total_return).collect();
+        assert_abs_diff_eq!(heatmap[1][0], (cell[0] + cell[1]) / 2.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_walk_forward() {
+        let chart = chart(70);
+        let backtester = backtester();
+        let points = grid().points();
+        let split = WalkForwardSplit { in_sample_bars: 30, out_of_sample_bars: 15 };
+        let windows = walk_forward(&backtester, &chart, &points, &short_call, SweepMetric::Sharpe, split);
+        // Windows start at bars 0 and 15; a third would need 75 bars
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[1].out_of_sample_start, chart.data[45].date);
+        assert_eq!(windows[1].out_of_sample_end, chart.data[59].date);
+
+        let in_sample = PriceChart::new(&chart.data[..30]);
+        let best = sweep(&backtester, &in_sample, &points, &short_call)
+            .iter()
+            .filter_map(|r| SweepMetric::Sharpe.value(&r.metrics))
+            .fold(f64::NEG_INFINITY, f64::max);
+        assert_eq!(windows[0].in_sample_score, Some(best));
+
+        // Later windows keep the earlier bars as history for the volatility estimate
+        let history = PriceChart::new(&chart.data[..45]);
+        let best = sweep_from(&backtester, &history, 15, &points, &short_call)
+            .iter()
+            .filter_map(|r| SweepMetric::Sharpe.value(&r.metrics))
+            .fold(f64::NEG_INFINITY, f64::max);
+        assert_eq!(windows[1].in_sample_score, Some(best));
+    }
+}
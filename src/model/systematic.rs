@@ -0,0 +1,280 @@
+use chrono::Days;
+use std::fmt;
+
+use super::analytic::{strike_for_delta, OptionType};
+use super::backtest::{Contract, MarketState, Order, Position, TradingStrategy};
+use super::params::MonteCarloParams;
+use super::strategy::LegKind;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SystematicStrategy {
+    #[default]
+    CoveredCall,    // long the shares, short a call
+    CashSecuredPut, // short a put
+    Wheel,          // short puts until assigned, then covered calls until called away
+    ProtectivePut,  // long the shares and a put
+    ShortStraddle,  // short an at the money call and put
+    IronCondor,     // short a call and a put, long further out of the money wings
+}
+
+impl SystematicStrategy {
+    pub const ALL: [SystematicStrategy; 6] = [
+        SystematicStrategy::CoveredCall,
+        SystematicStrategy::CashSecuredPut,
+        SystematicStrategy::Wheel,
+        SystematicStrategy::ProtectivePut,
+        SystematicStrategy::ShortStraddle,
+        SystematicStrategy::IronCondor,
+    ];
+}
+
+impl fmt::Display for SystematicStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SystematicStrategy::CoveredCall => write!(f, "Covered call"),
+            SystematicStrategy::CashSecuredPut => write!(f, "Cash-secured put"),
+            SystematicStrategy::Wheel => write!(f, "Wheel"),
+            SystematicStrategy::ProtectivePut => write!(f, "Protective put"),
+            SystematicStrategy::ShortStraddle => write!(f, "Short straddle"),
+            SystematicStrategy::IronCondor => write!(f, "Iron condor"),
+        }
+    }
+}
+
+/// Early exits of the option package, as fractions of the premium paid or received when it was opened.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ExitRules {
+    pub profit_take: Option<f64>,
+    pub stop_loss: Option<f64>,
+}
+
+impl ExitRules {
+    /// Whether the package opened at `entry` and now worth `current` should be closed, values being
+    /// signed so that short packages are negative.
+    pub fn triggered(&self, entry: f64, current: f64) -> bool {
+        let premium = entry.abs();
+        let pnl = current - entry;
+        self.profit_take.is_some_and(|take| pnl >= take * premium) || self.stop_loss.is_some_and(|stop| pnl <= -stop * premium)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SystematicSettings {
+    pub quantity: f64, // shares, and options on as many shares
+    pub days_to_expiry: u64,
+    pub roll_days: u64, // options are closed this many calendar days before expiry, 0 holding them to expiry
+    pub target_delta: f64, // absolute delta of the short strikes, and of the protective put
+    pub wing_delta: f64,   // absolute delta of the iron condor's long wings
+    pub rate: f64,
+    pub dividend_yield: f64,
+    pub exits: ExitRules,
+}
+
+impl Default for SystematicSettings {
+    fn default() -> Self {
+        SystematicSettings {
+            quantity: 1.0,
+            days_to_expiry: 30,
+            roll_days: 0,
+            target_delta: 0.3,
+            wing_delta: 0.1,
+            rate: 0.0,
+            dividend_yield: 0.0,
+            exits: ExitRules::default(),
+        }
+    }
+}
+
+impl SystematicSettings {
+    /// Settings entered in the parameters, with the rate and dividend yield used to select strikes.
+    pub fn from_params(params: &MonteCarloParams, rate: f64, dividend_yield: f64) -> SystematicSettings {
+        let optional = |text: &str| text.trim().parse::<f64>().ok();
+        SystematicSettings {
+            quantity: params.systematic_quantity.parse::<f64>().unwrap_or(1.0),
+            days_to_expiry: params.systematic_days.parse::<u64>().unwrap_or(30),
+            roll_days: params.systematic_roll_days.parse::<u64>().unwrap_or(0),
+            target_delta: params.systematic_delta.parse::<f64>().unwrap_or(0.3),
+            wing_delta: params.systematic_wing_delta.parse::<f64>().unwrap_or(0.1),
+            rate,
+            dividend_yield,
+            exits: ExitRules { profit_take: optional(&params.profit_take), stop_loss: optional(&params.stop_loss) },
+        }
+    }
+}
+
+/// Runs one of the systematic strategies on the backtester. Option strikes are picked by Black-Scholes
+/// delta at the bar's volatility over the backtester's day count, and the package is valued at the
+/// backtester's marks for the exit rules.
+pub struct SystematicTrader {
+    pub strategy: SystematicStrategy,
+    pub settings: SystematicSettings,
+    entry_value: f64,
+    opened: Vec<Contract>,
+}
+
+fn option_type(kind: LegKind) -> OptionType {
+    match kind {
+        LegKind::Put => OptionType::Put,
+        _ => OptionType::Call,
+    }
+}
+
+impl SystematicTrader {
+    pub fn new(strategy: SystematicStrategy, settings: SystematicSettings) -> SystematicTrader {
+        SystematicTrader { strategy, settings, entry_value: 0.0, opened: Vec::new() }
+    }
+
+    fn contract_at_delta(&self, kind: LegKind, delta: f64, market: &MarketState) -> Contract {
+        let s = &self.settings;
+        let expiry = market.date.checked_add_days(Days::new(s.days_to_expiry)).unwrap_or(market.date);
+        let years = market.years_until(expiry);
+        let strike = strike_for_delta(option_type(kind), delta, market.spot, s.rate, s.rate - s.dividend_yield, market.vol, years);
+        Contract { kind, strike, expiry }
+    }
+
+    /// Shares bought or sold to settle options that expired in the money since the last bar, as if they
+    /// were physically delivered: the cash settlement plus a trade at the spot nets to the strike.
+    fn assignment(&mut self, market: &MarketState, shares: f64) -> f64 {
+        let mut traded = 0.0;
+        for contract in self.opened.drain(..).filter(|c| c.expiry <= market.date) {
+            match contract.kind {
+                LegKind::Put if market.spot < contract.strike => traded += self.settings.quantity,
+                LegKind::Call if market.spot > contract.strike && shares + traded > 0.0 => traded -= self.settings.quantity.min(shares + traded),
+                _ => {}
+            }
+        }
+        traded
+    }
+
+    /// Option legs of a new package, with signed quantities.
+    fn package(&self, market: &MarketState, shares: f64) -> Vec<Order> {
+        let s = &self.settings;
+        let q = s.quantity;
+        let order = |contract, quantity| Order { contract, quantity };
+        match self.strategy {
+            SystematicStrategy::CoveredCall => vec![order(self.contract_at_delta(LegKind::Call, s.target_delta, market), -q)],
+            SystematicStrategy::CashSecuredPut => vec![order(self.contract_at_delta(LegKind::Put, s.target_delta, market), -q)],
+            SystematicStrategy::Wheel if shares > 0.0 => vec![order(self.contract_at_delta(LegKind::Call, s.target_delta, market), -shares.min(q))],
+            SystematicStrategy::Wheel => vec![order(self.contract_at_delta(LegKind::Put, s.target_delta, market), -q)],
+            SystematicStrategy::ProtectivePut => vec![order(self.contract_at_delta(LegKind::Put, s.target_delta, market), q)],
+            SystematicStrategy::ShortStraddle => {
+                let call = self.contract_at_delta(LegKind::Call, 0.5, market);
+                vec![order(call, -q), order(Contract { kind: LegKind::Put, ..call }, -q)]
+            }
+            SystematicStrategy::IronCondor => vec![
+                order(self.contract_at_delta(LegKind::Put, s.wing_delta, market), q),
+                order(self.contract_at_delta(LegKind::Put, s.target_delta, market), -q),
+                order(self.contract_at_delta(LegKind::Call, s.target_delta, market), -q),
+                order(self.contract_at_delta(LegKind::Call, s.wing_delta, market), q),
+            ],
+        }
+    }
+}
+
+impl TradingStrategy for SystematicTrader {
+    fn on_bar(&mut self, market: &MarketState) -> Vec<Order> {
+        let shares: f64 = market.positions.iter().filter(|p| p.contract.kind == LegKind::Underlying).map(|p| p.quantity).sum();
+        let options: Vec<&Position> = market.positions.iter().filter(|p| p.contract.kind != LegKind::Underlying).collect();
+
+        // Close the package when it is due to roll or hits an exit, and reopen on the next bar
+        if !options.is_empty() {
+            let value: f64 = options.iter().map(|p| p.quantity * market.contract_price(&p.contract)).sum();
+            let rolling = options.iter().any(|p| (p.contract.expiry - market.date).num_days() <= self.settings.roll_days as i64);
+            if !rolling && !self.settings.exits.triggered(self.entry_value, value) {
+                return Vec::new();
+            }
+            self.opened.clear();
+            return options.iter().map(|p| Order { contract: p.contract, quantity: -p.quantity }).collect();
+        }
+
+        let mut orders = Vec::new();
+        let mut shares = shares;
+        let target_shares = match self.strategy {
+            SystematicStrategy::CoveredCall | SystematicStrategy::ProtectivePut => self.settings.quantity,
+            SystematicStrategy::Wheel => shares + self.assignment(market, shares),
+            _ => 0.0,
+        };
+        if target_shares != shares {
+            orders.push(Order { contract: Contract::underlying(), quantity: target_shares - shares });
+            shares = target_shares;
+        }
+        let package = self.package(market, shares);
+        self.entry_value = package.iter().map(|o| o.quantity * market.contract_price(&o.contract)).sum();
+        self.opened = package.iter().map(|o| o.contract).collect();
+        orders.extend(package);
+        orders
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::analytic::black_scholes_greeks;
+    use crate::model::backtest::{Backtester, Trade, TradeReason};
+    use crate::model::calendar::{Calendar, DayCount};
+    use crate::model::chart::PriceChart;
+    use crate::model::monte_carlo::MonteCarloPricing;
+    use approx::assert_abs_diff_eq;
+    use chrono::NaiveDate;
+
+    fn backtester() -> Backtester {
+        let pricing = MonteCarloPricing { risk_free_rate: 0.03, implied_vol: 0.2, ..MonteCarloPricing::default() };
+        Backtester::new(pricing, 10000.0, DayCount::Bus252, Calendar::WeekendsOnly)
+    }
+
+    fn chart(prices: Vec<f64>) -> PriceChart {
+        PriceChart::from_prices_and_date(prices, NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(), &Calendar::WeekendsOnly)
+    }
+
+    fn orders(trades: &[Trade]) -> Vec<&Trade> {
+        trades.iter().filter(|t| t.reason == TradeReason::Order).collect()
+    }
+
+    #[test]
+    fn test_covered_call_strike_selection() {
+        let chart = chart((0..30).map(|i| 100.0 + 0.1 * i as f64).collect());
+        let settings = SystematicSettings { quantity: 100.0, rate: 0.03, ..SystematicSettings::default() };
+        let result = backtester().run(&chart, &mut SystematicTrader::new(SystematicStrategy::CoveredCall, settings));
+        let opening = orders(&result.trades);
+        assert_eq!(opening[0].contract, Contract::underlying());
+        assert_eq!(opening[0].quantity, 100.0);
+
+        // The call is sold 30 days out at the target delta, valued at the configured vol while it warms up
+        let call = opening[1].contract;
+        assert_eq!((call.kind, opening[1].quantity), (LegKind::Call, -100.0));
+        assert_eq!(call.expiry, chart.data[0].date + Days::new(30));
+        let years = DayCount::Bus252.year_fraction(chart.data[0].date, call.expiry, &Calendar::WeekendsOnly);
+        let delta = black_scholes_greeks(OptionType::Call, 100.0, call.strike, 0.03, 0.03, 0.2, years).delta;
+        assert_abs_diff_eq!(delta, 0.3, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_profit_take_closes_early() {
+        // A steady fall makes a short call worthless well before expiry
+        let chart = chart((0..15).map(|i| 100.0 - 1.5 * i as f64).collect());
+        let exits = ExitRules { profit_take: Some(0.5), stop_loss: None };
+        let settings = SystematicSettings { exits, rate: 0.03, ..SystematicSettings::default() };
+        let result = backtester().run(&chart, &mut SystematicTrader::new(SystematicStrategy::CoveredCall, settings));
+        let trades = orders(&result.trades);
+        let close = trades.iter().position(|t| t.contract.kind == LegKind::Call && t.quantity > 0.0).unwrap();
+        assert!(trades[close].price < 0.6 * trades[1].price);
+        // The next bar opens a new call
+        assert_eq!(trades[close + 1].date, chart.data.iter().find(|dp| dp.date > trades[close].date).unwrap().date);
+        assert!(ExitRules { profit_take: None, stop_loss: Some(1.0) }.triggered(-2.0, -4.5));
+    }
+
+    #[test]
+    fn test_wheel_is_assigned() {
+        // Prices fall through the put strike by expiry, so the put is assigned and calls are sold next
+        let chart = chart((0..40).map(|i| 100.0 - 0.5 * i as f64).collect());
+        let settings = SystematicSettings { days_to_expiry: 14, ..SystematicSettings::default() };
+        let result = backtester().run(&chart, &mut SystematicTrader::new(SystematicStrategy::Wheel, settings));
+        let trades = orders(&result.trades);
+        assert_eq!(trades[0].contract.kind, LegKind::Put);
+        let assigned = trades.iter().position(|t| t.contract == Contract::underlying()).unwrap();
+        assert_eq!(trades[assigned].quantity, 1.0);
+        assert!(trades[assigned].date >= trades[0].contract.expiry);
+        assert_eq!((trades[assigned + 1].contract.kind, trades[assigned + 1].quantity), (LegKind::Call, -1.0));
+    }
+}
@@ -1,61 +1,95 @@
 use statrs::distribution::{Continuous, ContinuousCDF, Normal};
 use crate::model::monte_carlo::MonteCarloPricing;
 
-const DAYS_IN_YEAR: f64 = 365.0;
-
 const SIGMA_INITIAL_GUESS: f64 = 2.0;
 const TOLERANCE: f64 = 0.0001;
+const NEWTON_TOLERANCE: f64 = 1e-10;
+const NEWTON_MAX_ITERATIONS: usize = 100;
+const MIN_IMPLIED_VOL: f64 = 1e-4;
+const MAX_IMPLIED_VOL: f64 = 5.0;
 
 impl MonteCarloPricing {
     pub fn implied_volatility(&self) -> f64 {
-        let mut low: f64 = 0.01;
-        let mut high: f64 = SIGMA_INITIAL_GUESS; 
-        let mut mid: f64;
-    
-        while (high - low).abs() > TOLERANCE {
-            mid = (low + high) / 2.0;
-            let price = self.black_scholes_call_price(mid);
-    
-            if price > self.market_option_price {
-                high = mid;
-            } else {
-                low = mid;
-            }
-        }
-    
-        (low + high) / 2.0 // Return estimated implied volatility
-    
+        implied_vol_from_call_price(self.market_option_price, self.dividend_adjusted_spot(), self.strike_price, self.zero_rate(self.years_to_expire), self.years_to_expire)
     }
 
+    /// Spot with the discrete dividends escrowed and the continuous yield paid out until expiry, so the
+    /// plain Black-Scholes formulas price a dividend paying underlying.
+    pub fn dividend_adjusted_spot(&self) -> f64 {
+        self.spot_net_of_dividends(self.current_asset_price, self.years_to_expire)
+    }
 
-    pub fn black_scholes_call_price(&self, sigma: f64) -> f64 {
-        let d1 = ((self.strike_price / self.strike_price).ln() + (self.risk_free_rate + 0.5 * sigma.powi(2)) * self.years_to_expire) / (sigma * self.years_to_expire.sqrt());
-        let d2 = d1 - sigma * self.years_to_expire.sqrt();
-        let normal = Normal::standard();
-
-        let n_d1 = normal.cdf(d1); // CDF of standard normal distribution
-        let n_d2 = normal.cdf(d2);
+    /// `spot` net of the discrete dividends and the continuous yield paid out within `years`.
+    pub fn spot_net_of_dividends(&self, spot: f64, years: f64) -> f64 {
+        self.dividends.escrowed_spot(spot, |t| self.discount_factor(t), years) * (-self.dividend_yield * years).exp()
+    }
 
-        self.current_asset_price * n_d1 - self.strike_price * (-self.risk_free_rate * self.years_to_expire).exp() * n_d2
+    pub fn black_scholes_call_price(&self, sigma: f64) -> f64 {
+        black_scholes_call(self.dividend_adjusted_spot(), self.strike_price, self.zero_rate(self.years_to_expire), self.years_to_expire, sigma)
     }
 
     pub fn black_scholes_vega(&self, sigma: f64) -> f64 {
-        let d1 = (self.current_asset_price.ln() / self.strike_price.ln() + (self.risk_free_rate + 0.5 * sigma.powi(2)) * self.years_to_expire) 
-            / (sigma * self.years_to_expire.sqrt());
-        let normal = Normal::standard();
-        let n_prime_d1 = normal.pdf(d1); // Standard normal PDF for d1
+        black_scholes_call_vega(self.dividend_adjusted_spot(), self.strike_price, self.zero_rate(self.years_to_expire), self.years_to_expire, sigma)
+    }
+}
 
-        self.current_asset_price * n_prime_d1 * self.years_to_expire.sqrt()
+pub fn black_scholes_call(spot: f64, strike: f64, rate: f64, years: f64, sigma: f64) -> f64 {
+    let d1 = ((spot / strike).ln() + (rate + 0.5 * sigma.powi(2)) * years) / (sigma * years.sqrt());
+    let d2 = d1 - sigma * years.sqrt();
+    let normal = Normal::standard();
+
+    let n_d1 = normal.cdf(d1); // CDF of standard normal distribution
+    let n_d2 = normal.cdf(d2);
+
+    spot * n_d1 - strike * (-rate * years).exp() * n_d2
+}
+
+pub fn implied_vol_from_call_price(price: f64, spot: f64, strike: f64, rate: f64, years: f64) -> f64 {
+    let mut low: f64 = 0.01;
+    let mut high: f64 = SIGMA_INITIAL_GUESS; 
+    let mut mid: f64;
+
+    while (high - low).abs() > TOLERANCE {
+        mid = (low + high) / 2.0;
+        if black_scholes_call(spot, strike, rate, years, mid) > price {
+            high = mid;
+        } else {
+            low = mid;
+        }
     }
+
+    (low + high) / 2.0 // Return estimated implied volatility
 }
 
-pub fn days_to_years(days: u16) -> f64 {
-    days as f64 / DAYS_IN_YEAR
+pub fn black_scholes_call_vega(spot: f64, strike: f64, rate: f64, years: f64, sigma: f64) -> f64 {
+    let d1 = ((spot / strike).ln() + (rate + 0.5 * sigma.powi(2)) * years) / (sigma * years.sqrt());
+    spot * Normal::standard().pdf(d1) * years.sqrt()
+}
+
+/// Implied volatility of a call price by Newton's method started from `guess`, falling back to bisection
+/// whenever a step would leave the bracket of the root. Converges to `NEWTON_TOLERANCE`, so the result is
+/// smooth enough in the price to sit inside an optimiser's objective.
+pub fn implied_vol_newton(price: f64, spot: f64, strike: f64, rate: f64, years: f64, guess: f64) -> f64 {
+    let (mut low, mut high) = (MIN_IMPLIED_VOL, MAX_IMPLIED_VOL);
+    let mut sigma = guess.clamp(low, high);
+    for _ in 0..NEWTON_MAX_ITERATIONS {
+        let diff = black_scholes_call(spot, strike, rate, years, sigma) - price;
+        if diff > 0.0 { high = sigma } else { low = sigma }
+        let vega = black_scholes_call_vega(spot, strike, rate, years, sigma);
+        let newton = sigma - diff / vega;
+        let next = if vega > 0.0 && newton > low && newton < high { newton } else { 0.5 * (low + high) };
+        if (next - sigma).abs() < NEWTON_TOLERANCE {
+            return next;
+        }
+        sigma = next;
+    }
+    sigma
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use approx::assert_abs_diff_eq;
 
     #[test]
     fn test_black_scholes_call_price() {
@@ -68,6 +102,7 @@ mod tests {
             num_simulations: 1000,
             num_steps: 10,
             implied_vol: 0.25,
+            ..MonteCarloPricing::default()
         };
         
         let sigma = 0.2;
@@ -87,6 +122,7 @@ mod tests {
             num_simulations: 1000,
             num_steps: 10,
             implied_vol: 0.25,
+            ..MonteCarloPricing::default()
         };
         
         let iv = mc.implied_volatility();
@@ -105,6 +141,7 @@ mod tests {
             num_simulations: 1000,
             num_steps: 10,
             implied_vol: 0.25,
+            ..MonteCarloPricing::default()
         };
         
         let sigma = 0.2;
@@ -114,9 +151,12 @@ mod tests {
     }
     
     #[test]
-    fn test_days_to_years() {
-        assert_eq!(days_to_years(365), 1.0);
-        assert_eq!(days_to_years(730), 2.0);
-        assert_eq!(days_to_years(0), 0.0);
+    fn test_implied_vol_newton() {
+        for (strike, sigma) in [(80.0, 0.35), (100.0, 0.2), (130.0, 0.15)] {
+            let price = black_scholes_call(100.0, strike, 0.03, 0.5, sigma);
+            for guess in [0.05, 0.2, 3.0] {
+                assert_abs_diff_eq!(implied_vol_newton(price, 100.0, strike, 0.03, 0.5, guess), sigma, epsilon = 1e-9);
+            }
+        }
     }
 }
@@ -0,0 +1,272 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+
+
+const MIN_TENOR: f64 = 1e-6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CurveInterpolation {
+    #[default]
+    Linear,
+    MonotoneCubic, // Fritsch-Carlson, no overshoot between pillars
+}
+
+impl CurveInterpolation {
+    pub const ALL: [CurveInterpolation; 2] = [CurveInterpolation::Linear, CurveInterpolation::MonotoneCubic];
+}
+
+impl fmt::Display for CurveInterpolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CurveInterpolation::Linear => write!(f, "Linear"),
+            CurveInterpolation::MonotoneCubic => write!(f, "Monotone cubic"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InterpolatedQuantity {
+    #[default]
+    ZeroRate,
+    DiscountFactor, // interpolated in logs, so linear means piecewise flat forwards
+}
+
+impl InterpolatedQuantity {
+    pub const ALL: [InterpolatedQuantity; 2] = [InterpolatedQuantity::ZeroRate, InterpolatedQuantity::DiscountFactor];
+}
+
+impl fmt::Display for InterpolatedQuantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterpolatedQuantity::ZeroRate => write!(f, "Zero rates"),
+            InterpolatedQuantity::DiscountFactor => write!(f, "Discount factors"),
+        }
+    }
+}
+
+/// A zero curve with continuously compounded rates at its pillar tenors. Beyond the pillars the zero
+/// rate is extrapolated flat.
+#[derive(Debug, Clone, PartialEq)]
+pub struct YieldCurve {
+    pub tenors: Vec<f64>,
+    pub zero_rates: Vec<f64>,
+    pub interpolation: CurveInterpolation,
+    pub quantity: InterpolatedQuantity,
+    slopes: Vec<f64>, // Hermite tangents of the interpolated quantity, for the monotone cubic
+}
+
+impl YieldCurve {
+    /// Sorts the pillars by tenor. Errs unless every tenor is positive and appears only once.
+    pub fn new(tenors: Vec<f64>, zero_rates: Vec<f64>, interpolation: CurveInterpolation, quantity: InterpolatedQuantity) -> Result<YieldCurve, Box<dyn Error>> {
+        if tenors.is_empty() || tenors.len() != zero_rates.len() {
+            return Err("Yield curve needs one zero rate for every tenor".into());
+        }
+        if let Some(t) = tenors.iter().find(|t| **t <= 0.0) {
+            return Err(format!("Yield curve tenors must be positive, got {}", t).into());
+        }
+        let mut pillars: Vec<(f64, f64)> = tenors.into_iter().zip(zero_rates).collect();
+        pillars.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some(pair) = pillars.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(format!("Yield curve quotes the tenor {} more than once", pair[0].0).into());
+        }
+        let (tenors, zero_rates) = pillars.into_iter().unzip();
+        Ok(YieldCurve::from_pillars(tenors, zero_rates, interpolation, quantity))
+    }
+
+    /// Curve through pillars already sorted by distinct positive tenors.
+    fn from_pillars(tenors: Vec<f64>, zero_rates: Vec<f64>, interpolation: CurveInterpolation, quantity: InterpolatedQuantity) -> YieldCurve {
+        let mut curve = YieldCurve { tenors, zero_rates, interpolation, quantity, slopes: Vec::new() };
+        curve.slopes = fritsch_carlson_slopes(&curve.tenors, &curve.values());
+        curve
+    }
+
+    pub fn flat(rate: f64) -> YieldCurve {
+        YieldCurve::from_pillars(vec![1.0], vec![rate], CurveInterpolation::Linear, InterpolatedQuantity::ZeroRate)
+    }
+
+    pub fn with_interpolation(&self, interpolation: CurveInterpolation, quantity: InterpolatedQuantity) -> YieldCurve {
+        YieldCurve::from_pillars(self.tenors.clone(), self.zero_rates.clone(), interpolation, quantity)
+    }
+
+    /// The curve with every zero rate moved by `shift`.
+    pub fn shifted(&self, shift: f64) -> YieldCurve {
+        YieldCurve::from_pillars(self.tenors.clone(), self.zero_rates.iter().map(|r| r + shift).collect(), self.interpolation, self.quantity)
+    }
+
+    fn values(&self) -> Vec<f64> {
+        match self.quantity {
+            InterpolatedQuantity::ZeroRate => self.zero_rates.clone(),
+            InterpolatedQuantity::DiscountFactor => self.tenors.iter().zip(&self.zero_rates).map(|(t, r)| -r * t).collect(),
+        }
+    }
+
+    pub fn zero_rate(&self, t: f64) -> f64 {
+        let t = t.max(MIN_TENOR);
+        let n = self.tenors.len();
+        if n == 1 || t <= self.tenors[0] {
+            return self.zero_rates[0];
+        }
+        if t >= self.tenors[n - 1] {
+            return self.zero_rates[n - 1];
+        }
+
+        let values = self.values();
+        let i = self.tenors.partition_point(|v| *v <= t) - 1;
+        let (t0, t1) = (self.tenors[i], self.tenors[i + 1]);
+        let h = t1 - t0;
+        let s = (t - t0) / h;
+        let value = match self.interpolation {
+            CurveInterpolation::Linear => values[i] + s * (values[i + 1] - values[i]),
+            CurveInterpolation::MonotoneCubic => {
+                let h00 = 2.0 * s.powi(3) - 3.0 * s.powi(2) + 1.0;
+                let h10 = s.powi(3) - 2.0 * s.powi(2) + s;
+                let h01 = -2.0 * s.powi(3) + 3.0 * s.powi(2);
+                let h11 = s.powi(3) - s.powi(2);
+                h00 * values[i] + h10 * h * self.slopes[i] + h01 * values[i + 1] + h11 * h * self.slopes[i + 1]
+            }
+        };
+        match self.quantity {
+            InterpolatedQuantity::ZeroRate => value,
+            InterpolatedQuantity::DiscountFactor => -value / t,
+        }
+    }
+
+    pub fn discount_factor(&self, t: f64) -> f64 {
+        (-self.zero_rate(t) * t).exp()
+    }
+
+    /// Continuously compounded forward rate between `t0` and `t1`.
+    pub fn forward_rate(&self, t0: f64, t1: f64) -> f64 {
+        if t1 - t0 < MIN_TENOR {
+            return self.zero_rate(t1);
+        }
+        (self.zero_rate(t1) * t1 - self.zero_rate(t0) * t0) / (t1 - t0)
+    }
+
+    /// Reads pillars from CSV with the columns `instrument,days_to_maturity,rate`, the calendar days being
+    /// converted to years by `to_years`. Deposit rates are simple over that year fraction, treasury yields
+    /// are semi-annually compounded and zero rates continuously compounded.
+    pub fn load<F: Fn(u64) -> f64>(path: &str, to_years: F) -> Result<YieldCurve, Box<dyn Error>> {
+        YieldCurve::parse(&fs::read_to_string(path)?, to_years)
+    }
+
+    pub fn parse<F: Fn(u64) -> f64>(contents: &str, to_years: F) -> Result<YieldCurve, Box<dyn Error>> {
+        let mut tenors = Vec::new();
+        let mut zero_rates = Vec::new();
+        for line in contents.lines().map(|l| l.trim()).filter(|l| !l.is_empty() && !l.starts_with("instrument")) {
+            let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+            if fields.len() < 3 {
+                return Err(format!("Expected instrument, days to maturity and rate in '{}'", line).into());
+            }
+            let t = to_years(fields[1].parse()?);
+            if t <= 0.0 {
+                return Err(format!("Expected a maturity in the future in '{}'", line).into());
+            }
+            let rate: f64 = fields[2].parse()?;
+            let zero = match fields[0].to_lowercase().as_str() {
+                "deposit" => (1.0 + rate * t).ln() / t,
+                "treasury" => 2.0 * (1.0 + rate / 2.0).ln(),
+                "zero" => rate,
+                other => return Err(format!("Unknown instrument '{}'", other).into()),
+            };
+            tenors.push(t);
+            zero_rates.push(zero);
+        }
+        if tenors.is_empty() {
+            return Err("Yield curve contains no rates".into());
+        }
+        YieldCurve::new(tenors, zero_rates, CurveInterpolation::default(), InterpolatedQuantity::default())
+    }
+}
+
+fn fritsch_carlson_slopes(xs: &[f64], ys: &[f64]) -> Vec<f64> {
+    let n = xs.len();
+    if n < 2 {
+        return vec![0.0; n];
+    }
+    let secants: Vec<f64> = (0..n - 1).map(|i| (ys[i + 1] - ys[i]) / (xs[i + 1] - xs[i])).collect();
+    let mut slopes = vec![0.0; n];
+    slopes[0] = secants[0];
+    slopes[n - 1] = secants[n - 2];
+    for i in 1..n - 1 {
+        slopes[i] = if secants[i - 1] * secants[i] <= 0.0 { 0.0 } else { 0.5 * (secants[i - 1] + secants[i]) };
+    }
+    for i in 0..n - 1 {
+        if secants[i] == 0.0 {
+            slopes[i] = 0.0;
+            slopes[i + 1] = 0.0;
+            continue;
+        }
+        let alpha = slopes[i] / secants[i];
+        let beta = slopes[i + 1] / secants[i];
+        let norm = alpha.hypot(beta);
+        if norm > 3.0 {
+            slopes[i] = 3.0 / norm * alpha * secants[i];
+            slopes[i + 1] = 3.0 / norm * beta * secants[i];
+        }
+    }
+    slopes
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::calendar::DayCount;
+    use crate::model::params::MonteCarloParams;
+    use approx::assert_abs_diff_eq;
+
+    fn sample_curve(interpolation: CurveInterpolation, quantity: InterpolatedQuantity) -> YieldCurve {
+        YieldCurve::new(vec![0.25, 1.0, 2.0, 5.0], vec![0.02, 0.03, 0.035, 0.04], interpolation, quantity).unwrap()
+    }
+
+    #[test]
+    fn test_interpolation_hits_pillars_and_extrapolates_flat() {
+        for interpolation in CurveInterpolation::ALL {
+            for quantity in InterpolatedQuantity::ALL {
+                let curve = sample_curve(interpolation, quantity);
+                assert_abs_diff_eq!(curve.zero_rate(1.0), 0.03, epsilon = 1e-12);
+                assert_abs_diff_eq!(curve.zero_rate(2.0), 0.035, epsilon = 1e-12);
+                assert_abs_diff_eq!(curve.zero_rate(0.1), 0.02, epsilon = 1e-12);
+                assert_abs_diff_eq!(curve.zero_rate(10.0), 0.04, epsilon = 1e-12);
+            }
+        }
+        let linear = sample_curve(CurveInterpolation::Linear, InterpolatedQuantity::ZeroRate);
+        assert_abs_diff_eq!(linear.zero_rate(1.5), 0.0325, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_monotone_cubic_does_not_overshoot() {
+        let curve = sample_curve(CurveInterpolation::MonotoneCubic, InterpolatedQuantity::ZeroRate);
+        let mut previous = curve.zero_rate(0.25);
+        for i in 1..=475 {
+            let rate = curve.zero_rate(0.25 + i as f64 * 0.01);
+            assert!(rate >= previous - 1e-12);
+            assert!(rate <= 0.04 + 1e-12);
+            previous = rate;
+        }
+    }
+
+    #[test]
+    fn test_forward_rates_reproduce_discount_factors() {
+        let curve = sample_curve(CurveInterpolation::Linear, InterpolatedQuantity::DiscountFactor);
+        let integrated: f64 = (0..10).map(|i| curve.forward_rate(i as f64 * 0.3, (i + 1) as f64 * 0.3) * 0.3).sum();
+        assert_abs_diff_eq!((-integrated).exp(), curve.discount_factor(3.0), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_parse() {
+        let curve = YieldCurve::parse("instrument,days_to_maturity,rate\ndeposit,365,0.05\ntreasury,730,0.04\nzero,1825,0.045\n", |days| days as f64 / 365.0).unwrap();
+        assert_abs_diff_eq!(curve.zero_rate(1.0), 1.05f64.ln(), epsilon = 1e-12);
+        assert_abs_diff_eq!(curve.zero_rate(2.0), 2.0 * 1.02f64.ln(), epsilon = 1e-12);
+        assert!(YieldCurve::parse("swap,365,0.05", |days| days as f64 / 365.0).is_err());
+        assert!(YieldCurve::parse("deposit,0,0.05", |days| days as f64 / 365.0).is_err());
+        assert!(YieldCurve::parse("zero,365,0.05\nzero,365,0.06", |days| days as f64 / 365.0).is_err());
+
+        // Tenors follow the configured day count
+        let params = MonteCarloParams { day_count: DayCount::Act360, ..MonteCarloParams::default() };
+        let curve = YieldCurve::parse("zero,90,0.04", |days| params.year_fraction_for_days(days)).unwrap();
+        assert_abs_diff_eq!(curve.tenors[0], 0.25, epsilon = 1e-12);
+    }
+}